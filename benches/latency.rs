@@ -73,7 +73,7 @@ fn p99_concurrent_insert<T: Sync>(name: &str, map: T, insert: impl Fn(&T, usize)
                     let i = (t + 1) * i;
 
                     let now = std::time::Instant::now();
-                    insert(&map, i);
+                    insert(map, i);
                     let elapsed = now.elapsed();
 
                     if max.map(|max| elapsed > max).unwrap_or(true) {
@@ -1,32 +1,51 @@
 use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
 
 const SIZE: usize = 10_000;
 
-fn compare(c: &mut Criterion) {
-    let mut group = c.benchmark_group("read");
+#[derive(Clone, Copy)]
+struct RandomKeys {
+    state: usize,
+}
 
-    #[derive(Clone, Copy)]
-    struct RandomKeys {
-        state: usize,
+impl RandomKeys {
+    fn new() -> Self {
+        RandomKeys { state: 0 }
     }
+}
 
-    impl RandomKeys {
-        fn new() -> Self {
-            RandomKeys { state: 0 }
-        }
+impl Iterator for RandomKeys {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        // Add 1 then multiply by some 32 bit prime.
+        self.state = self.state.wrapping_add(1).wrapping_mul(3_787_392_781);
+        Some(self.state)
     }
+}
 
-    impl Iterator for RandomKeys {
-        type Item = usize;
-        fn next(&mut self) -> Option<usize> {
-            // Add 1 then multiply by some 32 bit prime.
-            self.state = self.state.wrapping_add(1).wrapping_mul(3_787_392_781);
-            Some(self.state)
-        }
+// A hasher that returns integer keys unchanged, exposing the distribution of the keys.
+#[derive(Default)]
+struct IdentityHasher(u64);
+
+impl Hasher for IdentityHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, _: &[u8]) {
+        unimplemented!()
     }
 
+    fn write_usize(&mut self, i: usize) {
+        self.0 = i as u64;
+    }
+}
+
+fn compare(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read");
+
     group.bench_function("papaya", |b| {
         let m = papaya::HashMap::<usize, usize>::builder()
             .collector(seize::Collector::new().epoch_frequency(None))
@@ -38,7 +57,7 @@ fn compare(c: &mut Criterion) {
 
         b.iter(|| {
             for i in RandomKeys::new().take(SIZE) {
-                black_box(assert_eq!(m.pin().get(&i), Some(&i)));
+                assert_eq!(black_box(m.pin().get(&i)), Some(&i));
             }
         });
     });
@@ -51,7 +70,7 @@ fn compare(c: &mut Criterion) {
 
         b.iter(|| {
             for i in RandomKeys::new().take(SIZE) {
-                black_box(assert_eq!(m.get(&i), Some(&i)));
+                assert_eq!(black_box(m.get(&i)), Some(&i));
             }
         });
     });
@@ -64,7 +83,7 @@ fn compare(c: &mut Criterion) {
 
         b.iter(|| {
             for i in RandomKeys::new().take(SIZE) {
-                black_box(assert_eq!(*m.get(&i).unwrap(), i));
+                assert_eq!(*black_box(m.get(&i)).unwrap(), i);
             }
         });
     });
@@ -72,5 +91,174 @@ fn compare(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, compare);
+fn churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("churn");
+
+    for entry_pool in [false, true] {
+        let name = if entry_pool { "papaya_pool" } else { "papaya" };
+
+        group.bench_function(name, |b| {
+            let m = papaya::HashMap::<usize, usize>::builder()
+                .collector(seize::Collector::new().epoch_frequency(None))
+                .entry_pool(entry_pool)
+                .build();
+
+            for i in RandomKeys::new().take(SIZE) {
+                m.pin().insert(i, i);
+            }
+
+            b.iter(|| {
+                for i in RandomKeys::new().take(SIZE) {
+                    assert_eq!(black_box(m.pin().remove(&i)), Some(&i));
+                    assert_eq!(black_box(m.pin().insert(i, i)), None);
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn probe(c: &mut Criterion) {
+    let mut group = c.benchmark_group("probe");
+
+    let distributions = [
+        ("random", RandomKeys::new().take(SIZE).collect::<Vec<_>>()),
+        ("sequential", (0..SIZE).collect()),
+        ("strided", (0..SIZE).map(|i| i * 64).collect()),
+    ];
+
+    for (distribution, keys) in &distributions {
+        for strategy in [
+            papaya::ProbeStrategy::Quadratic,
+            papaya::ProbeStrategy::Linear,
+        ] {
+            let name = format!("{distribution}/{strategy:?}");
+
+            group.bench_function(name, |b| {
+                let m = papaya::HashMap::<usize, usize, _>::builder()
+                    .hasher(BuildHasherDefault::<IdentityHasher>::default())
+                    .collector(seize::Collector::new().epoch_frequency(None))
+                    .probe_strategy(strategy)
+                    .build();
+
+                for &i in keys {
+                    m.pin().insert(i, i);
+                }
+
+                b.iter(|| {
+                    for i in keys {
+                        assert_eq!(black_box(m.pin().get(i)), Some(i));
+                    }
+                });
+            });
+        }
+    }
+
+    group.finish();
+}
+
+fn iter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iter");
+
+    // Returns a map with an incremental resize that has only just started.
+    let resizing = || {
+        let m = papaya::HashMap::<usize, usize>::builder()
+            .collector(seize::Collector::new().epoch_frequency(None))
+            .resize_mode(papaya::ResizeMode::Incremental(1))
+            .build();
+
+        let mut keys = RandomKeys::new();
+        for i in keys.by_ref().take(SIZE) {
+            m.pin().insert(i, i);
+        }
+
+        // Insert until the next resize is triggered.
+        let (grows, _) = m.resize_count();
+        while m.resize_count().0 == grows {
+            let i = keys.next().unwrap();
+            m.pin().insert(i, i);
+        }
+
+        m
+    };
+
+    group.bench_function("settled", |b| {
+        b.iter_batched(
+            || {
+                let m = resizing();
+                m.pin().finalize_resize();
+                m
+            },
+            |m| {
+                assert!(black_box(m.pin().iter().count()) > SIZE);
+                m
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    // Iterating over a map during a resize first completes the resize.
+    group.bench_function("resizing", |b| {
+        b.iter_batched(
+            resizing,
+            |m| {
+                assert!(black_box(m.pin().iter().count()) > SIZE);
+                m
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.finish();
+}
+
+fn compact(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compact");
+
+    // Returns a map that has seen heavy churn at a steady size, leaving tombstones
+    // behind, along with the keys that are currently present.
+    let churned = || {
+        let m = papaya::HashMap::<usize, usize>::builder()
+            .collector(seize::Collector::new().epoch_frequency(None))
+            .build();
+
+        let mut keys = RandomKeys::new().take(SIZE).collect::<Vec<_>>();
+        for &i in &keys {
+            m.pin().insert(i, i);
+        }
+
+        let fresh = RandomKeys::new().skip(SIZE);
+        for (key, new) in keys.iter_mut().take(SIZE / 2).zip(fresh) {
+            assert!(m.pin().remove(key).is_some());
+            m.pin().insert(new, new);
+            *key = new;
+        }
+
+        (m, keys)
+    };
+
+    for compacted in [false, true] {
+        let name = if compacted { "compacted" } else { "churned" };
+
+        group.bench_function(name, |b| {
+            let (m, keys) = churned();
+            if compacted {
+                m.pin().compact();
+            }
+
+            // Look up both present and absent keys, as misses must probe past any tombstones.
+            b.iter(|| {
+                for &i in &keys {
+                    assert_eq!(black_box(m.pin().get(&i)), Some(&i));
+                    assert_eq!(black_box(m.pin().get(&!i)), None);
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, compare, churn, probe, iter, compact);
 criterion_main!(benches);
@@ -0,0 +1,76 @@
+use std::hash::{BuildHasher, Hash};
+
+use crate::{Equivalent, Guard, HashMapRef};
+
+/// Returns the value for a key from the first of the given maps that contains it.
+///
+/// The maps are checked in order, which is useful for tiered caches, where the first map
+/// is the smallest and fastest tier. If `promote` is `true` and the key is found in a later
+/// tier, the value is inserted into every earlier tier, so that future lookups are served by
+/// the first tier.
+///
+/// Every map has its own collector, so the maps are accessed through pinned references,
+/// each holding a guard for its map.
+///
+/// Promotion into each tier is atomic, but promotion across tiers is not. If the key is
+/// concurrently inserted into an earlier tier, for example by another thread promoting the
+/// same key, the existing value is retained rather than overwritten, and it is the existing
+/// value that is promoted into the remaining earlier tiers. The returned value is the value
+/// in the first tier after promotion, which may be the concurrently inserted value.
+///
+/// The key may be any borrowed form of the map's key type, but
+/// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+/// the key type.
+///
+/// # Examples
+///
+/// ```
+/// use papaya::HashMap;
+///
+/// let l1 = HashMap::new();
+/// let l2 = HashMap::new();
+/// l2.pin().insert("a", 1);
+///
+/// let (l1, l2) = (l1.pin(), l2.pin());
+/// assert_eq!(papaya::get_tiered(&[&l1, &l2], "a", true), Some(&1));
+/// assert_eq!(papaya::get_tiered(&[&l1, &l2], "b", true), None);
+///
+/// // The value was promoted to the first tier.
+/// assert_eq!(l1.get("a"), Some(&1));
+/// ```
+pub fn get_tiered<'a, K, V, S, G, Q>(
+    tiers: &[&'a HashMapRef<'_, K, V, S, G>],
+    key: &Q,
+    promote: bool,
+) -> Option<&'a V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    S: BuildHasher,
+    G: Guard,
+    Q: Equivalent<K> + Hash + ?Sized,
+{
+    for (i, tier) in tiers.iter().enumerate() {
+        let Some((found, value)) = tier.get_key_value(key) else {
+            continue;
+        };
+
+        if !promote {
+            return Some(value);
+        }
+
+        // Promote the value into the earlier tiers, starting with the closest.
+        let mut current = value;
+        for earlier in tiers[..i].iter().rev() {
+            current = match earlier.try_insert(found.clone(), current.clone()) {
+                Ok(value) => value,
+                // The key was concurrently inserted into this tier.
+                Err(err) => err.current,
+            };
+        }
+
+        return Some(current);
+    }
+
+    None
+}
@@ -0,0 +1,99 @@
+use std::hash::{BuildHasher, Hash};
+
+use seize::{Guard, LocalGuard};
+
+use crate::HashMap;
+
+/// A common interface for concurrent maps with guarded access.
+///
+/// This trait allows code to be generic over the map implementation, for example to
+/// substitute a mock map in tests. Like the methods on [`HashMap`], every operation takes
+/// a guard, and returned references are tied to the lifetime of that guard. Guards should
+/// be created with [`ConcurrentMap::guard`].
+///
+/// # Examples
+///
+/// ```
+/// use papaya::{ConcurrentMap, HashMap};
+///
+/// fn increment<M: ConcurrentMap<&'static str, u64>>(map: &M, key: &'static str) {
+///     let guard = map.guard();
+///     let current = map.get(&key, &guard).copied().unwrap_or(0);
+///     map.insert(key, current + 1, &guard);
+/// }
+///
+/// let map = HashMap::new();
+/// increment(&map, "a");
+/// increment(&map, "a");
+/// assert_eq!(map.pin().get("a"), Some(&2));
+/// ```
+pub trait ConcurrentMap<K, V> {
+    /// Returns a guard for use with this map.
+    ///
+    /// See [`HashMap::guard`] for details.
+    fn guard(&self) -> LocalGuard<'_>;
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// See [`HashMap::get`] for details.
+    fn get<'g>(&self, key: &K, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        K: 'g,
+        V: 'g;
+
+    /// Inserts a key-value pair into the map, returning a reference to the previous
+    /// value if one was present.
+    ///
+    /// See [`HashMap::insert`] for details.
+    fn insert<'g>(&self, key: K, value: V, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        K: 'g,
+        V: 'g;
+
+    /// Removes a key from the map, returning a reference to the value if the key
+    /// was previously in the map.
+    ///
+    /// See [`HashMap::remove`] for details.
+    fn remove<'g>(&self, key: &K, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        K: 'g,
+        V: 'g;
+}
+
+impl<K, V, S> ConcurrentMap<K, V> for HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    #[inline]
+    fn guard(&self) -> LocalGuard<'_> {
+        HashMap::guard(self)
+    }
+
+    #[inline]
+    fn get<'g>(&self, key: &K, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        K: 'g,
+        V: 'g,
+    {
+        HashMap::get(self, key, guard)
+    }
+
+    #[inline]
+    fn insert<'g>(&self, key: K, value: V, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        K: 'g,
+        V: 'g,
+    {
+        HashMap::insert(self, key, value, guard)
+    }
+
+    #[inline]
+    fn remove<'g>(&self, key: &K, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        K: 'g,
+        V: 'g,
+    {
+        HashMap::remove(self, key, guard)
+    }
+}
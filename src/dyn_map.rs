@@ -0,0 +1,159 @@
+use std::any::{Any, TypeId};
+use std::hash::{BuildHasher, Hash};
+
+use crate::HashMap;
+
+/// An object-safe interface to a [`HashMap`] with an erased value type.
+///
+/// `HashMap<K, V, S>` is generic over its value type, so maps with different value types
+/// cannot be stored in the same collection. This trait erases the value type behind [`Any`],
+/// allowing heterogeneous maps with a common key type to be stored as `Box<dyn DynMap<K>>`,
+/// such as in a registry of plugins.
+///
+/// Values are passed in and out of the map as `dyn Any`, and are type-checked at runtime.
+/// As guards cannot be passed through a trait object, every method pins the map internally,
+/// and values can only be borrowed for the duration of a closure. For full access to the map,
+/// the trait object can be downcast to the underlying `HashMap` with
+/// [`downcast_ref`](trait.DynMap.html#method.downcast_ref).
+///
+/// # Examples
+///
+/// ```
+/// use papaya::{DynMap, HashMap};
+/// use std::collections::hash_map::RandomState;
+///
+/// let mut registry: Vec<Box<dyn DynMap<&str>>> = Vec::new();
+/// registry.push(Box::new(HashMap::<&str, i32>::new()));
+/// registry.push(Box::new(HashMap::<&str, String>::new()));
+///
+/// assert!(registry[0].insert("a", Box::new(1)).is_ok());
+/// assert!(registry[1].insert("a", Box::new(String::from("one"))).is_ok());
+///
+/// // Values of the wrong type are rejected.
+/// assert!(registry[0].insert("b", Box::new("two")).is_err());
+/// assert!(!registry[0].contains_key(&"b"));
+///
+/// assert_eq!(registry[0].get(&"a", |value: &i32| *value), Some(1));
+/// assert_eq!(registry[1].get(&"a", |value: &String| value.len()), Some(3));
+///
+/// let map = registry[1].downcast_ref::<String, RandomState>().unwrap();
+/// assert_eq!(map.pin().get("a").unwrap(), "one");
+/// ```
+pub trait DynMap<K> {
+    /// Returns the [`TypeId`] of the values stored in the map.
+    fn value_type_id(&self) -> TypeId;
+
+    /// Returns the number of entries in the map.
+    ///
+    /// See [`HashMap::len`] for details.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the map is empty. Otherwise returns `false`.
+    ///
+    /// See [`HashMap::is_empty`] for details.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// See [`HashMap::contains_key`] for details.
+    fn contains_key(&self, key: &K) -> bool;
+
+    /// Calls a closure with a reference to the value corresponding to the key.
+    ///
+    /// Returns `false` without calling the closure if the key is not present in the map.
+    /// The value can be downcast to its concrete type with `<dyn Any>::downcast_ref`, or
+    /// the typed [`get`](trait.DynMap.html#method.get) helper can be used instead.
+    fn get_with(&self, key: &K, f: &mut dyn FnMut(&dyn Any)) -> bool;
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// Returns `true` if a previous value was replaced. If the value is not of the map's
+    /// value type, it is returned as an error and the map is left unchanged.
+    ///
+    /// See [`HashMap::insert`] for details.
+    fn insert(&self, key: K, value: Box<dyn Any>) -> Result<bool, Box<dyn Any>>;
+
+    /// Removes a key from the map, returning `true` if the key was previously in the map.
+    ///
+    /// See [`HashMap::remove`] for details.
+    fn remove(&self, key: &K) -> bool;
+
+    /// Clears the map, removing all key-value pairs.
+    ///
+    /// See [`HashMap::clear`] for details.
+    fn clear(&self);
+
+    /// Returns the map as [`Any`], allowing it to be downcast to the underlying `HashMap`.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<K, V, S> DynMap<K> for HashMap<K, V, S>
+where
+    K: Hash + Eq + 'static,
+    V: 'static,
+    S: BuildHasher + 'static,
+{
+    fn value_type_id(&self) -> TypeId {
+        TypeId::of::<V>()
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.pin().contains_key(key)
+    }
+
+    fn get_with(&self, key: &K, f: &mut dyn FnMut(&dyn Any)) -> bool {
+        match self.pin().get(key) {
+            Some(value) => {
+                f(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn insert(&self, key: K, value: Box<dyn Any>) -> Result<bool, Box<dyn Any>> {
+        let value = value.downcast::<V>()?;
+        Ok(self.pin().insert(key, *value).is_some())
+    }
+
+    fn remove(&self, key: &K) -> bool {
+        self.pin().remove(key).is_some()
+    }
+
+    fn clear(&self) {
+        self.pin().clear()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl<K: 'static> dyn DynMap<K> + '_ {
+    /// Calls a closure with a reference to the value corresponding to the key, if it
+    /// is of type `V`.
+    ///
+    /// Returns `None` if the key is not present in the map, or the map does not store
+    /// values of type `V`.
+    pub fn get<V: 'static, R>(&self, key: &K, f: impl FnOnce(&V) -> R) -> Option<R> {
+        let mut f = Some(f);
+        let mut result = None;
+        self.get_with(key, &mut |value| {
+            if let Some(value) = value.downcast_ref::<V>() {
+                result = f.take().map(|f| f(value));
+            }
+        });
+        result
+    }
+
+    /// Returns a reference to the underlying `HashMap` if it has the given value and hasher types.
+    pub fn downcast_ref<V: 'static, S: 'static>(&self) -> Option<&HashMap<K, V, S>> {
+        self.as_any().downcast_ref()
+    }
+}
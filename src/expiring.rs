@@ -0,0 +1,62 @@
+use std::time::{Duration, Instant};
+
+/// A value with an optional expiration time.
+///
+/// Storing `Expiring<V>` values in a [`HashMap`](crate::HashMap) enables per-entry expiration,
+/// through methods such as [`insert_with_ttl`](crate::HashMap::insert_with_ttl) and
+/// [`get_unexpired`](crate::HashMap::get_unexpired). Expired entries are treated as absent
+/// and removed lazily when they are accessed, or proactively with
+/// [`purge_expired`](crate::HashMap::purge_expired).
+///
+/// Expiration is measured with [`Instant`], so it is monotonic and not affected by changes
+/// to the system clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Expiring<V> {
+    value: V,
+    expires_at: Option<Instant>,
+}
+
+impl<V> Expiring<V> {
+    /// Creates a value that expires after the given duration.
+    ///
+    /// If the expiration time cannot be represented, the value never expires.
+    pub fn new(value: V, ttl: Duration) -> Expiring<V> {
+        Expiring {
+            value,
+            expires_at: Instant::now().checked_add(ttl),
+        }
+    }
+
+    /// Creates a value that never expires.
+    pub fn forever(value: V) -> Expiring<V> {
+        Expiring {
+            value,
+            expires_at: None,
+        }
+    }
+
+    /// Returns a reference to the value, regardless of whether it has expired.
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    /// Consumes the wrapper, returning the value.
+    pub fn into_value(self) -> V {
+        self.value
+    }
+
+    /// Returns the time at which the value expires, or `None` if it never expires.
+    pub fn expires_at(&self) -> Option<Instant> {
+        self.expires_at
+    }
+
+    /// Returns `true` if the value has expired.
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(Instant::now())
+    }
+
+    /// Returns `true` if the value has expired as of the given time.
+    pub fn is_expired_at(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
@@ -78,8 +78,19 @@
 //!
 //! Due to the concurrent nature of the map, read and write operations may overlap in time. There is no support for locking the entire table nor individual keys to prevent concurrent access, except through external fine-grained locking. As such, read operations (such as `get`) reflect the results of the *most-recent* write. More formally, a read establishes a *happens-before* relationship with the corresponding write.
 //!
+//! Concretely, every operation that writes an entry does so with *release* semantics, and every operation that reads an entry does so with *acquire* semantics. This has two consequences:
+//!
+//! - If a read observes a value written by another thread, everything that happened-before the write in the writing thread also happens-before the rest of the reading thread. The map can be used to publish data: a thread that observes a key through `get` is guaranteed to observe any memory writes made by the inserting thread before the insert, including writes to unrelated memory.
+//! - Operations on a single key are linearizable. If a write completes and then happens-before a read through other means of synchronization, such as sending a message over a channel or joining a thread, the read is guaranteed to observe that write or a later write to the same key. This holds regardless of which guards are used and of any concurrent resizes.
+//!
+//! Note that these guarantees apply to individual keys. Writes to different keys are not ordered with respect to each other unless they are synchronized externally or through the map itself, as described above.
+//!
 //! Aggregate operations, such as iterators, rely on a weak snapshot of the table and return results reflecting the state of the table at or some point after the creation of the iterator. This means that they may, but are not guaranteed to, reflect concurrent modifications to the table that occur during iteration. Similarly, operations such as `clear` and `clone` rely on iteration and may not produce "perfect" results if the map is being concurrently modified.
 //!
+//! Iterators are tied to the table generation that was current when they were created. If the map is resized during iteration, [`HashMap::iter`] continues to walk the old table, yielding each entry at most once, as of its migration. Entries that were not yet migrated may still reflect concurrent writes, so this is not a point-in-time snapshot, and writes made to an entry after its migration are missed. [`HashMap::iter_live`] instead reads migrated entries from the current table, observing later updates and removals of the keys it visits.
+//!
+//! Reads of multiple keys are similarly not atomic with respect to each other. Two separate calls to `get` may observe one key before and another after a concurrent write, and there is no way to atomically read or write several keys at once. [`HashMap::get_consistent`] performs a validated read of multiple keys, retrying until it observes the same entries twice in a row, which guarantees that the values returned were present in the map at the same point in time. If related values must always be updated together, they should instead be stored in a single entry.
+//!
 //! Note that to obtain a stable snapshot of the table, aggregate table operations require completing any in-progress resizes. If you rely heavily on iteration or similar operations you should consider configuring [`ResizeMode::Blocking`].
 //!
 //! # Atomic Operations
@@ -94,6 +105,10 @@
 //!
 //! Note that in the event that the entry is concurrently modified during an `update`, the closure may be called multiple times to retry the operation. For this reason, update operations are intended to be quick and *pure*, as they may be retried or internally memoized.
 //!
+//! Closures passed to atomic operations are called without holding any locks, so they may safely call back into the same map, for example to read or modify other keys. This can never deadlock. Reading the *same* key from within the closure is also safe, but modifying it invalidates the entry the closure was called with, causing the operation to be retried. A closure that unconditionally modifies its own key will therefore retry indefinitely, and should be avoided.
+//!
+//! If a closure panics, the operation is abandoned before anything is written to the map, so the existing entry is left unchanged. The map is never poisoned, and remains fully usable after the panic.
+//!
 //! `papaya` also exposes more powerful atomic operations that serve as a replacement for the [standard entry API](std::collections::hash_map::Entry). These include:
 //!
 //! - [`HashMap::update`]
@@ -101,6 +116,7 @@
 //! - [`HashMap::update_or_insert_with`]
 //! - [`HashMap::get_or_insert`]
 //! - [`HashMap::get_or_insert_with`]
+//! - [`HashMap::try_insert`]
 //! - [`HashMap::compute`]
 //!
 //! For example, with a standard `HashMap`, `Entry::and_modify` is often paired with `Entry::or_insert`:
@@ -167,6 +183,27 @@
 //! }
 //! ```
 //!
+//! Owned guards are also slightly more expensive to use than local guards. If you need to perform a hot synchronous section of work while holding an owned reference, you can temporarily switch to a thread-local guard with [`HashMapRef::as_local`]:
+//!
+//! ```rust
+//! # use std::sync::Arc;
+//! use papaya::HashMap;
+//!
+//! async fn run(map: Arc<HashMap<i32, String>>) {
+//!     tokio::spawn(async move {
+//!         let map = map.pin_owned();
+//!
+//!         // Perform synchronous work with a local guard.
+//!         let total: usize = {
+//!             let map = map.as_local();
+//!             (0..1000).filter_map(|i| map.get(&i)).map(|value| value.len()).sum()
+//!         };
+//!
+//!         tokio::fs::write("db.txt", format!("{total}")).await;
+//!     });
+//! }
+//! ```
+//!
 //! # Advanced Lifetimes
 //!
 //! You may run into issues when you try to return a reference to a map contained within an outer type. For example:
@@ -206,12 +243,58 @@
 //!
 //! The `Guard` trait supports both local and owned guards. Note the `'guard` lifetime that ties the guard to the returned reference. No wrapper types or guard mapping is necessary.
 //!
+//! The concrete guard and pin types are also exported, so they can be named in signatures without depending on `seize` directly. [`HashMap::guard`] returns a [`LocalGuard`], and [`HashMap::owned_guard`] returns an [`OwnedGuard`]. [`HashMap::pin`] returns a [`HashMapPin`], and [`HashMap::pin_owned`] returns a [`HashMapPinOwned`], both of which are aliases for [`HashMapRef`] with the corresponding guard type. The same applies to [`HashSet`].
+//!
+//! ```rust
+//! use papaya::{HashMap, HashMapPin, LocalGuard};
+//!
+//! fn sum(map: &HashMap<u64, u64>, guard: &LocalGuard<'_>) -> u64 {
+//!     map.iter(guard).map(|(_, value)| value).sum()
+//! }
+//!
+//! fn increment(map: &HashMapPin<'_, u64, u64>, key: u64) {
+//!     map.update_or_insert(key, |value| value + 1, 1);
+//! }
+//!
+//! let map = HashMap::new();
+//! increment(&map.pin(), 1);
+//! increment(&map.pin(), 1);
+//! assert_eq!(sum(&map, &map.guard()), 2);
+//! ```
+//!
+//! # Exclusive Access
+//!
+//! `papaya` never blocks writers across the entire map, as doing so would require every operation to synchronize with a lock. Instead, exclusive access is provided statically through `&mut HashMap`. As guards and pinned references borrow the map, holding a mutable reference guarantees that there are no concurrent readers or writers, so no waiting for readers to leave the collector is required. Operations such as [`HashMap::get_mut`], [`HashMap::retain`], and [`HashMap::rehash_with`] take advantage of this to modify the map without any synchronization.
+//!
+//! For maintenance tasks that need a consistent view of multiple entries, such as re-keying every entry, the map can be wrapped in a [`RwLock`](std::sync::RwLock). Regular operations acquire a shared lock and run concurrently as usual, while the maintenance task acquires the exclusive lock. Note that this adds the cost of the shared lock to every operation, so it is only worth it if such tasks are required.
+//!
+//! ```rust
+//! use papaya::HashMap;
+//! use std::sync::RwLock;
+//!
+//! let map = RwLock::new(HashMap::new());
+//! map.read().unwrap().pin().insert(1, "a");
+//! map.read().unwrap().pin().insert(2, "b");
+//!
+//! // Re-key every entry while no other threads can access the map.
+//! {
+//!     let mut map = map.write().unwrap();
+//!     for (key, value) in std::mem::take(&mut *map) {
+//!         map.pin().insert(key * 10, value);
+//!     }
+//! }
+//!
+//! assert_eq!(map.read().unwrap().pin().get(&10), Some(&"a"));
+//! ```
+//!
 //! # Performance
 //!
 //! `papaya` is built with read-heavy workloads in mind. As such, read operations are extremely high throughput and provide consistent performance that scales with concurrency, meaning `papaya` will excel in workloads where reads are more common than writes. In write heavy workloads, `papaya` will still provide competitive performance despite not being it's primary use case. See the [benchmarks] for details.
 //!
 //! `papaya` aims to provide predictable and consistent latency across all operations. Most operations are lock-free, and those that aren't only block under rare and constrained conditions. `papaya` also features [incremental resizing](ResizeMode). Predictable latency is an important part of performance that doesn't often show up in benchmarks, but has significant implications for real-world usage.
 //!
+//! Every entry in the map is stored in a separate allocation holding both the key and the value, and the table itself only stores a metadata byte and a pointer per slot. A successful lookup therefore reads the metadata, followed by a single pointer to the entry, which is needed to compare the key anyway. The value lives next to the key, so reading it does not require chasing a second pointer. Values are intentionally never stored inline in the table, even small `Copy` values. Updates are performed by atomically swapping the entry pointer, which is what allows references to values to remain valid and immutable for as long as the guard is held. Storing values inline would require mutating them in place, which is incompatible with handing out references. Workloads that frequently replace entries can reduce allocator pressure with [`HashMapBuilder::entry_pool`].
+//!
 //! [benchmarks]: https://github.com/ibraheemdev/papaya/blob/master/BENCHMARKS.md
 
 #![deny(
@@ -225,17 +308,38 @@
 // Stylistic preferences.
 #![allow(clippy::multiple_bound_locations, clippy::single_match)]
 
+mod combinators;
+mod concurrent_map;
+mod dyn_map;
+mod expiring;
 mod map;
+mod multi_map;
+mod persist;
+mod projection;
 mod raw;
 mod set;
 
+#[cfg(feature = "async")]
+mod pending;
 #[cfg(feature = "serde")]
 mod serde_impls;
 
+pub use combinators::get_tiered;
+pub use concurrent_map::ConcurrentMap;
+pub use dyn_map::DynMap;
 pub use equivalent::Equivalent;
+pub use expiring::Expiring;
 pub use map::{
-    Compute, HashMap, HashMapBuilder, HashMapRef, Iter, Keys, OccupiedError, Operation, ResizeMode,
-    Values,
+    CapacityError, CollisionReport, Compute, Entry, FrozenHashMap, GetResult, HashMap,
+    HashMapBuilder, HashMapPin, HashMapPinOwned, HashMapRef, IntoIter, IntoKeys, IntoValues, Iter,
+    IterEnumerate, IterRemovable, Keys, LiveIter, OccupiedEntry, OccupiedError, Operation,
+    ProbeStrategy, ReadOnlyView, RemovalHandle, ResizeMode, SeededState, Snapshot, Values,
+    WouldResize,
 };
+pub use multi_map::{GetAll, MultiMap};
+pub use projection::{ByProjection, KeyProjection, Projected};
 pub use seize::{Guard, LocalGuard, OwnedGuard};
-pub use set::{HashSet, HashSetBuilder, HashSetRef};
+pub use set::{
+    Difference, HashSet, HashSetBuilder, HashSetPin, HashSetPinOwned, HashSetRef, Intersection,
+    SymmetricDifference, Union,
+};
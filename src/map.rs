@@ -1,20 +1,37 @@
 use crate::raw::utils::MapGuard;
 use crate::raw::{self, InsertResult};
-use crate::Equivalent;
-use seize::{Collector, Guard, LocalGuard, OwnedGuard};
+use crate::{persist, Equivalent, Expiring};
+use seize::{Collector, Guard, LocalGuard, OwnedGuard, UnprotectedGuard};
 
-use std::collections::hash_map::RandomState;
+use std::cell::Cell;
+use std::collections::hash_map::{DefaultHasher, RandomState};
 use std::fmt;
-use std::hash::{BuildHasher, Hash};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io;
 use std::marker::PhantomData;
+use std::ops::{ControlFlow, RangeBounds, Sub};
+use std::pin::Pin;
+use std::ptr;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 
 /// A concurrent hash table.
 ///
 /// Most hash table operations require a [`Guard`](crate::Guard), which can be acquired through
 /// [`HashMap::guard`] or using the [`HashMap::pin`] API. See the [crate-level documentation](crate#usage)
 /// for details.
+///
+/// Dropping a `HashMap` synchronously frees all of its tables and entries, including any tables
+/// involved in an in-progress resize and any entries that were removed or replaced but whose
+/// reclamation was still pending. No guard is required, as no references into the map can outlive it.
+///
+/// By default, maps use the standard library's [`RandomState`], which is seeded independently for
+/// every map that is created, so two maps will generally hash the same key differently. For
+/// reproducible hashing, a fixed seed can be configured with [`HashMap::with_seed`].
 pub struct HashMap<K, V, S = RandomState> {
     raw: raw::HashMap<K, V, S>,
+    #[cfg(feature = "async")]
+    pending: crate::pending::Pending<K>,
 }
 
 // Safety: `HashMap` acts as a single-threaded collection on a single thread.
@@ -61,6 +78,11 @@ pub struct HashMapBuilder<K, V, S = RandomState> {
     capacity: usize,
     collector: Collector,
     resize_mode: ResizeMode,
+    entry_pool: bool,
+    probe_strategy: ProbeStrategy,
+    auto_compact: Option<f64>,
+    concurrency: Option<usize>,
+    max_probe: Option<usize>,
     _kv: PhantomData<(K, V)>,
 }
 
@@ -80,6 +102,11 @@ impl<K, V> HashMapBuilder<K, V> {
             capacity: self.capacity,
             collector: self.collector,
             resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
             _kv: PhantomData,
         }
     }
@@ -97,6 +124,11 @@ impl<K, V, S> HashMapBuilder<K, V, S> {
             hasher: self.hasher,
             collector: self.collector,
             resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
             _kv: PhantomData,
         }
     }
@@ -108,6 +140,11 @@ impl<K, V, S> HashMapBuilder<K, V, S> {
             hasher: self.hasher,
             capacity: self.capacity,
             collector: self.collector,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
             _kv: PhantomData,
         }
     }
@@ -124,6 +161,170 @@ impl<K, V, S> HashMapBuilder<K, V, S> {
             hasher: self.hasher,
             capacity: self.capacity,
             resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
+            _kv: PhantomData,
+        }
+    }
+
+    /// Set the number of retired entries that are batched before reclamation is attempted.
+    ///
+    /// Entries that are removed or replaced cannot be freed immediately, as they may still be
+    /// accessed by concurrent readers. Instead, they are retired into a thread-local batch, and
+    /// reclamation of the batch is attempted once it reaches the given size. A smaller batch size
+    /// means that reclamation happens in smaller, more frequent increments, reducing memory usage
+    /// and the latency of any individual reclamation at the cost of throughput. A larger batch size
+    /// amortizes reclamation costs over more operations, but allows more memory to be held by
+    /// retired entries and makes each reclamation more expensive.
+    ///
+    /// By default, the batch size is the number of available CPUs, up to a maximum of `64`. Note
+    /// that batch sizes should generally be larger than the number of threads accessing the map.
+    ///
+    /// This option configures the map's [`Collector`], and is overridden by a later call
+    /// to [`collector`](HashMapBuilder::collector). See [`Collector::batch_size`] for details.
+    pub fn reclaim_batch(self, batch_size: usize) -> Self {
+        HashMapBuilder {
+            collector: self.collector.batch_size(batch_size),
+            hasher: self.hasher,
+            capacity: self.capacity,
+            resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
+            _kv: PhantomData,
+        }
+    }
+
+    /// Recycle entry allocations through a thread-local pool.
+    ///
+    /// Every entry in the map is allocated separately, and entries are deallocated once they
+    /// are removed or replaced and are no longer accessible to concurrent readers. Workloads that
+    /// frequently insert and remove entries can put significant pressure on the allocator. When
+    /// enabled, reclaimed entries are cached and reused for future insertions on the same thread,
+    /// reducing the number of allocator calls.
+    ///
+    /// The number of cached allocations is bounded per-thread. This option is disabled by default.
+    pub fn entry_pool(self, entry_pool: bool) -> Self {
+        HashMapBuilder {
+            entry_pool,
+            hasher: self.hasher,
+            capacity: self.capacity,
+            collector: self.collector,
+            resize_mode: self.resize_mode,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
+            _kv: PhantomData,
+        }
+    }
+
+    /// Set the probe sequence used by the map. See [`ProbeStrategy`] for details.
+    pub fn probe_strategy(self, probe_strategy: ProbeStrategy) -> Self {
+        HashMapBuilder {
+            probe_strategy,
+            hasher: self.hasher,
+            capacity: self.capacity,
+            collector: self.collector,
+            resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
+            _kv: PhantomData,
+        }
+    }
+
+    /// Automatically compact the map once removals leave behind enough tombstones.
+    ///
+    /// Removing an entry leaves behind a tombstone, which is only cleared when the table is
+    /// resized or [compacted](HashMap::compact). With this option, the table is compacted
+    /// in-place once the number of entries removed from it reaches `ratio` multiplied by its
+    /// capacity, keeping maps with steady-state churn fast without manual intervention. The
+    /// compaction is performed by the removal that reaches the threshold and blocks until
+    /// it is complete.
+    ///
+    /// Note that tracking removals adds a shared atomic increment to every removal. This
+    /// option is disabled by default.
+    ///
+    /// # Panics
+    ///
+    /// Building the map panics if `ratio` is not positive and finite.
+    pub fn auto_compact_at(self, ratio: f64) -> Self {
+        HashMapBuilder {
+            auto_compact: Some(ratio),
+            hasher: self.hasher,
+            capacity: self.capacity,
+            collector: self.collector,
+            resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
+            _kv: PhantomData,
+        }
+    }
+
+    /// Set the expected number of threads concurrently writing to the map.
+    ///
+    /// The length of the map is tracked with a set of striped counters, each on its own cache
+    /// line, and every thread updates the counter selected by its thread ID. This option sets
+    /// the number of counters, rounded up to the next power of two, so that concurrent writers
+    /// are less likely to contend on the same cache line. A larger number of counters reduces
+    /// false sharing between writers, but increases the memory usage of the map and the cost
+    /// of [`HashMap::len`]. The diagnostic counters enabled by the `stats` feature are striped
+    /// in the same way. No other part of the map's layout is affected. The number of counters
+    /// is capped at 65536.
+    ///
+    /// By default, the number of counters is based on the available parallelism of the system.
+    pub fn concurrency(self, concurrency: usize) -> Self {
+        HashMapBuilder {
+            concurrency: Some(concurrency),
+            hasher: self.hasher,
+            capacity: self.capacity,
+            collector: self.collector,
+            resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            max_probe: self.max_probe,
+            _kv: PhantomData,
+        }
+    }
+
+    /// Set the maximum probe length of the map.
+    ///
+    /// Entries are found by probing a sequence of slots, starting at the slot the key hashes to.
+    /// By default, the probe length is limited to a multiple of the logarithm of the table's
+    /// capacity, and an insert that would exceed the limit resizes the table instead. This option
+    /// caps the probe length at `max_probe_len`, bounding the worst-case cost of lookups for
+    /// clustered or adversarial inputs.
+    ///
+    /// The probe limit and the load factor both trigger a resize, so whichever is reached
+    /// first grows the table. Note that a small limit may cause the table to grow at a
+    /// significantly lower load factor. Keys whose hashes collide entirely cannot be separated
+    /// by growing the table, so once the table would be less than 1/16th full, the cap is lifted
+    /// and the table falls back to the default probe limit until it grows again. This bounds
+    /// the memory used for clustered or adversarial inputs, at the cost of longer probes.
+    ///
+    /// A `max_probe_len` of zero means that every entry must be stored in the first slot it
+    /// hashes to. By default, the probe length is not capped.
+    pub fn max_probe_len(self, max_probe_len: usize) -> Self {
+        HashMapBuilder {
+            max_probe: Some(max_probe_len),
+            hasher: self.hasher,
+            capacity: self.capacity,
+            collector: self.collector,
+            resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
             _kv: PhantomData,
         }
     }
@@ -131,7 +332,19 @@ impl<K, V, S> HashMapBuilder<K, V, S> {
     /// Construct a [`HashMap`] from the builder, using the configured options.
     pub fn build(self) -> HashMap<K, V, S> {
         HashMap {
-            raw: raw::HashMap::new(self.capacity, self.hasher, self.collector, self.resize_mode),
+            raw: raw::HashMap::new(
+                self.capacity,
+                self.hasher,
+                self.collector,
+                self.resize_mode,
+                self.entry_pool,
+                self.probe_strategy,
+                self.auto_compact,
+                self.concurrency,
+                self.max_probe,
+            ),
+            #[cfg(feature = "async")]
+            pending: Default::default(),
         }
     }
 }
@@ -142,6 +355,11 @@ impl<K, V, S> fmt::Debug for HashMapBuilder<K, V, S> {
             .field("capacity", &self.capacity)
             .field("collector", &self.collector)
             .field("resize_mode", &self.resize_mode)
+            .field("entry_pool", &self.entry_pool)
+            .field("probe_strategy", &self.probe_strategy)
+            .field("auto_compact", &self.auto_compact)
+            .field("concurrency", &self.concurrency)
+            .field("max_probe", &self.max_probe)
             .finish()
     }
 }
@@ -151,6 +369,21 @@ impl<K, V, S> fmt::Debug for HashMapBuilder<K, V, S> {
 /// Hash maps must resize when the underlying table becomes full, migrating all key and value pairs
 /// to a new table. This type allows you to configure the resizing behavior when passed to
 /// [`HashMapBuilder::resize_mode`].
+///
+/// In either mode, read operations such as [`HashMap::get`] and [`HashMap::contains_key`] never
+/// help with migration, leaving all resize work to writers. This keeps read latency predictable,
+/// but means that an incremental resize may stay in progress indefinitely if writes to the map
+/// are rare. In that case, readers may have to search both tables until the resize completes.
+/// [`HashMap::finalize_resize`] can be used to complete a pending resize, for example after
+/// bulk loading the map.
+///
+/// The capacity of the table always doubles when it grows. Table lengths must be powers of two, both
+/// to map hashes to slots with a cheap mask and to guarantee that a quadratic probe sequence visits
+/// every slot, so the growth factor is not configurable. Doubling keeps the amortized cost of copying
+/// entries constant per insert. However, both tables coexist until the migration completes and the
+/// old table is reclaimed, so the memory used by the table itself peaks at roughly three times the
+/// size of the old table during a resize. For large maps where this is a concern, consider configuring
+/// the expected capacity up front with [`HashMapBuilder::capacity`] to avoid resizing altogether.
 #[derive(Debug)]
 pub enum ResizeMode {
     /// Writers copy a constant number of key/value pairs to the new table before making
@@ -186,11 +419,103 @@ impl Default for ResizeMode {
     }
 }
 
+/// The probe sequence used by a [`HashMap`].
+///
+/// When a key hashes to a slot that is already occupied, the table probes other slots
+/// until it finds the key or an empty slot. This type allows you to configure the order in
+/// which slots are visited when passed to [`HashMapBuilder::probe_strategy`].
+///
+/// Regardless of the strategy, the length of a probe sequence is bounded based on the capacity
+/// of the table, and the table is resized when the limit is reached. A strategy that produces
+/// longer probe sequences for a given key distribution will therefore also cause the table to
+/// resize at a lower load factor.
+///
+/// Note that schemes that move existing entries during insertion, such as Robin Hood hashing,
+/// are not supported, as entries are never moved within a table so that they can be read
+/// without locking.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProbeStrategy {
+    /// Probe slots at offsets following the triangular numbers, i.e. `1, 3, 6, 10, ...`.
+    ///
+    /// Quadratic probing is resistant to clustering of keys with similar hashes, and is
+    /// the default probe strategy.
+    #[default]
+    Quadratic,
+    /// Probe consecutive slots.
+    ///
+    /// Linear probing has better cache locality, which can make lookups faster for
+    /// well-distributed hashes. However, it is prone to clustering, which can lead to long
+    /// probe sequences and premature resizes with a poor hash distribution.
+    Linear,
+}
+
+/// A [`BuildHasher`] with a fixed seed.
+///
+/// Maps hashing with the same seed will hash keys identically, which makes iteration order
+/// and resizing behavior reproducible across runs of a given program. Note that the underlying
+/// hash function is not guaranteed to stay the same across Rust releases.
+///
+/// Unlike [`RandomState`], the seed is chosen by the caller, and so this should not be used to
+/// protect against HashDoS attacks unless the seed is itself generated randomly. See
+/// [`HashMap::with_seed`] for details.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SeededState {
+    seed: u64,
+}
+
+impl SeededState {
+    /// Creates a `SeededState` with the given seed.
+    pub const fn new(seed: u64) -> SeededState {
+        SeededState { seed }
+    }
+
+    /// Returns the seed used by this hasher.
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl BuildHasher for SeededState {
+    type Hasher = DefaultHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> DefaultHasher {
+        let mut hasher = DefaultHasher::new();
+        hasher.write_u64(self.seed);
+        hasher
+    }
+}
+
 impl<K, V> HashMap<K, V> {
     /// Creates an empty `HashMap`.
     ///
     /// The hash map is initially created with a capacity of 0, so it will not allocate
-    /// until it is first inserted into.
+    /// a table until it is first inserted into.
+    ///
+    /// # Static Maps
+    ///
+    /// This function is not `const`, and so cannot be used to initialize a `static` directly.
+    /// Every map owns a memory reclamation [`Collector`] and a set of striped length counters,
+    /// both of which are allocated on construction, and the default [`RandomState`] hasher
+    /// draws its seed from the operating system at runtime. Note that using a fixed-seed hasher
+    /// such as [`SeededState`] does not help, as the collector and counters must still be allocated.
+    ///
+    /// Instead, a global map can be lazily initialized with [`OnceLock`](std::sync::OnceLock).
+    /// The map is constructed on first access, and the table itself is only allocated on the
+    /// first insert:
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::sync::OnceLock;
+    ///
+    /// fn cache() -> &'static HashMap<u64, String> {
+    ///     static CACHE: OnceLock<HashMap<u64, String>> = OnceLock::new();
+    ///     CACHE.get_or_init(HashMap::new)
+    /// }
+    ///
+    /// cache().pin().insert(1, "a".to_owned());
+    /// assert_eq!(cache().pin().get(&1).unwrap(), "a");
+    /// ```
     ///
     /// # Examples
     ///
@@ -218,6 +543,71 @@ impl<K, V> HashMap<K, V> {
         HashMap::with_capacity_and_hasher(capacity, RandomState::new())
     }
 
+    /// Creates an empty `HashMap` that uses the given [`Collector`] for garbage collection.
+    ///
+    /// This is a shorthand for [`HashMap::builder`] with a custom
+    /// [`collector`](HashMapBuilder::collector). Note that a collector cannot be shared between
+    /// multiple maps, as dropping a map reclaims every object retired to its collector.
+    /// For a batch of short-lived maps, a collector configured once can instead be cloned for
+    /// each map, which creates an independent collector with the same configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use seize::Collector;
+    ///
+    /// let template = Collector::new().batch_size(16);
+    /// let maps: Vec<HashMap<u64, u64>> = (0..4)
+    ///     .map(|_| HashMap::with_collector(template.clone()))
+    ///     .collect();
+    /// ```
+    pub fn with_collector(collector: Collector) -> HashMap<K, V> {
+        HashMap::builder().collector(collector).build()
+    }
+
+    /// Creates a `HashMap` from parallel vectors of keys and values, pairing them by index.
+    ///
+    /// This is useful for bulk-loading columnar data. The map is preallocated with enough
+    /// capacity for every key. If a key appears more than once, the value with the highest
+    /// index is kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` and `values` have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from_keys_values(vec!["a", "b", "a"], vec![1, 2, 3]);
+    /// assert_eq!(map.len(), 2);
+    /// assert_eq!(map.pin().get("a"), Some(&3));
+    /// assert_eq!(map.pin().get("b"), Some(&2));
+    /// ```
+    pub fn from_keys_values(keys: Vec<K>, values: Vec<V>) -> HashMap<K, V>
+    where
+        K: Hash + Eq,
+    {
+        assert_eq!(
+            keys.len(),
+            values.len(),
+            "`keys` and `values` must have the same length"
+        );
+
+        let map = HashMap::with_capacity(keys.len());
+
+        {
+            let guard = map.guard();
+            for (key, value) in keys.into_iter().zip(values) {
+                map.insert(key, value, &guard);
+            }
+        }
+
+        map
+    }
+
     /// Returns a builder for a `HashMap`.
     ///
     /// The builder can be used for more complex configuration, such as using
@@ -228,11 +618,42 @@ impl<K, V> HashMap<K, V> {
             hasher: RandomState::default(),
             collector: Collector::new(),
             resize_mode: ResizeMode::default(),
+            entry_pool: false,
+            probe_strategy: ProbeStrategy::default(),
+            auto_compact: None,
+            concurrency: None,
+            max_probe: None,
             _kv: PhantomData,
         }
     }
 }
 
+impl<K, V> HashMap<K, V, SeededState> {
+    /// Creates an empty `HashMap` that hashes keys with the given seed.
+    ///
+    /// By default, every map is seeded independently at random, so maps created with
+    /// [`HashMap::new`] already hash keys differently from each other. Using a fixed seed
+    /// instead makes hashing reproducible, which can be useful for testing or debugging.
+    /// Shards of a larger structure that require independent hashing should be given
+    /// distinct seeds.
+    ///
+    /// This is equivalent to calling [`HashMap::with_hasher`] with a [`SeededState`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::hash::BuildHasher;
+    ///
+    /// let a: HashMap<&str, i32, _> = HashMap::with_seed(1);
+    /// let b: HashMap<&str, i32, _> = HashMap::with_seed(1);
+    /// assert_eq!(a.hasher().hash_one("a"), b.hasher().hash_one("a"));
+    /// ```
+    pub fn with_seed(seed: u64) -> HashMap<K, V, SeededState> {
+        HashMap::with_hasher(SeededState::new(seed))
+    }
+}
+
 impl<K, V, S> Default for HashMap<K, V, S>
 where
     S: Default,
@@ -300,7 +721,14 @@ impl<K, V, S> HashMap<K, V, S> {
                 hash_builder,
                 Collector::default(),
                 ResizeMode::default(),
+                false,
+                ProbeStrategy::default(),
+                None,
+                None,
+                None,
             ),
+            #[cfg(feature = "async")]
+            pending: Default::default(),
         }
     }
 
@@ -338,7 +766,7 @@ impl<K, V, S> HashMap<K, V, S> {
     /// See the [crate-level documentation](crate#usage) for details.
     #[inline]
     pub fn guard(&self) -> LocalGuard<'_> {
-        self.raw.collector().enter()
+        self.raw.enter()
     }
 
     /// Returns an owned guard for use with this map.
@@ -351,7 +779,158 @@ impl<K, V, S> HashMap<K, V, S> {
     /// See the [crate-level documentation](crate#usage) for details.
     #[inline]
     pub fn owned_guard(&self) -> OwnedGuard<'_> {
-        self.raw.collector().enter_owned()
+        self.raw.enter_owned()
+    }
+
+    /// Returns the number of guards created for this map over its lifetime.
+    ///
+    /// The first value is the number of local guards and the second is the number of owned
+    /// guards, including those created implicitly through [`HashMap::pin`] and
+    /// [`HashMap::pin_owned`]. Guards created directly through a shared [`Collector`] are
+    /// not counted.
+    ///
+    /// Note that guards are not cached by the map, so every call to `pin` or `guard` enters
+    /// the collector. Creating a guard while the current thread already holds another guard
+    /// for the same collector is cheaper than creating the first one, but is still not free.
+    /// A guard count that grows with the number of operations in a hot loop indicates that
+    /// a guard may be worth hoisting out of the loop.
+    ///
+    /// This method is intended for diagnostics, and is only available with the `stats` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    ///
+    /// let guard = map.guard();
+    /// for i in 0..100 {
+    ///     map.insert(i, i, &guard);
+    /// }
+    ///
+    /// assert_eq!(map.guard_count(), (1, 0));
+    /// ```
+    #[inline]
+    #[cfg(feature = "stats")]
+    pub fn guard_count(&self) -> (u64, u64) {
+        self.raw.guard_count()
+    }
+
+    /// Returns a reference to the map's [`BuildHasher`].
+    ///
+    /// Keys are hashed with [`BuildHasher::hash_one`], and the resulting hash is used as-is,
+    /// without any secondary mixing. See [`finish_hash`](HashMap::finish_hash) for details
+    /// about how the hash is used to locate entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let hasher = RandomState::new();
+    /// let map: HashMap<i32, i32> = HashMap::with_hasher(hasher);
+    /// let hasher: &RandomState = map.hasher();
+    /// ```
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        &self.raw.hasher
+    }
+
+    /// Returns the hash that the map uses to locate entries, given the output of the
+    /// map's [`hasher`](HashMap::hasher).
+    ///
+    /// The map does not currently apply a secondary mix, so this returns `hash` unchanged.
+    /// The low bits of the hash select the starting slot in the table, and the top 7 bits
+    /// are stored as metadata to speed up probing. A hasher with poor entropy in either
+    /// range will degrade performance.
+    ///
+    /// External structures that need to agree with the map on how keys are hashed, such as
+    /// a membership filter used in front of [`contains_key_with_hash`](HashMap::contains_key_with_hash),
+    /// should pass hashes through this method to remain compatible if a mix is introduced in the
+    /// future. Note that the precomputed-hash methods expect the unfinished hash, as produced by
+    /// the hasher.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::hash::BuildHasher;
+    ///
+    /// let map: HashMap<i32, i32> = HashMap::new();
+    /// let hash = map.hasher().hash_one(&1);
+    ///
+    /// // Select a bucket in a filter with 64 buckets in the same way as the map.
+    /// let bucket = map.finish_hash(hash) & 63;
+    /// # let _ = bucket;
+    /// ```
+    #[inline]
+    pub fn finish_hash(&self, hash: u64) -> u64 {
+        hash
+    }
+
+    /// Returns a read-only view of the map.
+    ///
+    /// The view only exposes methods that read from the map, allowing read-only access to
+    /// be enforced at API boundaries. See [`ReadOnlyView`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, "a");
+    ///
+    /// let view = map.as_readonly();
+    /// let guard = view.guard();
+    /// assert_eq!(view.get(&1, &guard), Some(&"a"));
+    /// ```
+    #[inline]
+    pub fn as_readonly(&self) -> ReadOnlyView<'_, K, V, S> {
+        ReadOnlyView { map: self }
+    }
+
+    /// Converts the map into a [`FrozenHashMap`] that can be read without a guard.
+    ///
+    /// A frozen map cannot be modified, so no entries are ever retired, and reads load
+    /// the table directly without entering the collector. Any in-progress resize is
+    /// completed before the map is frozen. The map can be converted back with
+    /// [`FrozenHashMap::thaw`].
+    ///
+    /// This is useful for lookup tables that are built once and then only read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, "a");
+    ///
+    /// let frozen = map.freeze();
+    /// assert_eq!(frozen.get(&1), Some(&"a"));
+    ///
+    /// let map = frozen.thaw();
+    /// map.pin().insert(2, "b");
+    /// assert_eq!(map.len(), 2);
+    /// ```
+    #[inline]
+    pub fn freeze(self) -> FrozenHashMap<K, V, S>
+    where
+        K: Hash + Eq,
+        S: BuildHasher,
+    {
+        self.pin().finalize_resize();
+
+        FrozenHashMap {
+            map: self,
+            // Safety: The map is owned by the frozen map, which only exposes methods that read
+            // from it, so no values are ever retired. Additionally, there is no in-progress
+            // resize, so reads never help with copying.
+            guard: unsafe { seize::unprotected() },
+        }
     }
 }
 
@@ -362,6 +941,13 @@ where
 {
     /// Returns the number of entries in the map.
     ///
+    /// The length is read from a set of live counters that are updated after each insertion
+    /// or removal, independently of any guard. Under concurrent modification, it may therefore
+    /// differ from the number of entries observed by an [iterator](HashMap::iter), even one
+    /// created at the same time. Use [`snapshot_len`](HashMap::snapshot_len) to count the
+    /// entries visible to an iteration instead. In the absence of concurrent writers, the two
+    /// are always equal.
+    ///
     /// # Examples
     ///
     /// ```
@@ -395,6 +981,36 @@ where
         self.len() == 0
     }
 
+    /// Returns the number of times the map has been resized over its lifetime.
+    ///
+    /// The first value is the number of resizes that grew the table, and the second is
+    /// the number of resizes that shrunk it. Resizes that keep the capacity the same, for
+    /// example to clean up deleted entries, are not counted. The counters are recorded when
+    /// a resize starts, regardless of whether it has completed.
+    ///
+    /// A large number of grows may indicate that the initial capacity of the map is too small.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::with_capacity(16);
+    /// assert_eq!(map.resize_count(), (0, 0));
+    ///
+    /// for i in 0..1000 {
+    ///     map.pin().insert(i, i);
+    /// }
+    ///
+    /// let (grows, shrinks) = map.resize_count();
+    /// assert!(grows > 0);
+    /// assert_eq!(shrinks, 0);
+    /// ```
+    #[inline]
+    pub fn resize_count(&self) -> (u64, u64) {
+        self.raw.resize_count()
+    }
+
     /// Returns `true` if the map contains a value for the specified key.
     ///
     /// The key may be any borrowed form of the map's key type, but
@@ -404,6 +1020,11 @@ where
     /// [`Eq`]: std::cmp::Eq
     /// [`Hash`]: std::hash::Hash
     ///
+    /// Note that the result may be outdated as soon as it is returned, as the key
+    /// can be concurrently removed by another thread. If you need the value, call
+    /// [`get`](HashMap::get) directly instead of `contains_key` followed by `get`;
+    /// both perform the same lookup, but `get` does it once, and the returned reference
+    /// remains valid for as long as the guard is held, even if the key is removed.
     ///
     /// # Examples
     ///
@@ -423,6 +1044,32 @@ where
         self.get(key, self.raw.verify(guard)).is_some()
     }
 
+    /// Returns whether each of the given keys is present in the map.
+    ///
+    /// The result has one element per key, in the same order as `keys`. This is a cheaper
+    /// alternative to looking up each value when filtering a large batch of candidate keys,
+    /// as the guard is only verified once. Each key is looked up independently, so the result
+    /// does not necessarily reflect a single point in time if the map is modified concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([(1, "a"), (3, "c")]);
+    /// assert_eq!(map.pin().contains_mask(&[&1, &2, &3]), [true, false, true]);
+    /// ```
+    #[inline]
+    pub fn contains_mask<Q>(&self, keys: &[&Q], guard: &impl Guard) -> Vec<bool>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        let guard = self.raw.verify(guard);
+        keys.iter()
+            .map(|key| self.raw.get(*key, guard).is_some())
+            .collect()
+    }
+
     /// Returns a reference to the value corresponding to the key.
     ///
     /// The key may be any borrowed form of the map's key type, but
@@ -432,6 +1079,11 @@ where
     /// [`Eq`]: std::cmp::Eq
     /// [`Hash`]: std::hash::Hash
     ///
+    /// There is no unchecked variant of this method that assumes the key is present.
+    /// Presence cannot be guaranteed in a concurrent map, even directly after a call
+    /// to [`contains_key`](HashMap::contains_key), so matching on the returned
+    /// `Option` is both the cheapest and the only correct existence check.
+    ///
     /// # Examples
     ///
     /// ```
@@ -441,6 +1093,12 @@ where
     /// map.pin().insert(1, "a");
     /// assert_eq!(map.pin().get(&1), Some(&"a"));
     /// assert_eq!(map.pin().get(&2), None);
+    ///
+    /// // Prefer a single lookup over `contains_key` followed by `get`.
+    /// let map = map.pin();
+    /// if let Some(value) = map.get(&1) {
+    ///     assert_eq!(*value, "a");
+    /// }
     /// ```
     #[inline]
     pub fn get<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> Option<&'g V>
@@ -454,171 +1112,225 @@ where
         }
     }
 
-    /// Returns the key-value pair corresponding to the supplied key.
+    /// Returns a reference to the value corresponding to the key, panicking if the key
+    /// is not present.
     ///
-    /// The supplied key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the key type.
+    /// This is intended for code where a missing key indicates a bug. Unlike calling `unwrap`
+    /// on the result of [`get`](HashMap::get), the panic message includes the [`Debug`]
+    /// representation of the key.
     ///
-    /// [`Eq`]: std::cmp::Eq
-    /// [`Hash`]: std::hash::Hash
+    /// [`Debug`]: std::fmt::Debug
     ///
-    /// # Examples
+    /// # Panics
     ///
-    /// ```
+    /// Panics if the key is not present in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
     /// use papaya::HashMap;
     ///
     /// let map = HashMap::new();
-    /// map.pin().insert(1, "a");
-    /// assert_eq!(map.pin().get_key_value(&1), Some((&1, &"a")));
-    /// assert_eq!(map.pin().get_key_value(&2), None);
+    /// map.pin().insert("a", 1);
+    /// assert_eq!(map.pin().get_expect("a"), &1);
+    ///
+    /// // Panics with "key \"b\" not found in map".
+    /// map.pin().get_expect("b");
     /// ```
     #[inline]
-    pub fn get_key_value<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> Option<(&'g K, &'g V)>
+    #[track_caller]
+    pub fn get_expect<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> &'g V
     where
-        Q: Equivalent<K> + Hash + ?Sized,
+        K: 'g,
+        Q: Equivalent<K> + Hash + fmt::Debug + ?Sized,
     {
-        self.raw.get(key, self.raw.verify(guard))
+        match self.get(key, guard) {
+            Some(value) => value,
+            None => panic!("key {key:?} not found in map"),
+        }
     }
 
-    /// Inserts a key-value pair into the map.
+    /// Returns a reference to the value corresponding to the key, using a precomputed hash.
     ///
-    /// If the map did not have this key present, [`None`] is returned.
-    ///
-    /// If the map did have this key present, the value is updated, and the old
-    /// value is returned. The key is not updated, though; this matters for
-    /// types that can be `==` without being identical. See the [standard library
-    /// documentation] for details.
+    /// This is useful if the hash of the key has already been computed, for example when
+    /// partitioning work by hash, and avoids hashing the key a second time.
     ///
-    /// [standard library documentation]: https://doc.rust-lang.org/std/collections/index.html#insert-and-complex-keys
+    /// The provided `hash` *must* be equal to the hash of `key` produced by the map's
+    /// [`hasher`](HashMap::hasher). Otherwise, the lookup may fail to find the entry. This
+    /// is checked with a debug assertion.
     ///
     /// # Examples
     ///
     /// ```
     /// use papaya::HashMap;
+    /// use std::hash::BuildHasher;
     ///
     /// let map = HashMap::new();
-    /// assert_eq!(map.pin().insert(37, "a"), None);
-    /// assert_eq!(map.pin().is_empty(), false);
+    /// let hash = map.hasher().hash_one(&1);
     ///
-    /// map.pin().insert(37, "b");
-    /// assert_eq!(map.pin().insert(37, "c"), Some(&"b"));
-    /// assert_eq!(map.pin().get(&37), Some(&"c"));
+    /// map.pin().insert_with_hash(hash, 1, "a");
+    /// assert_eq!(map.pin().get_with_hash(hash, &1), Some(&"a"));
     /// ```
     #[inline]
-    pub fn insert<'g>(&self, key: K, value: V, guard: &'g impl Guard) -> Option<&'g V> {
-        match self.raw.insert(key, value, true, self.raw.verify(guard)) {
-            InsertResult::Inserted(_) => None,
-            InsertResult::Replaced(value) => Some(value),
-            InsertResult::Error { .. } => unreachable!(),
+    pub fn get_with_hash<'g, Q>(&self, hash: u64, key: &Q, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        K: 'g,
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        debug_assert_eq!(
+            hash,
+            self.raw.hasher.hash_one(key),
+            "provided hash does not match the hash of the key"
+        );
+
+        match self.raw.get_with_hash(hash, key, self.raw.verify(guard)) {
+            Some((_, v)) => Some(v),
+            None => None,
         }
     }
 
-    /// Tries to insert a key-value pair into the map, and returns
-    /// a reference to the value that was inserted.
+    /// Returns `true` if the map contains a value for the specified key, using a precomputed hash.
     ///
-    /// If the map already had this key present, nothing is updated, and
-    /// an error containing the existing value is returned.
+    /// Together with [`get_with_hash`](HashMap::get_with_hash) and
+    /// [`insert_with_hash`](HashMap::insert_with_hash), this allows the hash of a key to be
+    /// computed exactly once across multiple operations.
+    ///
+    /// The provided `hash` *must* be equal to the hash of `key` produced by the map's
+    /// [`hasher`](HashMap::hasher). Otherwise, the lookup may fail to find the entry. This
+    /// is checked with a debug assertion.
     ///
     /// # Examples
     ///
     /// ```
     /// use papaya::HashMap;
+    /// use std::hash::BuildHasher;
     ///
     /// let map = HashMap::new();
-    /// let map = map.pin();
+    /// let hash = map.hasher().hash_one(&1);
     ///
-    /// assert_eq!(map.try_insert(37, "a").unwrap(), &"a");
+    /// if !map.pin().contains_key_with_hash(hash, &1) {
+    ///     map.pin().insert_with_hash(hash, 1, "a");
+    /// }
     ///
-    /// let err = map.try_insert(37, "b").unwrap_err();
-    /// assert_eq!(err.current, &"a");
-    /// assert_eq!(err.not_inserted, "b");
+    /// assert!(map.pin().contains_key_with_hash(hash, &1));
     /// ```
     #[inline]
-    pub fn try_insert<'g>(
-        &self,
-        key: K,
-        value: V,
-        guard: &'g impl Guard,
-    ) -> Result<&'g V, OccupiedError<'g, V>> {
-        // Safety: Checked the guard above.
-        match self.raw.insert(key, value, false, self.raw.verify(guard)) {
-            InsertResult::Inserted(value) => Ok(value),
-            InsertResult::Error {
-                current,
-                not_inserted,
-            } => Err(OccupiedError {
-                current,
-                not_inserted,
-            }),
-            InsertResult::Replaced(_) => unreachable!(),
-        }
+    pub fn contains_key_with_hash<Q>(&self, hash: u64, key: &Q, guard: &impl Guard) -> bool
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        debug_assert_eq!(
+            hash,
+            self.raw.hasher.hash_one(key),
+            "provided hash does not match the hash of the key"
+        );
+
+        self.raw
+            .get_with_hash(hash, key, self.raw.verify(guard))
+            .is_some()
     }
 
-    /// Tries to insert a key and value computed from a closure into the map,
-    /// and returns a reference to the value that was inserted.
+    /// Returns a reference to the value corresponding to the key, without
+    /// searching through any in-progress resizes.
     ///
-    /// If the map already had this key present, nothing is updated, and
-    /// the existing value is returned.
+    /// During an incremental resize, [`get`](HashMap::get) may have to search both the
+    /// current table and the table being migrated to. This method only searches the
+    /// current table, and returns [`GetResult::Migrating`] if the key may have been moved
+    /// to the new table. Callers on latency-critical paths can then choose to back off
+    /// and retry later, or fall back to `get`.
+    ///
+    /// [`GetResult::Migrating`] is never returned in [blocking](ResizeMode::Blocking) resize mode.
     ///
     /// # Examples
     ///
     /// ```
-    /// use papaya::HashMap;
+    /// use papaya::{GetResult, HashMap};
     ///
     /// let map = HashMap::new();
-    /// let map = map.pin();
+    /// map.pin().insert(1, "a");
     ///
-    /// assert_eq!(map.try_insert_with(37, || "a").unwrap(), &"a");
+    /// let value = match map.pin().try_get(&1) {
+    ///     GetResult::Found(value) => Some(*value),
+    ///     GetResult::Absent => None,
+    ///     GetResult::Migrating => map.pin().get(&1).copied(),
+    /// };
     ///
-    /// let current = map.try_insert_with(37, || "b").unwrap_err();
-    /// assert_eq!(current, &"a");
+    /// assert_eq!(value, Some("a"));
+    /// assert_eq!(map.pin().try_get(&2), GetResult::Absent);
     /// ```
     #[inline]
-    pub fn try_insert_with<'g, F>(
-        &self,
-        key: K,
-        f: F,
-        guard: &'g impl Guard,
-    ) -> Result<&'g V, &'g V>
+    pub fn try_get<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> GetResult<'g, V>
     where
-        F: FnOnce() -> V,
         K: 'g,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
-        self.raw.try_insert_with(key, f, self.raw.verify(guard))
+        self.raw.try_get(key, self.raw.verify(guard))
     }
 
-    /// Returns a reference to the value corresponding to the key, or inserts a default value.
+    /// Returns a pinned reference to the value corresponding to the key.
     ///
-    /// If the given key is present, the corresponding value is returned. If it is not present,
-    /// the provided `value` is inserted, and a reference to the newly inserted value is returned.
+    /// Values are allocated separately from the table and are never moved by the map
+    /// after they are inserted, even across resizes. Values are only ever dropped in
+    /// place once they have been removed and are no longer accessible to concurrent
+    /// readers. This makes it possible to store `!Unpin` types, such as self-referential
+    /// structs or futures, and access them through a stable address.
+    ///
+    /// # Pinning
+    ///
+    /// Any method that moves a value out of the map, or hands out a mutable reference to a
+    /// value, could break the pinning guarantee and therefore requires `V: Unpin`. This is
+    /// the complete list of such methods:
+    ///
+    /// - [`get_mut`](HashMap::get_mut)
+    /// - [`get_mut_or_insert_with`](HashMap::get_mut_or_insert_with)
+    /// - [`partition`](HashMap::partition)
+    /// - [`into_values`](HashMap::into_values)
+    /// - [`into_iter`](HashMap::into_iter)
+    ///
+    /// Each of these methods includes an example showing that it cannot be called on a map
+    /// with `!Unpin` values. New methods that move values must be added to this list.
     ///
     /// # Examples
     ///
     /// ```
     /// use papaya::HashMap;
+    /// use std::marker::PhantomPinned;
+    ///
+    /// struct Pinned {
+    ///     value: u32,
+    ///     _pinned: PhantomPinned,
+    /// }
     ///
     /// let map = HashMap::new();
-    /// assert_eq!(map.pin().get_or_insert("a", 3), &3);
-    /// assert_eq!(map.pin().get_or_insert("a", 6), &3);
+    /// map.pin().insert(1, Pinned { value: 7, _pinned: PhantomPinned });
+    ///
+    /// let map = map.pin();
+    /// let pinned = map.get_pin(&1).unwrap();
+    /// assert_eq!(pinned.value, 7);
     /// ```
     #[inline]
-    pub fn get_or_insert<'g>(&self, key: K, value: V, guard: &'g impl Guard) -> &'g V {
-        // Note that we use `try_insert` instead of `compute` or `get_or_insert_with` here, as it
-        // allows us to avoid the closure indirection.
-        match self.try_insert(key, value, guard) {
-            Ok(inserted) => inserted,
-            Err(OccupiedError { current, .. }) => current,
+    pub fn get_pin<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> Option<Pin<&'g V>>
+    where
+        K: 'g,
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        match self.raw.get(key, self.raw.verify(guard)) {
+            // Safety: Values are never moved after being inserted into the map, and
+            // are dropped in place when reclaimed. Every method that moves values out
+            // of the map requires `V: Unpin`, see the `# Pinning` section above.
+            Some((_, v)) => Some(unsafe { Pin::new_unchecked(v) }),
+            None => None,
         }
     }
 
-    /// Returns a reference to the value corresponding to the key, or inserts a default value
-    /// computed from a closure.
+    /// Returns the key-value pair corresponding to the supplied key.
     ///
-    /// If the given key is present, the corresponding value is returned. If it is not present,
-    /// the value computed from `f` is inserted, and a reference to the newly inserted value is
-    /// returned.
+    /// The supplied key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the key type.
     ///
+    /// [`Eq`]: std::cmp::Eq
+    /// [`Hash`]: std::hash::Hash
     ///
     /// # Examples
     ///
@@ -626,34 +1338,38 @@ where
     /// use papaya::HashMap;
     ///
     /// let map = HashMap::new();
-    /// assert_eq!(map.pin().get_or_insert_with("a", || 3), &3);
-    /// assert_eq!(map.pin().get_or_insert_with("a", || 6), &3);
+    /// map.pin().insert(1, "a");
+    /// assert_eq!(map.pin().get_key_value(&1), Some((&1, &"a")));
+    /// assert_eq!(map.pin().get_key_value(&2), None);
     /// ```
     #[inline]
-    pub fn get_or_insert_with<'g, F>(&self, key: K, f: F, guard: &'g impl Guard) -> &'g V
+    pub fn get_key_value<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> Option<(&'g K, &'g V)>
     where
-        F: FnOnce() -> V,
-        K: 'g,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
-        self.raw.get_or_insert_with(key, f, self.raw.verify(guard))
+        self.raw.get(key, self.raw.verify(guard))
     }
 
-    /// Updates an existing entry atomically.
-    ///
-    /// If the value for the specified `key` is present, the new value is computed and stored the
-    /// using the provided update function, and the new value is returned. Otherwise, `None`
-    /// is returned.
-    ///
-    ///
-    /// The update function is given the current value associated with the given key and returns the
-    /// new value to be stored. The operation is applied atomically only if the state of the entry remains
-    /// the same, meaning that it is not concurrently modified in any way. If the entry is
-    /// modified, the operation is retried with the new entry, similar to a traditional [compare-and-swap](https://en.wikipedia.org/wiki/Compare-and-swap)
-    /// operation.
-    ///
-    /// Note that the `update` function should be pure as it may be called multiple times, and the output
-    /// for a given entry may be memoized across retries.
-    ///
+    /// Returns references to the values corresponding to multiple keys, as observed
+    /// at a single point in time.
+    ///
+    /// Reading several keys with separate calls to [`get`](HashMap::get) may observe
+    /// some keys before and others after a concurrent write, for example when related
+    /// entries such as a count and a sum are updated one after the other. True atomic
+    /// multi-key operations are not possible in a lock-free map, but this method performs
+    /// a *validated* read, repeatedly reading all keys until two consecutive reads are
+    /// identical. The values returned were all present in the map at some point between
+    /// those two reads.
+    ///
+    /// Note that this only guarantees that the individual reads are consistent with one another.
+    /// Whether related keys are themselves consistent depends on how they are written.
+    /// Writers that update multiple keys one after the other will still expose the intermediate
+    /// states, which this method may observe. Related values that must always be read together
+    /// are best stored in a single entry, for example as a tuple or struct.
+    ///
+    /// Additionally, a key that is absent in both reads may have been briefly inserted and
+    /// removed in between. If the keys are continuously written to, this method may retry
+    /// indefinitely.
     ///
     /// # Examples
     ///
@@ -661,28 +1377,42 @@ where
     /// use papaya::HashMap;
     ///
     /// let map = HashMap::new();
-    /// map.pin().insert("a", 1);
-    /// assert_eq!(map.pin().get(&"a"), Some(&1));
+    /// let map = map.pin();
+    /// map.insert("count:a", 2);
+    /// map.insert("sum:a", 10);
     ///
-    /// map.pin().update("a", |v| v + 1);
-    /// assert_eq!(map.pin().get(&"a"), Some(&2));
+    /// let [count, sum] = map.get_consistent(["count:a", "sum:a"]);
+    /// assert_eq!((count, sum), (Some(&2), Some(&10)));
     /// ```
     #[inline]
-    pub fn update<'g, F>(&self, key: K, update: F, guard: &'g impl Guard) -> Option<&'g V>
+    pub fn get_consistent<'g, Q, const N: usize>(
+        &self,
+        keys: [&Q; N],
+        guard: &'g impl Guard,
+    ) -> [Option<&'g V>; N]
     where
-        F: Fn(&V) -> V,
         K: 'g,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
-        self.raw.update(key, update, self.raw.verify(guard))
+        self.raw
+            .get_consistent(keys, self.raw.verify(guard))
+            .map(|entry| entry.map(|(_, value)| value))
     }
 
-    /// Updates an existing entry or inserts a default value.
+    /// Inserts a key-value pair into the map.
     ///
-    /// If the value for the specified `key` is present, the new value is computed and stored the
-    /// using the provided update function, and the new value is returned. Otherwise, the provided
-    /// `value` is inserted into the map, and a reference to the newly inserted value is returned.
+    /// If the map did not have this key present, [`None`] is returned.
     ///
-    /// See [`HashMap::update`] for details about how atomic updates are performed.
+    /// If the map did have this key present, the value is updated, and the old
+    /// value is returned. The key is not updated, though; this matters for
+    /// types that can be `==` without being identical. See the [standard library
+    /// documentation] for details.
+    ///
+    /// [standard library documentation]: https://doc.rust-lang.org/std/collections/index.html#insert-and-complex-keys
+    ///
+    /// The old value is always returned if present. The entry is replaced with a single atomic
+    /// swap, so `insert` can be used as an atomic get-and-set operation. Every value that is
+    /// replaced is returned to exactly one caller, even if the key is modified concurrently.
     ///
     /// # Examples
     ///
@@ -690,32 +1420,57 @@ where
     /// use papaya::HashMap;
     ///
     /// let map = HashMap::new();
-    /// assert_eq!(*map.pin().update_or_insert("a", |i| i + 1, 0), 0);
-    /// assert_eq!(*map.pin().update_or_insert("a", |i| i + 1, 0), 1);
+    /// assert_eq!(map.pin().insert(37, "a"), None);
+    /// assert_eq!(map.pin().is_empty(), false);
+    ///
+    /// map.pin().insert(37, "b");
+    /// assert_eq!(map.pin().insert(37, "c"), Some(&"b"));
+    /// assert_eq!(map.pin().get(&37), Some(&"c"));
     /// ```
     #[inline]
-    pub fn update_or_insert<'g, F>(
+    pub fn insert<'g>(&self, key: K, value: V, guard: &'g impl Guard) -> Option<&'g V> {
+        match self.raw.insert(key, value, true, self.raw.verify(guard)) {
+            InsertResult::Inserted(_) => None,
+            InsertResult::Replaced(value) => Some(value),
+            InsertResult::Error { .. } => unreachable!(),
+        }
+    }
+
+    /// Inserts a key-value pair into the map, converting the key and value with [`Into`].
+    ///
+    /// This behaves exactly like [`insert`](HashMap::insert), but allows writing
+    /// `map.insert_into("a", 1, &guard)` for a `HashMap<String, i64>` without explicit
+    /// conversions. Note that `insert` itself is not generic, as that would break type
+    /// inference for maps whose key or value types are inferred from their usage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map: HashMap<String, i64> = HashMap::new();
+    /// assert_eq!(map.pin().insert_into("a", 1), None);
+    /// assert_eq!(map.pin().insert_into("a", 2), Some(&1));
+    /// assert_eq!(map.pin().get("a"), Some(&2));
+    /// ```
+    #[inline]
+    pub fn insert_into<'g>(
         &self,
-        key: K,
-        update: F,
-        value: V,
+        key: impl Into<K>,
+        value: impl Into<V>,
         guard: &'g impl Guard,
-    ) -> &'g V
-    where
-        F: Fn(&V) -> V,
-        K: 'g,
-    {
-        self.update_or_insert_with(key, update, || value, guard)
+    ) -> Option<&'g V> {
+        self.insert(key.into(), value.into(), guard)
     }
 
-    /// Updates an existing entry or inserts a default value computed from a closure.
-    ///
-    /// If the value for the specified `key` is present, the new value is computed and stored the
-    /// using the provided update function, and the new value is returned. Otherwise, the value
-    /// computed by `f` is inserted into the map, and a reference to the newly inserted value is
-    /// returned.
+    /// Inserts a key-value pair into the map, also returning whether the insert grew the table.
     ///
-    /// See [`HashMap::update`] for details about how atomic updates are performed.
+    /// The first element of the returned tuple is the same as the result of
+    /// [`insert`](HashMap::insert). The second is `true` if this insert triggered an allocation
+    /// of a larger table, which can be used to attribute latency spikes to resizes. Note that
+    /// in [incremental](crate::ResizeMode::Incremental) mode, the entries are copied to the new
+    /// table gradually by subsequent operations, so the insert that triggered the resize does
+    /// not necessarily pay for the entire copy.
     ///
     /// # Examples
     ///
@@ -723,90 +1478,168 @@ where
     /// use papaya::HashMap;
     ///
     /// let map = HashMap::new();
-    /// assert_eq!(*map.pin().update_or_insert_with("a", |i| i + 1, || 0), 0);
-    /// assert_eq!(*map.pin().update_or_insert_with("a", |i| i + 1, || 0), 1);
+    /// let map_ref = map.pin();
+    /// assert_eq!(map_ref.insert_tracked(1, "a"), (None, false));
+    ///
+    /// let grows = (2..1000)
+    ///     .filter(|&i| map_ref.insert_tracked(i, "b").1)
+    ///     .count();
+    /// assert_eq!(grows as u64, map.resize_count().0);
     /// ```
     #[inline]
-    pub fn update_or_insert_with<'g, U, F>(
+    pub fn insert_tracked<'g>(
         &self,
         key: K,
-        update: U,
-        f: F,
+        value: V,
         guard: &'g impl Guard,
-    ) -> &'g V
-    where
-        F: FnOnce() -> V,
-        U: Fn(&V) -> V,
-        K: 'g,
-    {
-        self.raw
-            .update_or_insert_with(key, update, f, self.raw.verify(guard))
+    ) -> (Option<&'g V>, bool) {
+        match self.raw.insert_tracked(key, value, self.raw.verify(guard)) {
+            (InsertResult::Inserted(_), grew) => (None, grew),
+            (InsertResult::Replaced(value), grew) => (Some(value), grew),
+            (InsertResult::Error { .. }, _) => unreachable!(),
+        }
     }
 
-    /// Updates an entry with a compare-and-swap (CAS) function.
-    ///
-    /// This method allows you to perform complex operations on the map atomically. The `compute`
-    /// closure is given the current state of the entry and returns the operation that should be
-    /// performed. The operation is applied atomically only if the state of the entry remains the same,
-    /// meaning it is not concurrently modified in any way.
+    /// Inserts a key-value pair into the map, unless the key is not present and the map
+    /// already contains at least `capacity` entries.
     ///
-    /// Note that the `compute` function should be pure as it may be called multiple times, and
-    /// the output for a given entry may be memoized across retries.
+    /// Replacing the value of an existing key always succeeds, and returns the previous value
+    /// like [`insert`](HashMap::insert). Inserting a new key into a full map instead returns a
+    /// [`CapacityError`] containing the key and value, so that they are never lost. This allows
+    /// an eviction policy to be layered on top of the map: on error, the caller can choose an
+    /// entry to evict, remove it, and retry the insert.
     ///
-    /// In most cases you can avoid this method and instead use a higher-level atomic operation.
-    /// See the [crate-level documentation](crate#atomic-operations) for details.
+    /// Note that the capacity is a soft bound. The length of the map is checked before the
+    /// insert, so concurrent inserts of new keys may briefly exceed `capacity`. See
+    /// [`len`](HashMap::len) for details.
     ///
     /// # Examples
     ///
-    /// ```rust
-    /// use papaya::{HashMap, Operation, Compute};
+    /// ```
+    /// use papaya::HashMap;
     ///
     /// let map = HashMap::new();
     /// let map = map.pin();
+    /// assert_eq!(map.insert_bounded("a", 1, 1), Ok(None));
+    /// assert_eq!(map.insert_bounded("a", 2, 1), Ok(Some(&1)));
     ///
-    /// let compute = |entry| match entry {
-    ///     // Remove the value if it is even.
-    ///     Some((_key, value)) if value % 2 == 0 => {
-    ///         Operation::Remove
-    ///     }
+    /// // The map is full, evict an entry and retry.
+    /// let rejected = map.insert_bounded("b", 3, 1).unwrap_err();
+    /// map.remove("a");
+    /// assert_eq!(map.insert_bounded(rejected.key, rejected.value, 1), Ok(None));
+    /// ```
+    #[inline]
+    pub fn insert_bounded<'g>(
+        &self,
+        key: K,
+        value: V,
+        capacity: usize,
+        guard: &'g impl Guard,
+    ) -> Result<Option<&'g V>, CapacityError<K, V>> {
+        let guard = self.raw.verify(guard);
+
+        // Only inserts of new keys increase the length of the map.
+        if self.raw.len() >= capacity && self.raw.get(&key, guard).is_none() {
+            return Err(CapacityError { key, value });
+        }
+
+        match self.raw.insert(key, value, true, guard) {
+            InsertResult::Inserted(_) => Ok(None),
+            InsertResult::Replaced(value) => Ok(Some(value)),
+            InsertResult::Error { .. } => unreachable!(),
+        }
+    }
+
+    /// Inserts a key-value pair into the map, evicting entries chosen by `select_victim` to
+    /// keep the map within `capacity` entries.
+    ///
+    /// This extends [`insert_bounded`](HashMap::insert_bounded) with the eviction plumbing for
+    /// building caches. If inserting a new key would exceed `capacity`, `select_victim` is called
+    /// to choose a key to evict, such as the least recently used key in a structure maintained
+    /// by the caller. The victim is removed, `on_evict` is called with the evicted entry, and the
+    /// insert is retried. This repeats until the insert succeeds, or `select_victim` returns `None`,
+    /// in which case a [`CapacityError`] containing the key and value is returned. Replacing the
+    /// value of an existing key never evicts an entry.
+    ///
+    /// If the selected victim is not present in the map, for example because it was concurrently
+    /// removed, the insert is retried without calling `on_evict`. The eviction policy is fully
+    /// controlled by the caller, so `select_victim` must eventually return a key that is present,
+    /// or `None`. Similar to `insert_bounded`, the capacity is a soft bound, and concurrent inserts
+    /// may briefly exceed it.
     ///
-    ///     // Increment the value if it is odd.
-    ///     Some((_key, value)) => {
-    ///         Operation::Insert(value + 1)
-    ///     }
+    /// # Examples
     ///
-    ///     // Do nothing if the key does not exist
-    ///     None => Operation::Abort(()),
-    /// };
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::collections::VecDeque;
+    /// use std::sync::Mutex;
     ///
-    /// assert_eq!(map.compute('A', compute), Compute::Aborted(()));
+    /// // A FIFO eviction policy.
+    /// let queue = Mutex::new(VecDeque::new());
+    /// let map = HashMap::new();
+    /// let map = map.pin();
     ///
-    /// map.insert('A', 1);
-    /// assert_eq!(map.compute('A', compute), Compute::Updated {
-    ///     old: (&'A', &1),
-    ///     new: (&'A', &2),
-    /// });
-    /// assert_eq!(map.compute('A', compute), Compute::Removed(&'A', &2));
+    /// for (key, value) in [("a", 1), ("b", 2), ("c", 3)] {
+    ///     queue.lock().unwrap().push_back(key);
+    ///     let result = map.insert_evicting(
+    ///         key,
+    ///         value,
+    ///         2,
+    ///         || queue.lock().unwrap().pop_front(),
+    ///         |key, value| println!("evicted {key}: {value}"),
+    ///     );
+    ///     assert!(result.is_ok());
+    /// }
+    ///
+    /// assert_eq!(map.len(), 2);
+    /// assert_eq!(map.get("a"), None);
     /// ```
     #[inline]
-    pub fn compute<'g, F, T>(
+    pub fn insert_evicting<'g, F, E>(
         &self,
         key: K,
-        compute: F,
+        value: V,
+        capacity: usize,
+        mut select_victim: F,
+        mut on_evict: E,
         guard: &'g impl Guard,
-    ) -> Compute<'g, K, V, T>
+    ) -> Result<Option<&'g V>, CapacityError<K, V>>
     where
-        F: FnMut(Option<(&'g K, &'g V)>) -> Operation<V, T>,
+        F: FnMut() -> Option<K>,
+        E: FnMut(&K, &V),
     {
-        self.raw.compute(key, compute, self.raw.verify(guard))
+        let mut rejected = CapacityError { key, value };
+
+        loop {
+            rejected = match self.insert_bounded(rejected.key, rejected.value, capacity, guard) {
+                Err(rejected) => rejected,
+                inserted => return inserted,
+            };
+
+            // Evict the selected victim before retrying.
+            let Some(victim) = select_victim() else {
+                return Err(rejected);
+            };
+
+            if let Some((key, value)) = self.raw.remove(&victim, self.raw.verify(guard)) {
+                on_evict(key, value);
+            }
+        }
     }
 
-    /// Removes a key from the map, returning the value at the key if the key
-    /// was previously in the map.
+    /// Inserts a key-value pair into the map, unless doing so would resize the table.
     ///
-    /// The key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the key type.
+    /// This behaves like [`insert`](HashMap::insert), except that it never allocates a table,
+    /// triggers a resize, or helps with an in-progress resize (which would block in
+    /// [`ResizeMode::Blocking`]). If the entry does not fit in the current table, a
+    /// [`WouldResize`] error containing the key and value is returned instead, so that the insert
+    /// can be deferred to another thread. Combined with [`reserve`](HashMap::reserve), this
+    /// allows threads with real-time requirements to insert without ever resizing the table.
+    ///
+    /// Note that the operation may still perform bounded probe work, up to the probe limit of
+    /// the table. Additionally, every entry is a separate allocation, so inserting still
+    /// allocates the entry itself, unless it can be reused from the thread-local pool enabled
+    /// with [`HashMapBuilder::entry_pool`].
     ///
     /// # Examples
     ///
@@ -814,28 +1647,39 @@ where
     /// use papaya::HashMap;
     ///
     /// let map = HashMap::new();
-    /// map.pin().insert(1, "a");
-    /// assert_eq!(map.pin().remove(&1), Some(&"a"));
-    /// assert_eq!(map.pin().remove(&1), None);
+    /// let map = map.pin();
+    ///
+    /// // The table has not been allocated yet.
+    /// let rejected = map.try_insert_no_resize("a", 1).unwrap_err();
+    ///
+    /// // Reserve capacity off the critical path.
+    /// map.reserve(16);
+    /// assert_eq!(map.try_insert_no_resize(rejected.key, rejected.value), Ok(None));
+    /// assert_eq!(map.try_insert_no_resize("a", 2), Ok(Some(&1)));
     /// ```
     #[inline]
-    pub fn remove<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> Option<&'g V>
-    where
-        K: 'g,
-        Q: Equivalent<K> + Hash + ?Sized,
-    {
-        match self.raw.remove(key, self.raw.verify(guard)) {
-            Some((_, value)) => Some(value),
-            None => None,
-        }
+    pub fn try_insert_no_resize<'g>(
+        &self,
+        key: K,
+        value: V,
+        guard: &'g impl Guard,
+    ) -> Result<Option<&'g V>, WouldResize<K, V>> {
+        self.raw
+            .insert_no_resize(key, value, self.raw.verify(guard))
+            .map_err(|(key, value)| WouldResize { key, value })
     }
 
-    /// Removes a key from the map, returning the stored key and value if the
-    /// key was previously in the map.
+    /// Inserts the key-value pairs from an iterator, assuming that every key is distinct and
+    /// not already present in the map.
     ///
-    /// The key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the key type.
+    /// This is a fast path for bulk-loading trusted data, such as a sorted export from a
+    /// database. Space for the lower bound of the iterator's size hint is reserved up front,
+    /// and the duplicate check performed by [`insert`](HashMap::insert) is skipped for each pair.
+    ///
+    /// If the precondition is violated, the behavior is unspecified but safe: the map will
+    /// contain duplicate entries for the same key, lookups may return any one of them, and
+    /// [`len`](HashMap::len) will count each duplicate separately. In debug builds, this
+    /// method panics if a key is already present in the map.
     ///
     /// # Examples
     ///
@@ -843,50 +1687,82 @@ where
     /// use papaya::HashMap;
     ///
     /// let map = HashMap::new();
-    /// map.pin().insert(1, "a");
-    /// assert_eq!(map.pin().get(&1), Some(&"a"));
-    /// assert_eq!(map.pin().remove_entry(&1), Some((&1, &"a")));
-    /// assert_eq!(map.pin().remove(&1), None);
+    /// map.extend_unique((0..100).map(|i| (i, i * 2)), &map.guard());
+    ///
+    /// assert_eq!(map.len(), 100);
+    /// assert_eq!(map.pin().get(&10), Some(&20));
     /// ```
     #[inline]
-    pub fn remove_entry<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> Option<(&'g K, &'g V)>
+    pub fn extend_unique<I>(&self, iter: I, guard: &impl Guard)
     where
-        K: 'g,
-        Q: Equivalent<K> + Hash + ?Sized,
+        I: IntoIterator<Item = (K, V)>,
     {
-        self.raw.remove(key, self.raw.verify(guard))
+        let guard = self.raw.verify(guard);
+        let iter = iter.into_iter();
+        self.raw.reserve(iter.size_hint().0, guard);
+
+        for (key, value) in iter {
+            self.raw.insert_unique(key, value, guard);
+        }
     }
 
-    /// Tries to reserve capacity for `additional` more elements to be inserted
-    /// in the `HashMap`.
-    ///
-    /// After calling this method, the table should be able to hold at least `capacity` elements
-    /// before resizing. However, the capacity is an estimate, and the table may prematurely resize
-    /// due to poor hash distribution. The collection may also reserve more space to avoid frequent
-    /// reallocations.
+    /// Inserts a key-value pair into the map, using a precomputed hash.
     ///
-    /// # Panics
+    /// The provided `hash` *must* be equal to the hash of `key` produced by the map's
+    /// [`hasher`](HashMap::hasher). Otherwise, the entry may be inserted in the wrong
+    /// position and become inaccessible to other operations. This is checked with a
+    /// debug assertion.
     ///
-    /// Panics if the new allocation size overflows `usize`.
+    /// See [`HashMap::insert`] and [`HashMap::get_with_hash`] for details.
     ///
     /// # Examples
     ///
     /// ```
     /// use papaya::HashMap;
+    /// use std::hash::BuildHasher;
     ///
-    /// let map: HashMap<&str, i32> = HashMap::new();
-    /// map.pin().reserve(10);
+    /// let map = HashMap::new();
+    /// let hash = map.hasher().hash_one(&37);
+    ///
+    /// assert_eq!(map.pin().insert_with_hash(hash, 37, "a"), None);
+    /// assert_eq!(map.pin().insert_with_hash(hash, 37, "b"), Some(&"a"));
+    /// assert_eq!(map.pin().get(&37), Some(&"b"));
     /// ```
     #[inline]
-    pub fn reserve(&self, additional: usize, guard: &impl Guard) {
-        self.raw.reserve(additional, self.raw.verify(guard))
+    pub fn insert_with_hash<'g>(
+        &self,
+        hash: u64,
+        key: K,
+        value: V,
+        guard: &'g impl Guard,
+    ) -> Option<&'g V> {
+        debug_assert_eq!(
+            hash,
+            self.raw.hasher.hash_one(&key),
+            "provided hash does not match the hash of the key"
+        );
+
+        match self
+            .raw
+            .insert_with_hash(hash, key, value, true, self.raw.verify(guard))
+        {
+            InsertResult::Inserted(_) => None,
+            InsertResult::Replaced(value) => Some(value),
+            InsertResult::Error { .. } => unreachable!(),
+        }
     }
 
-    /// Clears the map, removing all key-value pairs.
+    /// Tries to insert a key-value pair into the map, and returns
+    /// a reference to the value that was inserted.
     ///
-    /// Note that this method will block until any in-progress resizes are
-    /// completed before proceeding. See the [consistency](crate#consistency)
-    /// section for details.
+    /// If the map already had this key present, nothing is updated, and
+    /// an error containing the existing value is returned.
+    ///
+    /// The check and the insertion are performed atomically. When the key is
+    /// already present, the value that was not inserted is moved back to the caller
+    /// through [`OccupiedError::not_inserted`], so it is never lost or cloned if the
+    /// insertion races with another thread. This makes `try_insert` suitable for
+    /// interning, where exactly one of several concurrent insertions must win.
     ///
     /// # Examples
     ///
@@ -894,601 +1770,4847 @@ where
     /// use papaya::HashMap;
     ///
     /// let map = HashMap::new();
+    /// let map = map.pin();
     ///
-    /// map.pin().insert(1, "a");
-    /// map.pin().clear();
-    /// assert!(map.pin().is_empty());
+    /// assert_eq!(map.try_insert(37, "a").unwrap(), &"a");
+    ///
+    /// let err = map.try_insert(37, "b").unwrap_err();
+    /// assert_eq!(err.current, &"a");
+    /// assert_eq!(err.not_inserted, "b");
     /// ```
     #[inline]
-    pub fn clear(&self, guard: &impl Guard) {
-        self.raw.clear(self.raw.verify(guard))
+    pub fn try_insert<'g>(
+        &self,
+        key: K,
+        value: V,
+        guard: &'g impl Guard,
+    ) -> Result<&'g V, OccupiedError<'g, V>> {
+        // Safety: Checked the guard above.
+        match self.raw.insert(key, value, false, self.raw.verify(guard)) {
+            InsertResult::Inserted(value) => Ok(value),
+            InsertResult::Error {
+                current,
+                not_inserted,
+            } => Err(OccupiedError {
+                current,
+                not_inserted,
+            }),
+            InsertResult::Replaced(_) => unreachable!(),
+        }
     }
 
-    /// Retains only the elements specified by the predicate.
+    /// Inserts a key-value pair into the map, unless the key is already mapped to an equal value.
     ///
-    /// In other words, remove all pairs `(k, v)` for which `f(&k, &v)` returns `false`.
-    /// The elements are visited in unsorted (and unspecified) order.
-    ///
-    /// Note the function may be called more than once for a given key if its value is
-    /// concurrently modified during removal.
+    /// Returns `true` if the value was written, or `false` if the existing value compared equal
+    /// to `value` and the map was left unchanged. Skipping redundant writes avoids contention and
+    /// the cost of reclaiming the replaced value, which is useful for caches that mirror an
+    /// upstream source.
     ///
-    /// Additionally, this method will block until any in-progress resizes are
-    /// completed before proceeding. See the [consistency](crate#consistency)
-    /// section for details.
+    /// The comparison and the write are performed atomically, so a concurrently inserted value
+    /// that differs is never overwritten based on a stale comparison. If the entry is modified
+    /// concurrently, the comparison is retried against the new value. Note that `value` is cloned
+    /// for every attempted write, as a fresh value is needed if the write has to be retried.
     ///
     /// # Examples
     ///
     /// ```
     /// use papaya::HashMap;
     ///
-    /// let mut map: HashMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
-    /// map.pin().retain(|&k, _| k % 2 == 0);
-    /// assert_eq!(map.len(), 4);
+    /// let map = HashMap::new();
+    /// assert!(map.pin().insert_if_changed("a", 1));
+    /// assert!(!map.pin().insert_if_changed("a", 1));
+    /// assert!(map.pin().insert_if_changed("a", 2));
+    /// assert_eq!(map.pin().get("a"), Some(&2));
     /// ```
     #[inline]
-    pub fn retain<F>(&mut self, f: F, guard: &impl Guard)
+    pub fn insert_if_changed(&self, key: K, value: V, guard: &impl Guard) -> bool
     where
-        F: FnMut(&K, &V) -> bool,
+        V: PartialEq + Clone,
     {
-        self.raw.retain(f, self.raw.verify(guard))
+        let compute = |entry: Option<(&K, &V)>| match entry {
+            // The current value is unchanged, skip the write.
+            Some((_, current)) if *current == value => Operation::Abort(()),
+
+            // Otherwise, write the new value.
+            _ => Operation::Insert(value.clone()),
+        };
+
+        !matches!(
+            self.raw.compute(key, compute, self.raw.verify(guard)),
+            Compute::Aborted(())
+        )
     }
 
-    /// An iterator visiting all key-value pairs in arbitrary order.
-    /// The iterator element type is `(&K, &V)`.
+    /// Tries to insert a key and value computed from a closure into the map,
+    /// and returns a reference to the value that was inserted.
     ///
-    /// Note that this method will block until any in-progress resizes are
-    /// completed before proceeding. See the [consistency](crate#consistency)
-    /// section for details.
+    /// If the map already had this key present, nothing is updated, and
+    /// the existing value is returned.
     ///
     /// # Examples
     ///
     /// ```
     /// use papaya::HashMap;
     ///
-    /// let map = HashMap::from([
-    ///     ("a", 1),
-    ///     ("b", 2),
-    ///     ("c", 3),
-    /// ]);
+    /// let map = HashMap::new();
+    /// let map = map.pin();
     ///
-    /// for (key, val) in map.pin().iter() {
-    ///     println!("key: {key} val: {val}");
-    /// }
+    /// assert_eq!(map.try_insert_with(37, || "a").unwrap(), &"a");
+    ///
+    /// let current = map.try_insert_with(37, || "b").unwrap_err();
+    /// assert_eq!(current, &"a");
+    /// ```
     #[inline]
-    pub fn iter<'g, G>(&self, guard: &'g G) -> Iter<'g, K, V, G>
+    pub fn try_insert_with<'g, F>(
+        &self,
+        key: K,
+        f: F,
+        guard: &'g impl Guard,
+    ) -> Result<&'g V, &'g V>
     where
-        G: Guard,
+        F: FnOnce() -> V,
+        K: 'g,
     {
-        Iter {
-            raw: self.raw.iter(self.raw.verify(guard)),
-        }
+        self.raw.try_insert_with(key, f, self.raw.verify(guard))
     }
 
-    /// An iterator visiting all keys in arbitrary order.
-    /// The iterator element type is `&K`.
+    /// Returns a reference to the value corresponding to the key, or inserts a default value.
     ///
-    /// Note that this method will block until any in-progress resizes are
-    /// completed before proceeding. See the [consistency](crate#consistency)
-    /// section for details.
+    /// If the given key is present, the corresponding value is returned. If it is not present,
+    /// the provided `value` is inserted, and a reference to the newly inserted value is returned.
     ///
     /// # Examples
     ///
     /// ```
     /// use papaya::HashMap;
     ///
-    /// let map = HashMap::from([
-    ///     ("a", 1),
-    ///     ("b", 2),
-    ///     ("c", 3),
-    /// ]);
-    ///
-    /// for key in map.pin().keys() {
-    ///     println!("{key}");
-    /// }
+    /// let map = HashMap::new();
+    /// assert_eq!(map.pin().get_or_insert("a", 3), &3);
+    /// assert_eq!(map.pin().get_or_insert("a", 6), &3);
     /// ```
     #[inline]
-    pub fn keys<'g, G>(&self, guard: &'g G) -> Keys<'g, K, V, G>
-    where
-        G: Guard,
-    {
-        Keys {
-            iter: self.iter(guard),
+    pub fn get_or_insert<'g>(&self, key: K, value: V, guard: &'g impl Guard) -> &'g V {
+        // Note that we use `try_insert` instead of `compute` or `get_or_insert_with` here, as it
+        // allows us to avoid the closure indirection.
+        match self.try_insert(key, value, guard) {
+            Ok(inserted) => inserted,
+            Err(OccupiedError { current, .. }) => current,
         }
     }
 
-    /// An iterator visiting all values in arbitrary order.
-    /// The iterator element type is `&V`.
-    ///
-    /// Note that this method will block until any in-progress resizes are
-    /// completed before proceeding. See the [consistency](crate#consistency)
-    /// section for details.
+    /// Returns a reference to the value corresponding to the key, or inserts a default value
+    /// computed from a closure.
+    ///
+    /// If the given key is present, the corresponding value is returned. If it is not present,
+    /// the value computed from `f` is inserted, and a reference to the newly inserted value is
+    /// returned.
+    ///
     ///
     /// # Examples
     ///
     /// ```
     /// use papaya::HashMap;
     ///
-    /// let map = HashMap::from([
-    ///     ("a", 1),
-    ///     ("b", 2),
-    ///     ("c", 3),
-    /// ]);
+    /// let map = HashMap::new();
+    /// assert_eq!(map.pin().get_or_insert_with("a", || 3), &3);
+    /// assert_eq!(map.pin().get_or_insert_with("a", || 6), &3);
+    /// ```
+    #[inline]
+    pub fn get_or_insert_with<'g, F>(&self, key: K, f: F, guard: &'g impl Guard) -> &'g V
+    where
+        F: FnOnce() -> V,
+        K: 'g,
+    {
+        self.raw.get_or_insert_with(key, f, self.raw.verify(guard))
+    }
+
+    /// Returns a reference to the value corresponding to the key, or inserts a default value
+    /// computed from a closure that is given a reference to the key.
     ///
-    /// for value in map.pin().values() {
-    ///     println!("{value}");
+    /// This behaves like [`get_or_insert_with`](HashMap::get_or_insert_with), but allows the
+    /// default value to depend on the key without cloning or capturing it separately. The
+    /// closure is only called if the key is not present. Note that if the key is inserted
+    /// concurrently after the closure is called, the existing value is returned and the
+    /// computed value is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// assert_eq!(map.pin().get_or_insert_with_key("abc", |key| key.len()), &3);
+    /// assert_eq!(map.pin().get_or_insert_with_key("abc", |_| 0), &3);
+    /// ```
+    #[inline]
+    pub fn get_or_insert_with_key<'g, F>(&self, key: K, f: F, guard: &'g impl Guard) -> &'g V
+    where
+        F: FnOnce(&K) -> V,
+        K: 'g,
+    {
+        self.raw
+            .get_or_insert_with_key(key, f, self.raw.verify(guard))
+    }
+
+    /// Returns a reference to the value corresponding to the key, or inserts a value
+    /// computed from an asynchronous closure.
+    ///
+    /// This is the asynchronous equivalent of [`get_or_insert_with`](HashMap::get_or_insert_with).
+    /// If the key is not present, the future returned by `f` is awaited and its output inserted
+    /// into the map. Concurrent callers for the same missing key do not run their own closures,
+    /// and instead wait for the in-progress initialization to complete. This avoids redundant
+    /// computation when many tasks miss on the same key at once.
+    ///
+    /// If the initializing future is cancelled or panics, one of the waiting callers will
+    /// retry the initialization with its own closure. Note that the key must be cloned to
+    /// track the in-progress initialization.
+    ///
+    /// This method is only available with the `async` feature.
+    ///
+    /// # Guards
+    ///
+    /// The guard remains active across the `.await` points of both the initializing future
+    /// and any wait on a concurrent initialization. This has two consequences:
+    ///
+    /// - Memory retired by any map sharing the guard's collector cannot be reclaimed until
+    ///   the returned future completes, so a slow initialization delays reclamation.
+    /// - A [`LocalGuard`] held across an `.await` point would make the future `!Send`, so
+    ///   this method only accepts an [`OwnedGuard`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// # let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// # runtime.block_on(async {
+    /// let map = HashMap::new();
+    /// let guard = map.owned_guard();
+    ///
+    /// let value = map.get_or_insert_future("a", || async { 1 }, &guard).await;
+    /// assert_eq!(value, &1);
+    ///
+    /// // The closure is not called if the key is present.
+    /// let value = map.get_or_insert_future("a", || async { 2 }, &guard).await;
+    /// assert_eq!(value, &1);
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn get_or_insert_future<'g, F, Fut>(
+        &self,
+        key: K,
+        f: F,
+        guard: &'g OwnedGuard<'_>,
+    ) -> &'g V
+    where
+        K: Clone + 'g,
+        V: 'g,
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = V>,
+    {
+        self.get_or_insert_future_inner(key, f, self.raw.verify(guard))
+            .await
+    }
+
+    // Implementation of `get_or_insert_future` for a verified owned guard.
+    #[cfg(feature = "async")]
+    async fn get_or_insert_future_inner<'g, F, Fut>(
+        &self,
+        key: K,
+        f: F,
+        guard: &'g MapGuard<OwnedGuard<'_>>,
+    ) -> &'g V
+    where
+        K: Clone + 'g,
+        V: 'g,
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = V>,
+    {
+        loop {
+            if let Some((_, value)) = self.raw.get(&key, guard) {
+                return value;
+            }
+
+            match self.pending.claim(&key) {
+                // Wait for the in-progress initialization to complete, then try again.
+                Err(signal) => signal.wait().await,
+
+                Ok(claim) => {
+                    // The key may have been initialized before we claimed it.
+                    if let Some((_, value)) = self.raw.get(&key, guard) {
+                        return value;
+                    }
+
+                    let value = f().await;
+                    let value = self.raw.get_or_insert_with(key, || value, guard);
+
+                    // Wake any waiters after the value has been inserted.
+                    drop(claim);
+                    return value;
+                }
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// As it takes `&mut self`, no guard is required and the value can be modified in place.
+    /// See the [crate-level documentation](crate#exclusive-access) for details about exclusive
+    /// access to the map.
+    ///
+    /// Values may have been pinned with [`get_pin`](HashMap::get_pin), so this requires `V: Unpin`.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let mut map = HashMap::new();
+    /// map.pin().insert("a", 1);
+    ///
+    /// *map.get_mut("a").unwrap() += 1;
+    /// assert_eq!(map.get_mut("b"), None);
+    /// assert_eq!(map.pin().get("a"), Some(&2));
+    /// ```
+    ///
+    /// Values that are `!Unpin` cannot be borrowed mutably:
+    ///
+    /// ```compile_fail
+    /// use papaya::HashMap;
+    /// use std::marker::PhantomPinned;
+    ///
+    /// let mut map = HashMap::new();
+    /// map.pin().insert(1, PhantomPinned);
+    /// let _ = map.get_mut(&1);
+    /// ```
+    #[inline]
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+        V: Unpin,
+    {
+        self.raw.get_mut(key)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key, or inserts a default
+    /// value computed from a closure.
+    ///
+    /// This is the exclusive-access equivalent of [`get_or_insert_with`](HashMap::get_or_insert_with),
+    /// similar to `entry(key).or_insert_with(f)` on a standard `HashMap`. As it takes `&mut self`,
+    /// no guard is required and the returned value can be modified in place.
+    ///
+    /// Values may have been pinned with [`get_pin`](HashMap::get_pin), so this requires `V: Unpin`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let mut map = HashMap::new();
+    /// for word in ["a", "b", "a"] {
+    ///     *map.get_mut_or_insert_with(word, || 0) += 1;
     /// }
+    ///
+    /// assert_eq!(map.pin().get("a"), Some(&2));
+    /// assert_eq!(map.pin().get("b"), Some(&1));
+    /// ```
+    ///
+    /// Values that are `!Unpin` cannot be borrowed mutably:
+    ///
+    /// ```compile_fail
+    /// use papaya::HashMap;
+    /// use std::marker::PhantomPinned;
+    ///
+    /// let mut map = HashMap::new();
+    /// map.pin().insert(1, PhantomPinned);
+    /// let _ = map.get_mut_or_insert_with(2, || PhantomPinned);
     /// ```
     #[inline]
-    pub fn values<'g, G>(&self, guard: &'g G) -> Values<'g, K, V, G>
+    pub fn get_mut_or_insert_with<F>(&mut self, key: K, f: F) -> &mut V
     where
-        G: Guard,
+        F: FnOnce() -> V,
+        V: Unpin,
     {
-        Values {
-            iter: self.iter(guard),
+        self.raw.get_mut_or_insert_with(key, f)
+    }
+
+    /// Updates an existing entry atomically.
+    ///
+    /// If the value for the specified `key` is present, the new value is computed and stored the
+    /// using the provided update function, and the new value is returned. Otherwise, `None`
+    /// is returned.
+    ///
+    ///
+    /// The update function is given the current value associated with the given key and returns the
+    /// new value to be stored. The operation is applied atomically only if the state of the entry remains
+    /// the same, meaning that it is not concurrently modified in any way. If the entry is
+    /// modified, the operation is retried with the new entry, similar to a traditional [compare-and-swap](https://en.wikipedia.org/wiki/Compare-and-swap)
+    /// operation.
+    ///
+    /// Note that the `update` function should be pure as it may be called multiple times, and the output
+    /// for a given entry may be memoized across retries. The update function may access other keys in the map,
+    /// see the [crate-level documentation](crate#atomic-operations) for details about reentrancy.
+    ///
+    /// Values are never cloned by the map. The update function only receives a reference to the
+    /// current value, and the new value it returns is allocated separately and swapped into the entry
+    /// atomically. The old value is retired and dropped once it is no longer accessible to any
+    /// concurrent readers. This means `V` does not need to implement [`Clone`], and large values can
+    /// be updated without copying any more of the old value than the update function reads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert("a", 1);
+    /// assert_eq!(map.pin().get(&"a"), Some(&1));
+    ///
+    /// map.pin().update("a", |v| v + 1);
+    /// assert_eq!(map.pin().get(&"a"), Some(&2));
+    /// ```
+    #[inline]
+    pub fn update<'g, F>(&self, key: K, update: F, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        F: Fn(&V) -> V,
+        K: 'g,
+    {
+        self.raw.update(key, update, self.raw.verify(guard))
+    }
+
+    /// Updates an existing entry atomically, using a function that is also given the key.
+    ///
+    /// If the value for the specified `key` is present, the new value is computed from the
+    /// key and current value using the provided update function, and the new value is returned.
+    /// Otherwise, `None` is returned. The update function receives the key stored in the map,
+    /// so it does not need to capture a separate copy of the key.
+    ///
+    /// The update is retried with the same compare-and-swap semantics as
+    /// [`update`](HashMap::update), so the function should be pure as it may be called
+    /// multiple times.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(3, 10);
+    ///
+    /// assert_eq!(map.pin().update_with_key(3, |k, v| k * v), Some(&30));
+    /// assert_eq!(map.pin().update_with_key(4, |k, v| k * v), None);
+    /// ```
+    #[inline]
+    pub fn update_with_key<'g, F>(&self, key: K, update: F, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        F: Fn(&K, &V) -> V,
+        K: 'g,
+    {
+        let compute = |entry| match entry {
+            // There is nothing to update.
+            None => Operation::Abort(()),
+            // Perform the update.
+            Some((key, value)) => Operation::Insert(update(key, value)),
+        };
+
+        match self.raw.compute(key, compute, self.raw.verify(guard)) {
+            // Return the updated value.
+            Compute::Updated {
+                new: (_, value), ..
+            } => Some(value),
+
+            // There was nothing to update.
+            Compute::Aborted(_) => None,
+
+            _ => unreachable!(),
         }
     }
-}
 
-/// An operation to perform on given entry in a [`HashMap`].
-///
-/// See [`HashMap::compute`] for details.
-#[derive(Debug, PartialEq, Eq)]
-pub enum Operation<V, T> {
-    /// Insert the given value.
-    Insert(V),
+    /// Updates an existing entry atomically with a fallible update function.
+    ///
+    /// If the value for the specified `key` is present, the update function is called with the
+    /// current value. If it returns `Ok`, the new value is stored and `Ok(Some(value))` is
+    /// returned. If it returns `Err`, the entry is left unchanged and the error is returned.
+    /// If the key is not present, the update function is never called and `Ok(None)` is returned.
+    ///
+    /// The update is retried with the same compare-and-swap semantics as
+    /// [`update`](HashMap::update), so the function should be pure as it may be called
+    /// multiple times. If the entry is concurrently modified after the function fails, the
+    /// error is still returned, as it reflects the state of the entry at some point during
+    /// the operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert("a", 1u8);
+    ///
+    /// let increment = |v: &u8| v.checked_add(1).ok_or("overflow");
+    /// assert_eq!(map.pin().try_update("a", increment), Ok(Some(&2)));
+    /// assert_eq!(map.pin().try_update("b", increment), Ok(None));
+    ///
+    /// map.pin().insert("a", u8::MAX);
+    /// assert_eq!(map.pin().try_update("a", increment), Err("overflow"));
+    /// assert_eq!(map.pin().get("a"), Some(&u8::MAX));
+    /// ```
+    #[inline]
+    pub fn try_update<'g, F, E>(
+        &self,
+        key: K,
+        update: F,
+        guard: &'g impl Guard,
+    ) -> Result<Option<&'g V>, E>
+    where
+        F: Fn(&V) -> Result<V, E>,
+        K: 'g,
+    {
+        let compute = |entry: Option<(&K, &V)>| match entry {
+            // There is nothing to update.
+            None => Operation::Abort(None),
+            // Perform the update.
+            Some((_, value)) => match update(value) {
+                Ok(value) => Operation::Insert(value),
+                Err(err) => Operation::Abort(Some(err)),
+            },
+        };
 
-    /// Remove the entry from the map.
-    Remove,
+        match self.raw.compute(key, compute, self.raw.verify(guard)) {
+            // Return the updated value.
+            Compute::Updated {
+                new: (_, value), ..
+            } => Ok(Some(value)),
 
-    /// Abort the operation with the given value.
-    Abort(T),
-}
+            // There was nothing to update.
+            Compute::Aborted(None) => Ok(None),
 
-/// The result of a [`compute`](HashMap::compute) operation.
-///
-/// Contains information about the [`Operation`] that was performed.
-#[derive(Debug, PartialEq, Eq)]
-pub enum Compute<'g, K, V, T> {
-    /// The given entry was inserted.
-    Inserted(&'g K, &'g V),
+            // The update function failed.
+            Compute::Aborted(Some(err)) => Err(err),
 
-    /// The entry was updated.
-    Updated {
-        /// The entry that was replaced.
-        old: (&'g K, &'g V),
+            _ => unreachable!(),
+        }
+    }
 
-        /// The entry that was inserted.
-        new: (&'g K, &'g V),
-    },
+    /// Updates an existing entry atomically by mutating a copy of the current value.
+    ///
+    /// If the value for the specified `key` is present, it is cloned into a temporary buffer and
+    /// passed to `update`, which can modify it in place. The modified value is then stored with the
+    /// same compare-and-swap semantics as [`update`](HashMap::update), and the new value is returned.
+    /// Otherwise, `None` is returned.
+    ///
+    /// This is more convenient than `update` for large values that are cheaper to patch than to
+    /// rebuild. However, note that the current value is cloned on every attempt. If the entry is
+    /// concurrently modified, the operation is retried with a fresh clone of the new value, so
+    /// heavily contended keys may pay for the clone many times. As with `update`, the function
+    /// may be called multiple times and should only modify the buffer it is given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert("a", vec![1, 2, 3]);
+    ///
+    /// map.pin().update_in_place("a", |v| v.push(4));
+    /// assert_eq!(map.pin().get(&"a"), Some(&vec![1, 2, 3, 4]));
+    /// assert_eq!(map.pin().update_in_place("b", |v| v.push(4)), None);
+    /// ```
+    #[inline]
+    pub fn update_in_place<'g, F>(
+        &self,
+        key: K,
+        mut update: F,
+        guard: &'g impl Guard,
+    ) -> Option<&'g V>
+    where
+        F: FnMut(&mut V),
+        V: Clone,
+        K: 'g,
+    {
+        let update = |value: &V| {
+            let mut value = value.clone();
+            update(&mut value);
+            value
+        };
+
+        self.raw.update(key, update, self.raw.verify(guard))
+    }
+
+    /// Updates an existing entry or inserts a default value.
+    ///
+    /// If the value for the specified `key` is present, the new value is computed and stored the
+    /// using the provided update function, and the new value is returned. Otherwise, the provided
+    /// `value` is inserted into the map, and a reference to the newly inserted value is returned.
+    ///
+    /// See [`HashMap::update`] for details about how atomic updates are performed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// assert_eq!(*map.pin().update_or_insert("a", |i| i + 1, 0), 0);
+    /// assert_eq!(*map.pin().update_or_insert("a", |i| i + 1, 0), 1);
+    /// ```
+    #[inline]
+    pub fn update_or_insert<'g, F>(
+        &self,
+        key: K,
+        update: F,
+        value: V,
+        guard: &'g impl Guard,
+    ) -> &'g V
+    where
+        F: Fn(&V) -> V,
+        K: 'g,
+    {
+        self.update_or_insert_with(key, update, || value, guard)
+    }
+
+    /// Updates an existing entry or inserts a default value computed from a closure.
+    ///
+    /// If the value for the specified `key` is present, the new value is computed and stored the
+    /// using the provided update function, and the new value is returned. Otherwise, the value
+    /// computed by `f` is inserted into the map, and a reference to the newly inserted value is
+    /// returned.
+    ///
+    /// See [`HashMap::update`] for details about how atomic updates are performed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// assert_eq!(*map.pin().update_or_insert_with("a", |i| i + 1, || 0), 0);
+    /// assert_eq!(*map.pin().update_or_insert_with("a", |i| i + 1, || 0), 1);
+    /// ```
+    #[inline]
+    pub fn update_or_insert_with<'g, U, F>(
+        &self,
+        key: K,
+        update: U,
+        f: F,
+        guard: &'g impl Guard,
+    ) -> &'g V
+    where
+        F: FnOnce() -> V,
+        U: Fn(&V) -> V,
+        K: 'g,
+    {
+        self.raw
+            .update_or_insert_with(key, update, f, self.raw.verify(guard))
+    }
+
+    /// Applies a delta to the value of each key, inserting a default value for any
+    /// key that is not present.
+    ///
+    /// For every `(key, delta)` pair, the new value is computed with `combine` from the current
+    /// value and the delta. If the key is not present, `combine` is instead called with
+    /// `default`, and the result is inserted into the map. This is useful for merging
+    /// thread-local accumulators into a shared map.
+    ///
+    /// Each key is updated atomically, with the same compare-and-swap semantics as
+    /// [`update`](HashMap::update), so concurrent deltas to the same key are never lost.
+    /// However, the batch as a whole is not atomic, and concurrent readers may observe
+    /// some deltas applied before others.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert("a", 1);
+    ///
+    /// let deltas = [("a", 2), ("b", 3), ("a", 4)];
+    /// map.apply_deltas(deltas, |v, d| v + d, 0, &map.guard());
+    ///
+    /// assert_eq!(map.pin().get("a"), Some(&7));
+    /// assert_eq!(map.pin().get("b"), Some(&3));
+    /// ```
+    #[inline]
+    pub fn apply_deltas<I, D, F>(&self, deltas: I, combine: F, default: V, guard: &impl Guard)
+    where
+        I: IntoIterator<Item = (K, D)>,
+        F: Fn(&V, &D) -> V,
+    {
+        let guard = self.raw.verify(guard);
+
+        for (key, delta) in deltas {
+            let compute = |entry: Option<(&K, &V)>| -> Operation<V, ()> {
+                match entry {
+                    // Apply the delta to the current value.
+                    Some((_, value)) => Operation::Insert(combine(value, &delta)),
+                    // Apply the delta to the default value.
+                    None => Operation::Insert(combine(&default, &delta)),
+                }
+            };
+
+            self.raw.compute(key, compute, guard);
+        }
+    }
+
+    /// Atomically replaces the value for a key with its default value, returning the
+    /// previous value.
+    ///
+    /// If the key is not present in the map, it is not inserted and `None` is returned. The
+    /// read and reset happen in a single atomic step, so any concurrent update is either
+    /// reflected in the returned value or applied to the reset value, and is never lost.
+    /// This makes it suitable for draining per-key accumulators. To take the value and remove
+    /// the entry instead, use [`remove`](HashMap::remove).
+    ///
+    /// See [`HashMap::compute`] for details about how atomic operations are performed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert("hits", 10);
+    ///
+    /// assert_eq!(map.pin().take_value("hits"), Some(&10));
+    /// assert_eq!(map.pin().get("hits"), Some(&0));
+    /// assert_eq!(map.pin().take_value("misses"), None);
+    /// ```
+    #[inline]
+    pub fn take_value<'g>(&self, key: K, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        K: 'g,
+        V: Default,
+    {
+        let compute = |entry: Option<(&K, &V)>| match entry {
+            // The key is not in the map.
+            None => Operation::Abort(()),
+            // Reset the value.
+            Some(_) => Operation::Insert(V::default()),
+        };
+
+        match self.raw.compute(key, compute, self.raw.verify(guard)) {
+            // Return the previous value.
+            Compute::Updated {
+                old: (_, value), ..
+            } => Some(value),
+
+            // The key was not present.
+            Compute::Aborted(_) => None,
+
+            _ => unreachable!(),
+        }
+    }
+
+    /// Atomically decrements the value for a key, removing the entry once it reaches zero.
+    ///
+    /// If the value for the specified `key` is greater than `amount`, it is decremented by
+    /// `amount` and a reference to the new value is returned. Otherwise, the entry is removed
+    /// and `None` is returned. In particular, if `amount` exceeds the current value, the entry
+    /// is removed rather than the value underflowing. If the key is not present in the map,
+    /// `None` is returned.
+    ///
+    /// This is useful for reference counts or token buckets that should be cleaned up once
+    /// exhausted. The decrement and removal happen in a single atomic operation, so concurrent
+    /// decrements of the same key are never lost and the entry is removed exactly once. See
+    /// [`HashMap::compute`] for details about how atomic operations are performed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert("tokens", 5);
+    ///
+    /// assert_eq!(map.pin().decrement_or_remove("tokens", 2), Some(&3));
+    /// assert_eq!(map.pin().decrement_or_remove("tokens", 10), None);
+    /// assert!(!map.pin().contains_key("tokens"));
+    /// ```
+    #[inline]
+    pub fn decrement_or_remove<'g>(&self, key: K, amount: V, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        K: 'g,
+        V: Copy + PartialOrd + Sub<Output = V>,
+    {
+        let compute = |entry: Option<(&K, &V)>| match entry {
+            // The key is not in the map.
+            None => Operation::Abort(()),
+            // The value would reach zero, remove the entry.
+            Some((_, &value)) if value <= amount => Operation::Remove,
+            // Perform the decrement.
+            Some((_, &value)) => Operation::Insert(value - amount),
+        };
+
+        match self.raw.compute(key, compute, self.raw.verify(guard)) {
+            // Return the decremented value.
+            Compute::Updated {
+                new: (_, value), ..
+            } => Some(value),
+
+            // The entry was removed, or was not present.
+            Compute::Removed(..) | Compute::Aborted(_) => None,
+
+            _ => unreachable!(),
+        }
+    }
+
+    /// Updates an entry with a compare-and-swap (CAS) function.
+    ///
+    /// This method allows you to perform complex operations on the map atomically. The `compute`
+    /// closure is given the current state of the entry and returns the operation that should be
+    /// performed. The operation is applied atomically only if the state of the entry remains the same,
+    /// meaning it is not concurrently modified in any way.
+    ///
+    /// Note that the `compute` function should be pure as it may be called multiple times, and
+    /// the output for a given entry may be memoized across retries.
+    ///
+    /// In most cases you can avoid this method and instead use a higher-level atomic operation.
+    /// See the [crate-level documentation](crate#atomic-operations) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use papaya::{HashMap, Operation, Compute};
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    ///
+    /// let compute = |entry| match entry {
+    ///     // Remove the value if it is even.
+    ///     Some((_key, value)) if value % 2 == 0 => {
+    ///         Operation::Remove
+    ///     }
+    ///
+    ///     // Increment the value if it is odd.
+    ///     Some((_key, value)) => {
+    ///         Operation::Insert(value + 1)
+    ///     }
+    ///
+    ///     // Do nothing if the key does not exist
+    ///     None => Operation::Abort(()),
+    /// };
+    ///
+    /// assert_eq!(map.compute('A', compute), Compute::Aborted(()));
+    ///
+    /// map.insert('A', 1);
+    /// assert_eq!(map.compute('A', compute), Compute::Updated {
+    ///     old: (&'A', &1),
+    ///     new: (&'A', &2),
+    /// });
+    /// assert_eq!(map.compute('A', compute), Compute::Removed(&'A', &2));
+    /// ```
+    #[inline]
+    pub fn compute<'g, F, T>(
+        &self,
+        key: K,
+        compute: F,
+        guard: &'g impl Guard,
+    ) -> Compute<'g, K, V, T>
+    where
+        F: FnMut(Option<(&'g K, &'g V)>) -> Operation<V, T>,
+    {
+        self.raw.compute(key, compute, self.raw.verify(guard))
+    }
+
+    /// Updates an entry with a compare-and-swap (CAS) function, returning the number
+    /// of times the operation was retried.
+    ///
+    /// Operations are retried when the entry is modified concurrently, or when the entry
+    /// is migrated to a new table during a resize. Note that retries do not necessarily
+    /// correspond to calls of the `compute` closure, as its output may be reused for retries
+    /// with the same input. A consistently high retry count indicates heavy contention on
+    /// the key.
+    ///
+    /// This method is intended for diagnostic purposes. See [`HashMap::compute`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::{Compute, HashMap, Operation};
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    ///
+    /// let (result, retries) = map.compute_verbose('A', |entry| match entry {
+    ///     Some((_, value)) => Operation::Insert(value + 1),
+    ///     None => Operation::Insert(0),
+    /// });
+    ///
+    /// assert_eq!(result, Compute::<_, _, ()>::Inserted(&'A', &0));
+    /// assert_eq!(retries, 0);
+    /// ```
+    #[inline]
+    pub fn compute_verbose<'g, F, T>(
+        &self,
+        key: K,
+        compute: F,
+        guard: &'g impl Guard,
+    ) -> (Compute<'g, K, V, T>, u32)
+    where
+        F: FnMut(Option<(&'g K, &'g V)>) -> Operation<V, T>,
+    {
+        self.raw
+            .compute_verbose(key, compute, self.raw.verify(guard))
+    }
+
+    /// Returns a handle to the entry for the given key, for performing multiple
+    /// operations on the same key.
+    ///
+    /// The hash of the key is computed once when the handle is created and reused by every
+    /// operation on the handle. Once an operation such as [`Entry::update`] or [`Entry::compute`]
+    /// has located the entry, the handle also remembers where it is stored in the table, so later
+    /// operations access the entry directly instead of probing for the key again. Note that the
+    /// handle does not lock the entry or keep it alive. Each operation is applied atomically,
+    /// independently of any other operations on the handle, exactly like the equivalent method on
+    /// the map. If the entry is concurrently removed between calls, later operations observe the
+    /// removal, e.g. [`Entry::update`] returns `None`. If the entry is migrated to a new table by
+    /// a resize, operations transparently follow it to the new table.
+    ///
+    /// Operations that write to the map require `K: Clone`, as every new value is stored
+    /// along with its own copy of the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    ///
+    /// let entry = map.entry("a");
+    /// assert_eq!(entry.get(), None);
+    /// assert_eq!(entry.insert(1), None);
+    /// assert_eq!(entry.update(|v| v + 1), Some(&2));
+    /// assert_eq!(entry.update(|v| v * 10), Some(&20));
+    /// assert_eq!(entry.remove(), Some(&20));
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn entry<'g, G>(&'g self, key: K, guard: &'g G) -> Entry<'g, K, V, S, G>
+    where
+        G: Guard,
+    {
+        Entry {
+            hash: self.raw.hasher.hash_one(&key),
+            key,
+            slot: Cell::new(None),
+            map: self,
+            guard: self.raw.verify(guard),
+        }
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, "a");
+    /// assert_eq!(map.pin().remove(&1), Some(&"a"));
+    /// assert_eq!(map.pin().remove(&1), None);
+    /// ```
+    #[inline]
+    pub fn remove<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        K: 'g,
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        match self.raw.remove(key, self.raw.verify(guard)) {
+            Some((_, value)) => Some(value),
+            None => None,
+        }
+    }
+
+    /// Removes a key from the map, returning the stored key and value if the
+    /// key was previously in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, "a");
+    /// assert_eq!(map.pin().get(&1), Some(&"a"));
+    /// assert_eq!(map.pin().remove_entry(&1), Some((&1, &"a")));
+    /// assert_eq!(map.pin().remove(&1), None);
+    /// ```
+    #[inline]
+    pub fn remove_entry<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> Option<(&'g K, &'g V)>
+    where
+        K: 'g,
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.raw.remove(key, self.raw.verify(guard))
+    }
+
+    /// Tries to reserve capacity for `additional` more elements to be inserted
+    /// in the `HashMap`.
+    ///
+    /// After calling this method, the table should be able to hold at least `capacity` elements
+    /// before resizing. However, the capacity is an estimate, and the table may prematurely resize
+    /// due to poor hash distribution. The collection may also reserve more space to avoid frequent
+    /// reallocations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map: HashMap<&str, i32> = HashMap::new();
+    /// map.pin().reserve(10);
+    /// ```
+    #[inline]
+    pub fn reserve(&self, additional: usize, guard: &impl Guard) {
+        self.raw.reserve(additional, self.raw.verify(guard))
+    }
+
+    /// Completes any in-progress resizes of the `HashMap`.
+    ///
+    /// In incremental resize mode, entries are migrated to a new table gradually
+    /// as the map is accessed, and operations that encounter a partially copied
+    /// table may need to search both the old and new table. This method blocks
+    /// until all pending migrations are complete, leaving the map in a steady
+    /// state. Unlike [`reserve`](HashMap::reserve), it never allocates a new table.
+    ///
+    /// This is useful after bulk loading the map, before entering a latency-sensitive
+    /// phase of work. Note that subsequent insertions may trigger new resizes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// for i in 0..1000 {
+    ///     map.pin().insert(i, i);
+    /// }
+    ///
+    /// map.pin().finalize_resize();
+    /// assert_eq!(map.pin().get(&500), Some(&500));
+    /// ```
+    #[inline]
+    pub fn finalize_resize(&self, guard: &impl Guard) {
+        self.raw.finalize_resize(self.raw.verify(guard))
+    }
+
+    /// Immediately grows the map's table to double its capacity, regardless of how full it is.
+    ///
+    /// This is intended for testing, where it can be used to exercise the resize path at
+    /// deterministic points rather than relying on the map filling up. Combined with a
+    /// fixed hasher such as [`SeededState`], this allows resize interactions to be reproduced
+    /// reliably. In blocking resize mode the resize is completed before returning, while in
+    /// incremental mode it is left in progress, and entries are migrated by subsequent
+    /// operations or by [`finalize_resize`](HashMap::finalize_resize). Any resize already in
+    /// progress is completed first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, "a");
+    ///
+    /// let capacity = map.pin().capacity();
+    /// map.pin().force_resize();
+    /// assert_eq!(map.pin().capacity(), capacity * 2);
+    /// assert_eq!(map.resize_count(), (1, 0));
+    /// assert_eq!(map.pin().get(&1), Some(&"a"));
+    /// ```
+    #[inline]
+    pub fn force_resize(&self, guard: &impl Guard) {
+        self.raw.force_resize(self.raw.verify(guard))
+    }
+
+    /// Rehashes the map into a table of the same capacity, removing any tombstones.
+    ///
+    /// Removing an entry leaves behind a tombstone, which is only cleared when the table
+    /// is resized. In long-lived maps with frequent removals and insertions at a steady size,
+    /// tombstones can accumulate and lengthen probe sequences, slowing down both lookups and
+    /// insertions. This method copies all entries to a new table of the same capacity,
+    /// restoring short probe sequences.
+    ///
+    /// Any in-progress resizes are completed first. If the table does not contain any
+    /// tombstones, no copy is performed. Note that this method blocks until the copy is
+    /// complete, and temporarily requires memory for a second table. See [`ResizeMode`]
+    /// for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// for i in 0..1000 {
+    ///     map.pin().insert(i, i);
+    /// }
+    ///
+    /// for i in 0..500 {
+    ///     map.pin().remove(&i);
+    /// }
+    ///
+    /// map.pin().compact();
+    /// assert_eq!(map.len(), 500);
+    /// ```
+    #[inline]
+    pub fn compact(&self, guard: &impl Guard) {
+        self.raw.compact(self.raw.verify(guard))
+    }
+
+    /// Returns the number of tombstones in the map's table.
+    ///
+    /// Tombstones are left behind by removed entries and are only cleared by a resize or
+    /// a call to [`compact`](HashMap::compact). This can be used to decide when compaction is
+    /// worthwhile, for example by comparing the count against the [`len`](HashMap::len) of
+    /// the map. Alternatively, compaction can be triggered automatically with
+    /// [`HashMapBuilder::auto_compact_at`].
+    ///
+    /// Note that counting requires a full scan of the table. Tombstones in a table that is
+    /// being resized are not counted, as they are not copied to the new table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// for i in 0..100 {
+    ///     map.pin().insert(i, i);
+    /// }
+    ///
+    /// for i in 0..50 {
+    ///     map.pin().remove(&i);
+    /// }
+    ///
+    /// assert_eq!(map.pin().tombstone_count(), 50);
+    /// map.pin().compact();
+    /// assert_eq!(map.pin().tombstone_count(), 0);
+    /// ```
+    #[inline]
+    pub fn tombstone_count(&self, guard: &impl Guard) -> usize {
+        self.raw.tombstone_count(self.raw.verify(guard))
+    }
+
+    /// Returns the number of slots in the map's table.
+    ///
+    /// This is the exact, power-of-two slot count of the table, not an estimate of the
+    /// number of entries the map can hold before resizing. The table resizes once its
+    /// probe sequences grow too long, which typically happens well before every slot is
+    /// occupied. If a resize is in progress, the slot count of the new table is returned.
+    /// A map that has not been allocated yet has a capacity of 0.
+    ///
+    /// Like [`len`](HashMap::len), this is cheap to call, and does not block on or help
+    /// with any in-progress resizes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// assert_eq!(map.pin().capacity(), 0);
+    ///
+    /// map.pin().insert(1, "a");
+    /// assert!(map.pin().capacity().is_power_of_two());
+    /// ```
+    #[inline]
+    pub fn capacity(&self, guard: &impl Guard) -> usize {
+        self.raw.capacity(self.raw.verify(guard))
+    }
+
+    /// Returns statistics about hash collisions in the map.
+    ///
+    /// The report includes the maximum and average probe sequence length of entries in
+    /// the map, as well as the number of groups of slots that are fully occupied. Long probe
+    /// sequences or a large number of full groups, relative to the number of entries, indicate
+    /// that keys are clustering, which is typically caused by a poor [`Hash`] implementation or
+    /// [`BuildHasher`]. See [`CollisionReport`] for details.
+    ///
+    /// This method inspects every slot in the table and rehashes every key, and so is
+    /// expensive regardless of the size of the map. It is intended for diagnostics.
+    ///
+    /// Note that this method will block until any in-progress resizes are
+    /// completed before proceeding. See the [consistency](crate#consistency)
+    /// section for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// for i in 0..1000 {
+    ///     map.pin().insert(i, i);
+    /// }
+    ///
+    /// let report = map.pin().collision_report();
+    /// assert_eq!(report.entries, 1000);
+    /// assert!(report.average_probe_len < 2.0);
+    /// ```
+    #[inline]
+    pub fn collision_report(&self, guard: &impl Guard) -> CollisionReport {
+        self.raw.collision_report(self.raw.verify(guard))
+    }
+
+    /// Asserts the internal invariants of the map, panicking with a diagnostic
+    /// message if any are violated.
+    ///
+    /// This checks that no key is present in more than one table, that every entry
+    /// is reachable within the probe limit and has correct metadata, that tombstones
+    /// are accounted for, that in-progress resizes are consistent with the
+    /// [`ResizeMode`], and that [`len`](HashMap::len) matches the number of entries.
+    ///
+    /// This method is intended for testing, and is only available with the `testing`
+    /// feature. It walks every table and is expensive regardless of the size of the map.
+    /// Additionally, the map must not be modified concurrently, otherwise the checks
+    /// may spuriously fail.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// for i in 0..1000 {
+    ///     map.pin().insert(i, i);
+    /// }
+    ///
+    /// map.pin().check_invariants();
+    /// ```
+    #[inline]
+    #[cfg(feature = "testing")]
+    pub fn check_invariants(&self, guard: &impl Guard) {
+        self.raw.check_invariants(self.raw.verify(guard))
+    }
+
+    /// Clears the map, removing all key-value pairs.
+    ///
+    /// Note that this method will block until any in-progress resizes are
+    /// completed before proceeding. See the [consistency](crate#consistency)
+    /// section for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    ///
+    /// map.pin().insert(1, "a");
+    /// map.pin().clear();
+    /// assert!(map.pin().is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&self, guard: &impl Guard) {
+        self.raw.clear(self.raw.verify(guard))
+    }
+
+    /// Clears the map, replacing the table with an empty table that has room for
+    /// at least `capacity` entries.
+    ///
+    /// Unlike calling [`clear`](HashMap::clear) followed by [`reserve`](HashMap::reserve),
+    /// this allocates the new table in a single step, and may also shrink the map. This
+    /// is useful for maps that are repeatedly cleared and refilled to a similar size, avoiding
+    /// immediate re-growth. The old table is retired once the new table is installed.
+    ///
+    /// Note that this method blocks until the new table is installed, and if the map is
+    /// being concurrently modified, the resulting capacity may differ from the one requested.
+    /// See the [consistency](crate#consistency) section for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// for i in 0..1000 {
+    ///     map.pin().insert(i, i);
+    /// }
+    ///
+    /// map.pin().clear_and_reserve(100);
+    /// assert!(map.pin().is_empty());
+    /// ```
+    #[inline]
+    pub fn clear_and_reserve(&self, capacity: usize, guard: &impl Guard) {
+        self.raw.clear_and_reserve(capacity, self.raw.verify(guard))
+    }
+
+    /// Replaces the map's hasher, rehashing all entries into a new table.
+    ///
+    /// This can be used to mitigate hash-flooding attacks, where an attacker chooses keys
+    /// that collide under the current hasher to degrade the performance of the map. Switching
+    /// to a hasher with a fresh random seed invalidates any precomputed collisions. Entries are
+    /// moved to the new table without being reallocated.
+    ///
+    /// As every operation relies on the hasher being fixed, rehashing requires exclusive access
+    /// to the map, and is performed in a single blocking step. Any in-progress resizes are
+    /// completed first. Note that this method temporarily requires memory for a second table.
+    ///
+    /// If the new hasher panics, the map is left unchanged and continues to use its current
+    /// hasher.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::{HashMap, SeededState};
+    ///
+    /// let mut map = HashMap::with_seed(1);
+    /// for i in 0..1000 {
+    ///     map.pin().insert(i, i);
+    /// }
+    ///
+    /// map.rehash_with(SeededState::new(2));
+    /// assert_eq!(map.hasher().seed(), 2);
+    /// assert_eq!(map.pin().get(&7), Some(&7));
+    /// ```
+    #[inline]
+    pub fn rehash_with(&mut self, hasher: S) {
+        self.raw.rehash_with(hasher)
+    }
+
+    /// Splits the map into `n` independent maps, distributing the entries by the hash of their key.
+    ///
+    /// An entry is moved into the map at index `hash % n`, where `hash` is the hash of its key
+    /// produced by the map's hasher. Every resulting map uses a clone of the hasher, so the
+    /// partition of a key can be computed at any time with [`hasher`](HashMap::hasher).
+    ///
+    /// This consumes the map, moving the entries into the new maps without cloning them. As the
+    /// map is owned, the partition is consistent and no concurrent modifications can be missed.
+    /// To partition a live map that is still being accessed concurrently, clone it first.
+    ///
+    /// Values may have been pinned with [`get_pin`](HashMap::get_pin), so this requires `V: Unpin`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::hash::BuildHasher;
+    ///
+    /// let map = HashMap::new();
+    /// for i in 0..100 {
+    ///     map.pin().insert(i, i);
+    /// }
+    ///
+    /// let shards = map.partition(4);
+    /// assert_eq!(shards.iter().map(|shard| shard.len()).sum::<usize>(), 100);
+    ///
+    /// let shard = shards[0].hasher().hash_one(&7) % 4;
+    /// assert_eq!(shards[shard as usize].pin().get(&7), Some(&7));
+    /// ```
+    ///
+    /// Values that are `!Unpin` cannot be moved between maps:
+    ///
+    /// ```compile_fail
+    /// use papaya::HashMap;
+    /// use std::marker::PhantomPinned;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, PhantomPinned);
+    /// let _ = map.partition(2);
+    /// ```
+    pub fn partition(self, n: usize) -> Vec<HashMap<K, V, S>>
+    where
+        V: Unpin,
+        S: Clone,
+    {
+        assert!(n > 0, "cannot partition a map into zero maps");
+
+        let shards: Vec<_> = (0..n)
+            .map(|_| {
+                HashMap::builder()
+                    .capacity(self.len() / n)
+                    .hasher(self.raw.hasher.clone())
+                    .collector(self.raw.collector().clone())
+                    .build()
+            })
+            .collect();
+
+        {
+            let guards: Vec<_> = shards.iter().map(HashMap::guard).collect();
+            for (key, value) in self {
+                let i = (shards[0].raw.hasher.hash_one(&key) % n as u64) as usize;
+                shards[i].insert(key, value, &guards[i]);
+            }
+        }
+
+        shards
+    }
+
+    /// Updates every entry in the map with a closure.
+    ///
+    /// The closure is called with each key and its current value. Returning `Some` atomically
+    /// replaces the value, while returning `None` leaves the entry unchanged. Each update is
+    /// applied with the same compare-and-swap semantics as [`update`](HashMap::update), meaning
+    /// that the closure may be called more than once for a given key if the entry is
+    /// concurrently modified, and should be pure. This is useful for sweeping transformations
+    /// such as decaying every counter in the map.
+    ///
+    /// The entries are visited in unsorted (and unspecified) order. Entries inserted or removed
+    /// during the sweep may or may not be visited. The sweep as a whole is not atomic, but every
+    /// individual update is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map: HashMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
+    /// map.pin().for_each_update(|&k, &v| (k % 2 == 0).then(|| v / 2));
+    ///
+    /// assert_eq!(map.pin().get(&2), Some(&10));
+    /// assert_eq!(map.pin().get(&3), Some(&30));
+    /// ```
+    #[inline]
+    pub fn for_each_update<F>(&self, f: F, guard: &impl Guard)
+    where
+        K: Clone,
+        F: FnMut(&K, &V) -> Option<V>,
+    {
+        self.raw.for_each_update(f, self.raw.verify(guard))
+    }
+
+    /// Updates every value in the map with a closure, sweeping the table in parallel on the
+    /// rayon thread pool.
+    ///
+    /// This behaves like [`for_each_update`](HashMap::for_each_update), except that the table
+    /// is split into ranges of slots that are updated concurrently, which is useful for applying
+    /// a uniform transformation, such as decay, to a large map. Each update is applied
+    /// atomically with the same compare-and-swap semantics as [`update`](HashMap::update), so
+    /// the closure may be called more than once for a given entry and should be pure.
+    ///
+    /// Every individual update is linearizable, but the sweep as a whole is not a snapshot.
+    /// Entries inserted during the sweep may be missed, and entries removed during the sweep
+    /// may or may not be updated before their removal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map: HashMap<i32, i32> = (0..1000).map(|x| (x, x * 10)).collect();
+    /// map.pin().par_update_all(|v| v / 2);
+    ///
+    /// assert_eq!(map.pin().get(&2), Some(&10));
+    /// assert_eq!(map.pin().get(&999), Some(&4995));
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub fn par_update_all<F>(&self, f: F, guard: &impl Guard)
+    where
+        K: Clone + Send + Sync,
+        V: Send + Sync,
+        S: Sync,
+        F: Fn(&V) -> V + Sync,
+    {
+        self.raw.par_update_all(f, self.raw.verify(guard))
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all pairs `(k, v)` for which `f(&k, &v)` returns `false`.
+    /// The elements are visited in unsorted (and unspecified) order.
+    ///
+    /// Note the function may be called more than once for a given key if its value is
+    /// concurrently modified during removal.
+    ///
+    /// Additionally, this method will block until any in-progress resizes are
+    /// completed before proceeding. See the [consistency](crate#consistency)
+    /// section for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let mut map: HashMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
+    /// map.pin().retain(|&k, _| k % 2 == 0);
+    /// assert_eq!(map.len(), 4);
+    /// ```
+    #[inline]
+    pub fn retain<F>(&mut self, f: F, guard: &impl Guard)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.raw.retain(f, self.raw.verify(guard))
+    }
+
+    /// Retains only the elements specified by the predicate, calling `on_removed` with
+    /// every entry that was removed.
+    ///
+    /// This is useful for performing cleanup on entries as they are pruned from the map.
+    /// `on_removed` is called exactly once for every entry removed by this operation, and
+    /// never for entries removed concurrently by other threads.
+    ///
+    /// Note that ownership of the removed entries cannot be transferred, as they may still be
+    /// accessed by concurrent readers. Instead, `on_removed` receives references that are valid
+    /// for the lifetime of the guard, and the entries are dropped once they are no longer accessible.
+    ///
+    /// See [`HashMap::retain`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let mut map: HashMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
+    ///
+    /// let mut removed = Vec::new();
+    /// map.pin().retain_with(|&k, _| k % 2 == 0, |k, v| removed.push((*k, *v)));
+    ///
+    /// removed.sort();
+    /// assert_eq!(removed, [(1, 10), (3, 30), (5, 50), (7, 70)]);
+    /// assert_eq!(map.len(), 4);
+    /// ```
+    #[inline]
+    pub fn retain_with<'g, F, R>(&mut self, f: F, on_removed: R, guard: &'g impl Guard)
+    where
+        K: 'g,
+        V: 'g,
+        F: FnMut(&K, &V) -> bool,
+        R: FnMut(&'g K, &'g V),
+    {
+        self.raw.retain_with(f, on_removed, self.raw.verify(guard))
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order.
+    /// The iterator element type is `(&K, &V)`.
+    ///
+    /// Note that this method will block until any in-progress resizes are
+    /// completed before proceeding. See the [consistency](crate#consistency)
+    /// section for details.
+    ///
+    /// The iterator walks the table that was current when it was created, even if the map is
+    /// resized during iteration. Every entry is yielded at most once, but entries that were
+    /// migrated to a newer table are yielded as they were at the time of migration, and keys
+    /// inserted after a migration are not observed. Use [`iter_live`](HashMap::iter_live)
+    /// to follow migrated entries instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([
+    ///     ("a", 1),
+    ///     ("b", 2),
+    ///     ("c", 3),
+    /// ]);
+    ///
+    /// for (key, val) in map.pin().iter() {
+    ///     println!("key: {key} val: {val}");
+    /// }
+    #[inline]
+    pub fn iter<'g, G>(&self, guard: &'g G) -> Iter<'g, K, V, G>
+    where
+        G: Guard,
+    {
+        Iter {
+            raw: self.raw.iter(self.raw.verify(guard)),
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order, following entries
+    /// that are migrated by concurrent resizes.
+    /// The iterator element type is `(&K, &V)`.
+    ///
+    /// Like [`iter`](HashMap::iter), this iterator walks the table that was current when it
+    /// was created. However, if an entry has been migrated to a newer table by the time it
+    /// is visited, its latest value is read from the current table instead, and the key is
+    /// skipped if it has since been removed. Every key is still yielded at most once, but
+    /// keys inserted into the newer table during iteration are not observed.
+    ///
+    /// In the absence of concurrent resizes, this iterator behaves identically to
+    /// [`iter`](HashMap::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([("a", 1)]);
+    ///
+    /// let guard = map.guard();
+    /// let mut stable = map.iter(&guard);
+    /// let mut live = map.iter_live(&guard);
+    ///
+    /// // Migrate the entry to a larger table, and then update it.
+    /// map.pin().reserve(1024);
+    /// map.pin().insert("a", 2);
+    ///
+    /// assert_eq!(stable.next(), Some((&"a", &1)));
+    /// assert_eq!(live.next(), Some((&"a", &2)));
+    /// ```
+    #[inline]
+    pub fn iter_live<'g, G>(&'g self, guard: &'g G) -> LiveIter<'g, K, V, S, G>
+    where
+        G: Guard,
+    {
+        LiveIter {
+            raw: self.raw.iter_live(self.raw.verify(guard)),
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order, that never helps
+    /// with in-progress resizes.
+    /// The iterator element type is `(&K, &V)`.
+    ///
+    /// Unlike [`iter`](HashMap::iter), which completes any in-progress resize before iterating,
+    /// this iterator never performs migration work, making it suitable for threads with hard
+    /// latency requirements. Like other read operations, such as [`get`](HashMap::get), it
+    /// only ever reads from the map. The iterator walks the current root table, and entries
+    /// that have already been migrated to a newer table are read from there instead, as with
+    /// [`iter_live`](HashMap::iter_live). Every key is yielded at most once, but keys that
+    /// were inserted into a newer table after a resize started are not observed until the
+    /// resize has completed.
+    ///
+    /// The work performed by the iterator is bounded by the number of slots in the root table,
+    /// plus a lookup for every migrated entry. Each lookup probes at most `5 * log2(n)` slots
+    /// in each table that is being resized into, where `n` is the number of slots in the
+    /// table, the same bound as [`get`](HashMap::get). Neither ever blocks or copies entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([("a", 1), ("b", 2)]);
+    ///
+    /// let map = map.pin();
+    /// let mut entries: Vec<_> = map.iter_passive().collect();
+    /// entries.sort();
+    /// assert_eq!(entries, [(&"a", &1), (&"b", &2)]);
+    /// ```
+    #[inline]
+    pub fn iter_passive<'g, G>(&'g self, guard: &'g G) -> LiveIter<'g, K, V, S, G>
+    where
+        G: Guard,
+    {
+        LiveIter {
+            raw: self.raw.iter_passive(self.raw.verify(guard)),
+        }
+    }
+
+    /// An iterator visiting all entries in arbitrary order, yielding handles that can remove
+    /// the entry if it has not changed since it was observed.
+    /// The iterator element type is [`RemovalHandle`].
+    ///
+    /// This is useful for sweeping the map concurrently, such as in a mark-and-sweep collector,
+    /// where an entry should only be removed based on the value that was inspected. Every update
+    /// to a key stores a new entry, so the identity of the observed entry acts as its version.
+    /// [`RemovalHandle::remove_if_unchanged`] atomically removes the entry only if it is still
+    /// the same entry, meaning a concurrent update is never accidentally removed. Entries keep
+    /// their identity when they are migrated to a new table by a resize.
+    ///
+    /// See [`iter`](HashMap::iter) for details about the iteration itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([("a", 1), ("b", 2)]);
+    /// let guard = map.guard();
+    ///
+    /// for handle in map.iter_removable(&guard) {
+    ///     if *handle.key() == "a" {
+    ///         // The entry is concurrently updated after it was observed.
+    ///         map.insert("a", 10, &guard);
+    ///     }
+    ///
+    ///     handle.remove_if_unchanged();
+    /// }
+    ///
+    /// assert_eq!(map.get("a", &guard), Some(&10));
+    /// assert_eq!(map.get("b", &guard), None);
+    /// ```
+    #[inline]
+    pub fn iter_removable<'g, G>(&'g self, guard: &'g G) -> IterRemovable<'g, K, V, S, G>
+    where
+        G: Guard,
+    {
+        let guard = self.raw.verify(guard);
+
+        IterRemovable {
+            raw: self.raw.iter(guard),
+            map: self,
+            guard,
+        }
+    }
+
+    /// An iterator visiting all keys in arbitrary order.
+    /// The iterator element type is `&K`.
+    ///
+    /// Note that this method will block until any in-progress resizes are
+    /// completed before proceeding. See the [consistency](crate#consistency)
+    /// section for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([
+    ///     ("a", 1),
+    ///     ("b", 2),
+    ///     ("c", 3),
+    /// ]);
+    ///
+    /// for key in map.pin().keys() {
+    ///     println!("{key}");
+    /// }
+    /// ```
+    #[inline]
+    pub fn keys<'g, G>(&self, guard: &'g G) -> Keys<'g, K, V, G>
+    where
+        G: Guard,
+    {
+        Keys {
+            iter: self.iter(guard),
+        }
+    }
+
+    /// An iterator visiting all values in arbitrary order.
+    /// The iterator element type is `&V`.
+    ///
+    /// Note that this method will block until any in-progress resizes are
+    /// completed before proceeding. See the [consistency](crate#consistency)
+    /// section for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([
+    ///     ("a", 1),
+    ///     ("b", 2),
+    ///     ("c", 3),
+    /// ]);
+    ///
+    /// for value in map.pin().values() {
+    ///     println!("{value}");
+    /// }
+    /// ```
+    #[inline]
+    pub fn values<'g, G>(&self, guard: &'g G) -> Values<'g, K, V, G>
+    where
+        G: Guard,
+    {
+        Values {
+            iter: self.iter(guard),
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in the order they are stored in the table.
+    /// The iterator element type is `(&K, &V)`.
+    ///
+    /// Unlike [`iter`](HashMap::iter), which visits entries in an unspecified order, this
+    /// iterator is guaranteed to traverse the table in ascending slot order. Processing entries
+    /// in physical order maximizes cache locality, and produces a deterministic order for a
+    /// given table state. Note that the order depends on the hasher and on the history of the
+    /// table, such as previous resizes and removals, so it is only stable as long as the map
+    /// is not modified.
+    ///
+    /// Note that this method will block until any in-progress resizes are
+    /// completed before proceeding. See the [consistency](crate#consistency)
+    /// section for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([
+    ///     ("a", 1),
+    ///     ("b", 2),
+    ///     ("c", 3),
+    /// ]);
+    ///
+    /// let map = map.pin();
+    /// let first: Vec<_> = map.iter_in_table_order().collect();
+    /// let second: Vec<_> = map.iter_in_table_order().collect();
+    /// assert_eq!(first, second);
+    /// ```
+    #[inline]
+    pub fn iter_in_table_order<'g, G>(&self, guard: &'g G) -> Iter<'g, K, V, G>
+    where
+        G: Guard,
+    {
+        // Iteration always walks a single table from the first slot to the last.
+        self.iter(guard)
+    }
+
+    /// An iterator visiting all key-value pairs along with the index of the slot they are
+    /// stored in. The iterator element type is `(usize, &K, &V)`.
+    ///
+    /// This is a diagnostic tool that exposes the physical layout of the table, for example
+    /// to find clusters of entries in neighbouring slots. Entries are visited in ascending
+    /// slot order, as with [`iter_in_table_order`](HashMap::iter_in_table_order), and every
+    /// index is less than the [`capacity`](HashMap::capacity) of the table being iterated over.
+    ///
+    /// Note that slot indices are only meaningful within a single table generation. Entries
+    /// are moved to different slots whenever the table is resized or compacted, and a removed
+    /// key may be reinserted into a different slot, so indices should not be relied on to
+    /// identify entries across modifications of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let map = map.pin();
+    ///
+    /// for (slot, key, val) in map.iter_enumerate() {
+    ///     println!("slot {slot}: {key} = {val}");
+    /// }
+    /// ```
+    #[inline]
+    pub fn iter_enumerate<'g, G>(&self, guard: &'g G) -> IterEnumerate<'g, K, V, G>
+    where
+        G: Guard,
+    {
+        IterEnumerate {
+            raw: self.raw.iter(self.raw.verify(guard)),
+        }
+    }
+
+    /// Returns an iterator over a snapshot of the map's entries.
+    ///
+    /// Unlike [`iter`](HashMap::iter), the entries are collected when the snapshot is
+    /// created, so the iterator has a fixed length and implements [`ExactSizeIterator`].
+    /// Modifications made to the map after the snapshot is taken are not reflected in the
+    /// iterator. The snapshot itself is subject to the same [consistency](crate#consistency)
+    /// guarantees as `iter`, so it is only a precise view of the map if there are no concurrent
+    /// writers while it is being taken.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([
+    ///     ("a", 1),
+    ///     ("b", 2),
+    ///     ("c", 3),
+    /// ]);
+    ///
+    /// let map = map.pin();
+    /// let snapshot = map.snapshot();
+    /// map.insert("d", 4);
+    ///
+    /// assert_eq!(snapshot.len(), 3);
+    /// ```
+    #[inline]
+    pub fn snapshot<'g, G>(&self, guard: &'g G) -> Snapshot<'g, K, V>
+    where
+        G: Guard,
+    {
+        let mut entries = Vec::with_capacity(self.len());
+        entries.extend(self.iter(guard));
+
+        Snapshot {
+            entries: entries.into_iter(),
+        }
+    }
+
+    /// Writes the map to `writer` in a versioned binary format.
+    ///
+    /// Every entry is encoded into a buffer by `encode`, which may use any serialization format,
+    /// such as one based on `serde`. The format only defines the framing around the encoded
+    /// entries, so changes to the framing are detectable across crate versions, while the
+    /// encoding of keys and values remains under the caller's control. The map can be loaded
+    /// again with [`load_from`](HashMap::load_from).
+    ///
+    /// The entries are written from a [`snapshot`](HashMap::snapshot) of the map, so the saved
+    /// entry count always matches the number of entries written, but the saved map is only a
+    /// precise view if there are no concurrent writers while the snapshot is taken.
+    ///
+    /// # Format
+    ///
+    /// All integers are encoded in little-endian byte order. A saved map consists of a header,
+    /// followed by the entries:
+    ///
+    /// - The magic bytes `PAPAYA`.
+    /// - The format version as a `u16`, currently `1`.
+    /// - The number of entries as a `u64`.
+    /// - For every entry, the length of the encoded entry as a `u64`, followed by the bytes
+    ///   written by `encode`.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error returned by `writer` or `encode`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([(1_u32, 10_u64), (2, 20)]);
+    ///
+    /// let mut bytes = Vec::new();
+    /// map.pin()
+    ///     .save_to(&mut bytes, |key, value, buf| {
+    ///         buf.extend_from_slice(&key.to_le_bytes());
+    ///         buf.extend_from_slice(&value.to_le_bytes());
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let loaded: HashMap<u32, u64> = HashMap::load_from(&bytes[..], |buf| {
+    ///     let key = u32::from_le_bytes(buf[..4].try_into().unwrap());
+    ///     let value = u64::from_le_bytes(buf[4..].try_into().unwrap());
+    ///     Ok((key, value))
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(loaded, map);
+    /// ```
+    pub fn save_to<W, F>(&self, writer: W, mut encode: F, guard: &impl Guard) -> io::Result<()>
+    where
+        W: io::Write,
+        F: FnMut(&K, &V, &mut Vec<u8>) -> io::Result<()>,
+    {
+        let mut writer = io::BufWriter::new(writer);
+        let snapshot = self.snapshot(guard);
+        persist::write_header(&mut writer, snapshot.len())?;
+
+        let mut buf = Vec::new();
+        for (key, value) in snapshot {
+            buf.clear();
+            encode(key, value, &mut buf)?;
+            persist::write_entry(&mut writer, &buf)?;
+        }
+
+        io::Write::flush(&mut writer)
+    }
+
+    /// Reads a map from `reader` that was written by [`save_to`](HashMap::save_to).
+    ///
+    /// Every entry is decoded from its encoded bytes by `decode`. The map is preallocated
+    /// based on the entry count in the header, so loading small to medium sized maps never has
+    /// to resize the table. The preallocation is capped, so a corrupted entry count results in
+    /// an error once the input runs out rather than a large allocation. If the saved map contains
+    /// duplicate keys, the last entry is retained.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`InvalidData`](io::ErrorKind::InvalidData) if the input does
+    /// not start with a valid header, or was written with an unsupported version of the format.
+    /// Returns an error of kind [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) if the input
+    /// ends before every entry has been read. Any error returned by `reader` or `decode` is
+    /// also returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map: HashMap<String, String> = HashMap::from([("a".to_owned(), "b".to_owned())]);
+    ///
+    /// let mut bytes = Vec::new();
+    /// map.pin()
+    ///     .save_to(&mut bytes, |key, value, buf| {
+    ///         serde_json::to_writer(buf, &(key, value)).map_err(Into::into)
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let loaded: HashMap<String, String> =
+    ///     HashMap::load_from(&bytes[..], |buf| serde_json::from_slice(buf).map_err(Into::into))
+    ///         .unwrap();
+    ///
+    /// assert_eq!(loaded, map);
+    /// ```
+    pub fn load_from<R, F>(reader: R, mut decode: F) -> io::Result<HashMap<K, V, S>>
+    where
+        R: io::Read,
+        F: FnMut(&[u8]) -> io::Result<(K, V)>,
+        S: Default,
+    {
+        let mut reader = io::BufReader::new(reader);
+        let len = persist::read_header(&mut reader)?;
+
+        // Don't trust the entry count for the preallocation, the map grows as needed.
+        let capacity = len.min(persist::MAX_PREALLOCATE);
+        let map = HashMap::with_capacity_and_hasher(capacity, S::default());
+        {
+            let map = map.pin();
+            let mut buf = Vec::new();
+            for _ in 0..len {
+                persist::read_entry(&mut reader, &mut buf)?;
+                let (key, value) = decode(&buf)?;
+                map.insert(key, value);
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Returns the number of entries visited by iterating over the map.
+    ///
+    /// Unlike [`len`](HashMap::len), which reads a live counter, this method counts the entries
+    /// that an [iterator](HashMap::iter) over the map observes, making it consistent with the
+    /// contents of a [`snapshot`](HashMap::snapshot) taken at the same time. The count is subject
+    /// to the same [consistency](crate#consistency) guarantees as iteration. Note that counting
+    /// requires a full scan of the table, so this method is considerably more expensive than `len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([("a", 1), ("b", 2)]);
+    /// let map = map.pin();
+    ///
+    /// assert_eq!(map.snapshot_len(), 2);
+    /// assert_eq!(map.snapshot_len(), map.iter().count());
+    /// ```
+    #[inline]
+    pub fn snapshot_len(&self, guard: &impl Guard) -> usize {
+        self.iter(guard).count()
+    }
+
+    /// Returns the number of entries matching a predicate, counted over a single table
+    /// generation.
+    ///
+    /// The count is taken by walking the table that was current when counting started,
+    /// with the same resize semantics as [`iter`](HashMap::iter). Entries that are migrated
+    /// to a newer table during the count are counted once, as they were when migrated, so
+    /// a concurrent resize can never cause an entry to be counted twice or missed. Entries
+    /// that are inserted, updated or removed concurrently are subject to the usual
+    /// [consistency](crate#consistency) guarantees of iteration. In particular, a key that is
+    /// present for the entire duration of the count is always visited exactly once.
+    ///
+    /// Note that the table generation is pinned by the guard. If the map is resized while
+    /// counting, the old table, along with any entries removed or replaced in the meantime,
+    /// cannot be reclaimed until the guard is dropped, temporarily retaining up to the memory
+    /// of an extra copy of the table. Counting also requires a full scan of the table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map: HashMap<i32, bool> = (0..10).map(|i| (i, i % 3 == 0)).collect();
+    /// assert_eq!(map.pin().count_matching_snapshot(|_, &active| active), 4);
+    /// ```
+    #[inline]
+    pub fn count_matching_snapshot<F>(&self, mut f: F, guard: &impl Guard) -> usize
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.iter(guard)
+            .filter(|(key, value)| f(key, value))
+            .count()
+    }
+
+    /// Creates a consuming iterator visiting all the keys in arbitrary order.
+    /// The map cannot be used after calling this. The iterator element type is `K`.
+    ///
+    /// Values are dropped as the iterator advances.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([
+    ///     ("a", 1),
+    ///     ("b", 2),
+    ///     ("c", 3),
+    /// ]);
+    ///
+    /// let mut keys: Vec<&str> = map.into_keys().collect();
+    /// keys.sort_unstable();
+    /// assert_eq!(keys, ["a", "b", "c"]);
+    /// ```
+    #[inline]
+    pub fn into_keys(self) -> IntoKeys<K, V, S> {
+        IntoKeys {
+            raw: self.raw.into_entries(),
+        }
+    }
+
+    /// Creates a consuming iterator visiting all the values in arbitrary order.
+    /// The map cannot be used after calling this. The iterator element type is `V`.
+    ///
+    /// Values may have been pinned with [`get_pin`](HashMap::get_pin), so this method
+    /// requires `V: Unpin` as it moves values out of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([
+    ///     ("a", 1),
+    ///     ("b", 2),
+    ///     ("c", 3),
+    /// ]);
+    ///
+    /// let mut values: Vec<i32> = map.into_values().collect();
+    /// values.sort_unstable();
+    /// assert_eq!(values, [1, 2, 3]);
+    /// ```
+    ///
+    /// Values that are `!Unpin` cannot be moved out:
+    ///
+    /// ```compile_fail
+    /// use papaya::HashMap;
+    /// use std::marker::PhantomPinned;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, PhantomPinned);
+    /// let _ = map.into_values();
+    /// ```
+    #[inline]
+    pub fn into_values(self) -> IntoValues<K, V, S>
+    where
+        V: Unpin,
+    {
+        IntoValues {
+            iter: self.into_iter(),
+        }
+    }
+
+    /// Returns all key-value pairs with keys in the given range, sorted by key.
+    ///
+    /// Hash tables do not store keys in any order, so this method scans the entire map and
+    /// sorts the entries that fall within the range, taking `O(n log n)` time. It is intended
+    /// as a convenience for small maps where a full scan is acceptable.
+    ///
+    /// Like [`iter`](HashMap::iter), this method will block until any in-progress resizes
+    /// are completed before proceeding, and the results may or may not reflect concurrent
+    /// modifications to the map. See the [consistency](crate#consistency) section for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map: HashMap<i32, char> = HashMap::from([(3, 'c'), (1, 'a'), (4, 'd'), (2, 'b')]);
+    ///
+    /// assert_eq!(map.pin().range_scan(2..=3), [(&2, &'b'), (&3, &'c')]);
+    /// assert_eq!(map.pin().range_scan(..2), [(&1, &'a')]);
+    /// ```
+    #[inline]
+    pub fn range_scan<'g, R>(&self, range: R, guard: &'g impl Guard) -> Vec<(&'g K, &'g V)>
+    where
+        K: Ord,
+        R: RangeBounds<K>,
+    {
+        let mut entries = self
+            .raw
+            .iter(self.raw.verify(guard))
+            .filter(|(key, _)| range.contains(*key))
+            .collect::<Vec<_>>();
+
+        entries.sort_unstable_by_key(|(key, _)| *key);
+        entries
+    }
+
+    /// Returns up to `n` arbitrary key-value pairs from the map.
+    ///
+    /// Iteration stops as soon as `n` entries have been collected, so this method is cheap
+    /// even for large maps, making it useful for debugging or displaying example entries.
+    /// Note that the selection is *not* random, and the same entries will likely be returned
+    /// by repeated calls on an unchanged map.
+    ///
+    /// Like [`iter`](HashMap::iter), this method will block until any in-progress resizes
+    /// are completed before proceeding, and the results may or may not reflect concurrent
+    /// modifications to the map. See the [consistency](crate#consistency) section for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    /// for i in 0..100 {
+    ///     map.insert(i, i * 2);
+    /// }
+    ///
+    /// let sample = map.sample(10);
+    /// assert_eq!(sample.len(), 10);
+    /// assert!(sample.iter().all(|(k, v)| **v == **k * 2));
+    ///
+    /// assert_eq!(map.sample(1000).len(), 100);
+    /// ```
+    #[inline]
+    pub fn sample<'g>(&self, n: usize, guard: &'g impl Guard) -> Vec<(&'g K, &'g V)> {
+        self.raw.iter(self.raw.verify(guard)).take(n).collect()
+    }
+
+    /// Calls a closure on each key-value pair in the map, stopping as soon as the closure
+    /// returns [`ControlFlow::Break`].
+    ///
+    /// This is equivalent to calling [`Iterator::try_for_each`] on [`iter`](HashMap::iter).
+    /// Iteration stops immediately after the closure breaks, and the break value is returned.
+    ///
+    /// Like [`iter`](HashMap::iter), this method will block until any in-progress resizes
+    /// are completed before proceeding, and the results may or may not reflect concurrent
+    /// modifications to the map. See the [consistency](crate#consistency) section for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::ops::ControlFlow;
+    ///
+    /// let map = HashMap::from([(1, "a"), (2, "b"), (3, "c")]);
+    ///
+    /// let found = map.pin().try_for_each(|key, value| {
+    ///     if *value == "b" {
+    ///         return ControlFlow::Break(*key);
+    ///     }
+    ///
+    ///     ControlFlow::Continue(())
+    /// });
+    ///
+    /// assert_eq!(found, ControlFlow::Break(2));
+    /// ```
+    #[inline]
+    pub fn try_for_each<'g, F, B>(&self, mut f: F, guard: &'g impl Guard) -> ControlFlow<B>
+    where
+        F: FnMut(&'g K, &'g V) -> ControlFlow<B>,
+        K: 'g,
+        V: 'g,
+    {
+        self.raw
+            .iter(self.raw.verify(guard))
+            .try_for_each(|(key, value)| f(key, value))
+    }
+}
+
+impl<K, T, S> HashMap<K, Arc<T>, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns a clone of the [`Arc`] corresponding to the key.
+    ///
+    /// Unlike [`get`](HashMap::get), the returned value is not tied to the lifetime of
+    /// the guard. Cloning an `Arc` only increments its reference count, so this allows values
+    /// to be cheaply handed out and kept alive after the guard is dropped. The returned `Arc`
+    /// can also be passed across an FFI boundary with [`Arc::into_raw`].
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::sync::Arc;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, Arc::new("a"));
+    ///
+    /// let value = map.get_arc(&1, &map.guard()).unwrap();
+    /// map.pin().remove(&1);
+    /// assert_eq!(*value, "a");
+    /// ```
+    #[inline]
+    pub fn get_arc<Q>(&self, key: &Q, guard: &impl Guard) -> Option<Arc<T>>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.get(key, guard).cloned()
+    }
+}
+
+impl<K, T, S> HashMap<K, Weak<T>, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns the value corresponding to the key, if it is still alive.
+    ///
+    /// This attempts to [`upgrade`](Weak::upgrade) the [`Weak`] stored for the key, returning
+    /// `None` if the key is not present in the map or the value has already been dropped. Dead
+    /// entries are left in the map, and can be removed in bulk with
+    /// [`prune_dead`](HashMap::prune_dead).
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::sync::Arc;
+    ///
+    /// let map = HashMap::new();
+    /// let value = Arc::new("a");
+    /// map.pin().insert(1, Arc::downgrade(&value));
+    ///
+    /// assert_eq!(map.get_upgraded(&1, &map.guard()), Some(value.clone()));
+    ///
+    /// drop(value);
+    /// assert_eq!(map.get_upgraded(&1, &map.guard()), None);
+    /// ```
+    #[inline]
+    pub fn get_upgraded<Q>(&self, key: &Q, guard: &impl Guard) -> Option<Arc<T>>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.get(key, guard).and_then(Weak::upgrade)
+    }
+
+    /// Removes all entries whose values have been dropped, returning the number of
+    /// entries removed.
+    ///
+    /// An entry is removed only if its [`Weak`] cannot be upgraded at the time it is visited,
+    /// so entries that are concurrently replaced with a live value are retained.
+    ///
+    /// See [`HashMap::retain`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::sync::Arc;
+    ///
+    /// let map = HashMap::new();
+    /// let a = Arc::new("a");
+    /// let b = Arc::new("b");
+    /// map.pin().insert(1, Arc::downgrade(&a));
+    /// map.pin().insert(2, Arc::downgrade(&b));
+    ///
+    /// drop(b);
+    /// assert_eq!(map.prune_dead(&map.guard()), 1);
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    #[inline]
+    pub fn prune_dead(&self, guard: &impl Guard) -> usize {
+        let mut removed = 0;
+        self.raw.retain_with(
+            |_, value| value.strong_count() > 0,
+            |_, _| removed += 1,
+            self.raw.verify(guard),
+        );
+        removed
+    }
+}
+
+impl<K, T, S> HashMap<K, Box<T>, S>
+where
+    K: Hash + Eq,
+    T: ?Sized,
+    S: BuildHasher,
+{
+    /// Returns a reference to the boxed value corresponding to the key.
+    ///
+    /// This is equivalent to [`get`](HashMap::get), but dereferences the [`Box`], which is
+    /// convenient for maps of trait objects, such as `HashMap<K, Box<dyn Trait + Send + Sync>>`.
+    /// As with any value type, the map is [`Send`] and [`Sync`] as long as the values are, so
+    /// trait objects that are shared across threads should include those bounds.
+    ///
+    /// Boxed values are never moved by the map. Resizes only copy the pointer to the entry
+    /// that owns the box, so references to the value remain valid for the lifetime of the guard,
+    /// even if the table is resized or the entry is concurrently removed.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::fmt::Display;
+    ///
+    /// let map: HashMap<i32, Box<dyn Display + Send + Sync>> = HashMap::new();
+    /// map.pin().insert(1, Box::new("a"));
+    /// map.pin().insert(2, Box::new(2.5));
+    ///
+    /// let guard = map.guard();
+    /// let value: &dyn Display = map.get_dyn(&2, &guard).unwrap();
+    /// assert_eq!(value.to_string(), "2.5");
+    /// ```
+    #[inline]
+    pub fn get_dyn<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> Option<&'g T>
+    where
+        K: 'g,
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.get(key, guard).map(|value| &**value)
+    }
+}
+
+impl<K, S> HashMap<K, usize, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Interns a key, incrementing its reference count, and returns a reference to the key
+    /// stored in the map.
+    ///
+    /// If the key is not present, it is inserted with a count of `1`. Otherwise, its count is
+    /// incremented. Together with [`release`](HashMap::release), this allows the map to be used
+    /// as a concurrent reference-counted interning table. The increment is a single atomic
+    /// operation, so it can never race with a concurrent release that removes the key.
+    ///
+    /// The returned key is the canonical key, i.e. the key that was inserted when the key was
+    /// first interned, not the key that was passed in. Every update stores a new entry holding
+    /// a clone of the canonical key, so for keys such as `Arc<str>`, every call returns a clone
+    /// of the same allocation until the key is released.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::sync::Arc;
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    ///
+    /// let a: Arc<str> = Arc::from("a");
+    /// assert!(Arc::ptr_eq(map.intern(a.clone()), &a));
+    /// assert!(Arc::ptr_eq(map.intern(Arc::from("a")), &a));
+    /// assert_eq!(map.get("a"), Some(&2));
+    /// ```
+    #[inline]
+    pub fn intern<'g>(&self, key: K, guard: &'g impl Guard) -> &'g K
+    where
+        K: Clone,
+    {
+        let guard = self.raw.verify(guard);
+
+        let mut key = key;
+        let mut canonical = false;
+
+        loop {
+            let compute = |entry: Option<(&K, &usize)>| match entry {
+                // The key is already present, retry with a clone of the stored key so that it
+                // is preserved by the update.
+                Some((stored, _)) if !canonical => Operation::Abort(stored.clone()),
+                // Increment the existing count.
+                Some((_, count)) => Operation::Insert(count + 1),
+                // Insert the key with an initial count.
+                None => Operation::Insert(1),
+            };
+
+            match self.raw.compute(key, compute, guard) {
+                Compute::Updated { new: (key, _), .. } => return key,
+                Compute::Inserted(key, _) => return key,
+                Compute::Aborted(stored) => {
+                    key = stored;
+                    canonical = true;
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// Releases a reference to an interned key, decrementing its reference count and removing
+    /// the key once the count reaches zero.
+    ///
+    /// Returns the remaining count, which is `Some(0)` if the key was removed, or `None` if the
+    /// key was not present in the map. The decrement and removal happen in a single atomic
+    /// operation, so the key is removed exactly once even if it is released concurrently.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    ///
+    /// map.intern("a".to_owned());
+    /// map.intern("a".to_owned());
+    ///
+    /// assert_eq!(map.release("a"), Some(1));
+    /// assert_eq!(map.release("a"), Some(0));
+    /// assert_eq!(map.release("a"), None);
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn release<Q>(&self, key: &Q, guard: &impl Guard) -> Option<usize>
+    where
+        K: Clone,
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        let guard = self.raw.verify(guard);
+
+        // Decrementing the count requires an owned key for the new entry.
+        let (key, _) = self.raw.get(key, guard)?;
+
+        let compute = |entry: Option<(&K, &usize)>| match entry {
+            // The key was concurrently removed.
+            None => Operation::Abort(()),
+            // This was the last reference, remove the key.
+            Some((_, &count)) if count <= 1 => Operation::Remove,
+            // Decrement the count.
+            Some((_, count)) => Operation::Insert(count - 1),
+        };
+
+        match self.raw.compute(key.clone(), compute, guard) {
+            Compute::Updated {
+                new: (_, &count), ..
+            } => Some(count),
+            Compute::Removed(..) => Some(0),
+            Compute::Aborted(_) => None,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<K, S> HashMap<K, (), S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Claims a key, returning `true` if the key was not already claimed.
+    ///
+    /// This allows the map to be used as a set of in-flight keys, for example to deduplicate
+    /// work across threads. The check and the insertion are performed in a single atomic
+    /// operation, so if the same key is claimed concurrently, exactly one `claim` returns `true`.
+    /// The claim is held until it is released with [`unclaim`](HashMap::unclaim).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    ///
+    /// assert!(map.claim("a"));
+    /// assert!(!map.claim("a"));
+    ///
+    /// assert!(map.unclaim("a"));
+    /// assert!(map.claim("a"));
+    /// ```
+    #[inline]
+    pub fn claim(&self, key: K, guard: &impl Guard) -> bool {
+        self.try_insert(key, (), guard).is_ok()
+    }
+
+    /// Releases a claimed key, allowing it to be claimed again.
+    ///
+    /// Returns `true` if the key was claimed. The key is removed atomically, so if a key
+    /// is released concurrently, exactly one `unclaim` returns `true`.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    ///
+    /// assert!(map.claim(1));
+    /// assert!(map.unclaim(&1));
+    /// assert!(!map.unclaim(&1));
+    /// ```
+    #[inline]
+    pub fn unclaim<Q>(&self, key: &Q, guard: &impl Guard) -> bool
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.remove(key, guard).is_some()
+    }
+}
+
+impl<K, V, S> HashMap<K, Expiring<V>, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Inserts a key-value pair into the map that expires after the given duration.
+    ///
+    /// If the map did not have this key present, or the previous value has expired, [`None`]
+    /// is returned. Otherwise, a reference to the previous value is returned.
+    ///
+    /// See [`Expiring`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::time::Duration;
+    ///
+    /// let map = HashMap::new();
+    /// map.insert_with_ttl("a", 1, Duration::from_secs(60), &map.guard());
+    /// map.insert_with_ttl("b", 2, Duration::ZERO, &map.guard());
+    ///
+    /// assert_eq!(map.get_unexpired("a", &map.guard()), Some(&1));
+    /// assert_eq!(map.get_unexpired("b", &map.guard()), None);
+    /// ```
+    #[inline]
+    pub fn insert_with_ttl<'g>(
+        &self,
+        key: K,
+        value: V,
+        ttl: Duration,
+        guard: &'g impl Guard,
+    ) -> Option<&'g V> {
+        self.insert(key, Expiring::new(value, ttl), guard)
+            .filter(|old| !old.is_expired())
+            .map(Expiring::value)
+    }
+
+    /// Returns a reference to the value corresponding to the key, if it has not expired.
+    ///
+    /// If the value for the key has expired, it is treated as absent and the entry is
+    /// removed from the map. Note that an entry is only removed if it is still expired
+    /// when the removal takes place, so a concurrent insert of a fresh value is retained.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::time::Duration;
+    ///
+    /// let map = HashMap::new();
+    /// map.insert_with_ttl(1, "a", Duration::ZERO, &map.guard());
+    ///
+    /// assert_eq!(map.get_unexpired(&1, &map.guard()), None);
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn get_unexpired<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        K: 'g,
+        V: 'g,
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        let guard = self.raw.verify(guard);
+        let (_, value) = self.raw.get(key, guard)?;
+
+        if !value.is_expired() {
+            return Some(value.value());
+        }
+
+        // Lazily remove the expired entry.
+        self.raw
+            .remove_if(key, |_, value| value.is_expired(), guard);
+        None
+    }
+
+    /// Removes all expired entries from the map, returning the number of entries removed.
+    ///
+    /// See [`HashMap::retain`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    /// use std::time::Duration;
+    ///
+    /// let map = HashMap::new();
+    /// map.insert_with_ttl("a", 1, Duration::from_secs(60), &map.guard());
+    /// map.insert_with_ttl("b", 2, Duration::ZERO, &map.guard());
+    ///
+    /// assert_eq!(map.purge_expired(&map.guard()), 1);
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    #[inline]
+    pub fn purge_expired(&self, guard: &impl Guard) -> usize {
+        let now = Instant::now();
+        let mut removed = 0;
+        self.raw.retain_with(
+            |_, value| !value.is_expired_at(now),
+            |_, _| removed += 1,
+            self.raw.verify(guard),
+        );
+        removed
+    }
+}
+
+/// An operation to perform on given entry in a [`HashMap`].
+///
+/// See [`HashMap::compute`] for details.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Operation<V, T> {
+    /// Insert the given value.
+    Insert(V),
+
+    /// Remove the entry from the map.
+    Remove,
+
+    /// Abort the operation with the given value.
+    Abort(T),
+}
+
+/// The result of a [`compute`](HashMap::compute) operation.
+///
+/// Contains information about the [`Operation`] that was performed.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Compute<'g, K, V, T> {
+    /// The given entry was inserted.
+    Inserted(&'g K, &'g V),
+
+    /// The entry was updated.
+    Updated {
+        /// The entry that was replaced.
+        old: (&'g K, &'g V),
+
+        /// The entry that was inserted.
+        new: (&'g K, &'g V),
+    },
+
+    /// The given entry was removed.
+    Removed(&'g K, &'g V),
+
+    /// The operation was aborted with the given value.
+    Aborted(T),
+}
+
+/// The result of a [`try_get`](HashMap::try_get) operation.
+#[derive(Debug, PartialEq, Eq)]
+pub enum GetResult<'g, V> {
+    /// The key was found in the map.
+    Found(&'g V),
+
+    /// The key is not present in the map.
+    Absent,
+
+    /// The key may be present in a table that is currently being migrated to.
+    ///
+    /// The lookup can be retried later, or completed with [`HashMap::get`].
+    Migrating,
+}
+
+/// Statistics about hash collisions in a [`HashMap`].
+///
+/// This struct is created by the [`collision_report`](HashMap::collision_report) method on
+/// [`HashMap`]. See its documentation for details.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct CollisionReport {
+    /// The number of entries that were inspected.
+    pub entries: usize,
+
+    /// The longest probe sequence of any entry.
+    ///
+    /// A probe length of zero means that the entry is stored in the first slot it hashes to.
+    pub max_probe_len: usize,
+
+    /// The average probe sequence length across all entries.
+    pub average_probe_len: f64,
+
+    /// The number of entries that are not stored in the first slot they hash to.
+    pub displaced: usize,
+
+    /// The number of groups of 8 consecutive slots that are fully occupied, either by entries or
+    /// by tombstones left behind by removals.
+    pub full_groups: usize,
+}
+
+/// A handle to an entry in a [`HashMap`].
+///
+/// This struct is created by the [`entry`](HashMap::entry) method on [`HashMap`]. See its
+/// documentation for details.
+pub struct Entry<'g, K, V, S, G> {
+    key: K,
+    hash: u64,
+    slot: Cell<Option<raw::Slot<K, V>>>,
+    map: &'g HashMap<K, V, S>,
+    guard: &'g MapGuard<G>,
+}
+
+impl<'g, K, V, S, G> Entry<'g, K, V, S, G>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    G: Guard,
+{
+    /// Returns a reference to the key of this entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map: HashMap<&str, i32> = HashMap::new();
+    /// assert_eq!(map.pin().entry("a").key(), &"a");
+    /// ```
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns a reference to the current value of this entry.
+    ///
+    /// See [`HashMap::get`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    /// map.insert("a", 1);
+    ///
+    /// assert_eq!(map.entry("a").get(), Some(&1));
+    /// assert_eq!(map.entry("b").get(), None);
+    /// ```
+    #[inline]
+    pub fn get(&self) -> Option<&'g V> {
+        let entry = match self.slot.get() {
+            Some(slot) => self
+                .map
+                .raw
+                .get_at_slot(slot, self.hash, &self.key, self.guard),
+            None => self.map.raw.get_with_hash(self.hash, &self.key, self.guard),
+        };
+
+        match entry {
+            Some((_, value)) => Some(value),
+            None => None,
+        }
+    }
+
+    /// Inserts a value into this entry, returning the previous value if one was present.
+    ///
+    /// See [`HashMap::insert`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    ///
+    /// let entry = map.entry("a");
+    /// assert_eq!(entry.insert(1), None);
+    /// assert_eq!(entry.insert(2), Some(&1));
+    /// assert_eq!(map.get("a"), Some(&2));
+    /// ```
+    #[inline]
+    pub fn insert(&self, value: V) -> Option<&'g V>
+    where
+        K: Clone,
+    {
+        let result = match self.slot.get() {
+            Some(slot) => {
+                self.map
+                    .raw
+                    .insert_at_slot(slot, self.hash, self.key.clone(), value, self.guard)
+            }
+            None => {
+                self.map
+                    .raw
+                    .insert_with_hash(self.hash, self.key.clone(), value, true, self.guard)
+            }
+        };
+
+        match result {
+            InsertResult::Inserted(_) => None,
+            InsertResult::Replaced(value) => Some(value),
+            InsertResult::Error { .. } => unreachable!(),
+        }
+    }
+
+    /// Updates the value of this entry atomically, returning the new value, or `None`
+    /// if the entry is not present.
+    ///
+    /// See [`HashMap::update`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    ///
+    /// let entry = map.entry("a");
+    /// assert_eq!(entry.update(|v| v + 1), None);
+    ///
+    /// entry.insert(1);
+    /// assert_eq!(entry.update(|v| v + 1), Some(&2));
+    /// assert_eq!(entry.update(|v| v + 1), Some(&3));
+    /// ```
+    #[inline]
+    pub fn update<F>(&self, mut update: F) -> Option<&'g V>
+    where
+        F: FnMut(&V) -> V,
+        K: Clone,
+    {
+        if let Some(mut slot) = self.slot.get() {
+            let value = self.map.raw.update_at_slot(
+                &mut slot,
+                self.hash,
+                self.key.clone(),
+                update,
+                self.guard,
+            );
+            self.slot.set(Some(slot));
+            return value;
+        }
+
+        let compute = |entry| match entry {
+            // There is nothing to update.
+            None => Operation::Abort(()),
+            // Perform the update.
+            Some((_, value)) => Operation::Insert(update(value)),
+        };
+
+        match self.compute(compute) {
+            // Return the updated value.
+            Compute::Updated {
+                new: (_, value), ..
+            } => Some(value),
+
+            // There was nothing to update.
+            Compute::Aborted(_) => None,
+
+            _ => unreachable!(),
+        }
+    }
+
+    /// Updates this entry with a compare-and-swap (CAS) function.
+    ///
+    /// See [`HashMap::compute`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::{Compute, HashMap, Operation};
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    ///
+    /// let entry = map.entry("a");
+    /// let increment = |entry: Option<(&_, &i32)>| match entry {
+    ///     Some((_, value)) => Operation::Insert::<_, ()>(value + 1),
+    ///     None => Operation::Insert(0),
+    /// };
+    ///
+    /// assert_eq!(entry.compute(increment), Compute::Inserted(&"a", &0));
+    /// assert!(matches!(entry.compute(increment), Compute::Updated { new: (_, 1), .. }));
+    /// ```
+    #[inline]
+    pub fn compute<F, T>(&self, compute: F) -> Compute<'g, K, V, T>
+    where
+        F: FnMut(Option<(&'g K, &'g V)>) -> Operation<V, T>,
+        K: Clone,
+    {
+        let (result, slot) =
+            self.map
+                .raw
+                .compute_with_slot(self.hash, self.key.clone(), compute, self.guard);
+
+        // Remember the location of the entry for later operations.
+        if slot.is_some() {
+            self.slot.set(slot);
+        }
+
+        result
+    }
+
+    /// Ensures a value is present in this entry by inserting the given value if the key is
+    /// vacant, returning a handle to the occupied entry.
+    ///
+    /// Unlike [`HashMap::get_or_insert`], the returned [`OccupiedEntry`] remembers where the
+    /// entry is stored in the table, allowing follow-up operations such as
+    /// [`OccupiedEntry::update`] to access the entry directly without probing for the key again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    ///
+    /// let entry = map.entry("a").or_insert(0);
+    /// assert_eq!(entry.get(), Some(&0));
+    /// assert_eq!(entry.update(|v| v + 1), Some(&1));
+    ///
+    /// let entry = map.entry("a").or_insert(10);
+    /// assert_eq!(entry.get(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn or_insert(self, value: V) -> OccupiedEntry<'g, K, V, S, G> {
+        self.or_insert_with(|| value)
+    }
+
+    /// Ensures a value is present in this entry by inserting the result of the given closure
+    /// if the key is vacant, returning a handle to the occupied entry.
+    ///
+    /// The closure is only called if the key is vacant. See [`Entry::or_insert`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    ///
+    /// let entry = map.entry("a").or_insert_with(Vec::new);
+    /// assert_eq!(entry.update(|v| [v.as_slice(), &[1]].concat()), Some(&vec![1]));
+    /// assert_eq!(map.get("a"), Some(&vec![1]));
+    /// ```
+    #[inline]
+    pub fn or_insert_with<F>(self, f: F) -> OccupiedEntry<'g, K, V, S, G>
+    where
+        F: FnOnce() -> V,
+    {
+        let mut f = Some(f);
+        let compute = |entry| match entry {
+            // There is already an existing value.
+            Some((key, value)) => Operation::Abort((key, value)),
+
+            // Insert the initial value.
+            //
+            // Note that this case is guaranteed to be executed at most
+            // once as insert values are cached, so this can never panic.
+            None => Operation::Insert((f.take().unwrap())()),
+        };
+
+        let (key, slot) = match self
+            .map
+            .raw
+            .compute_with_slot(self.hash, self.key, compute, self.guard)
+        {
+            (Compute::Inserted(key, _), slot) | (Compute::Aborted((key, _)), slot) => (key, slot),
+            _ => unreachable!(),
+        };
+
+        OccupiedEntry {
+            key,
+            hash: self.hash,
+            // The slot is always recorded when an entry is inserted or found.
+            slot: Cell::new(slot.unwrap()),
+            map: self.map,
+            guard: self.guard,
+        }
+    }
+
+    /// Removes this entry from the map, returning the value if it was present.
+    ///
+    /// See [`HashMap::remove`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let map = map.pin();
+    /// map.insert("a", 1);
+    ///
+    /// assert_eq!(map.entry("a").remove(), Some(&1));
+    /// assert_eq!(map.entry("a").remove(), None);
+    /// ```
+    #[inline]
+    pub fn remove(self) -> Option<&'g V> {
+        let entry = match self.slot.get() {
+            Some(slot) => self
+                .map
+                .raw
+                .remove_at_slot(slot, self.hash, &self.key, self.guard),
+            None => self
+                .map
+                .raw
+                .remove_with_hash(self.hash, &self.key, self.guard),
+        };
+
+        match entry {
+            Some((_, value)) => Some(value),
+            None => None,
+        }
+    }
+}
+
+impl<K, V, S, G> fmt::Debug for Entry<'_, K, V, S, G>
+where
+    K: Hash + Eq + fmt::Debug,
+    V: fmt::Debug,
+    S: BuildHasher,
+    G: Guard,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Entry")
+            .field("key", &self.key)
+            .field("value", &self.get())
+            .finish()
+    }
+}
+
+/// A handle to an entry in a [`HashMap`] that is known to be present.
+///
+/// This struct is created by the [`or_insert`](Entry::or_insert) and
+/// [`or_insert_with`](Entry::or_insert_with) methods on [`Entry`]. See their documentation
+/// for details.
+///
+/// The handle stores the location of the entry in the table, so operations on the handle access
+/// the entry directly, falling back to probing for the key if the entry was concurrently removed
+/// or migrated to a new table by a resize. Like [`Entry`], the handle does not lock the entry,
+/// and every operation is applied atomically and independently.
+pub struct OccupiedEntry<'g, K, V, S, G> {
+    key: &'g K,
+    hash: u64,
+    slot: Cell<raw::Slot<K, V>>,
+    map: &'g HashMap<K, V, S>,
+    guard: &'g MapGuard<G>,
+}
+
+impl<'g, K, V, S, G> OccupiedEntry<'g, K, V, S, G>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    G: Guard,
+{
+    /// Returns a reference to the key of this entry.
+    #[inline]
+    pub fn key(&self) -> &'g K {
+        self.key
+    }
+
+    /// Returns a reference to the current value of this entry, or `None` if the entry has
+    /// since been removed.
+    ///
+    /// See [`HashMap::get`] for details.
+    #[inline]
+    pub fn get(&self) -> Option<&'g V> {
+        match self
+            .map
+            .raw
+            .get_at_slot(self.slot.get(), self.hash, self.key, self.guard)
+        {
+            Some((_, value)) => Some(value),
+            None => None,
+        }
+    }
+
+    /// Updates the value of this entry atomically, returning the new value, or `None`
+    /// if the entry has since been removed.
+    ///
+    /// See [`HashMap::update`] for details.
+    #[inline]
+    pub fn update<F>(&self, update: F) -> Option<&'g V>
+    where
+        F: FnMut(&V) -> V,
+        K: Clone,
+    {
+        let mut slot = self.slot.get();
+        let value =
+            self.map
+                .raw
+                .update_at_slot(&mut slot, self.hash, self.key.clone(), update, self.guard);
+        self.slot.set(slot);
+        value
+    }
+
+    /// Inserts a value into this entry, returning the previous value, or `None` if the entry
+    /// had since been removed.
+    ///
+    /// See [`HashMap::insert`] for details.
+    #[inline]
+    pub fn insert(&self, value: V) -> Option<&'g V>
+    where
+        K: Clone,
+    {
+        match self.map.raw.insert_at_slot(
+            self.slot.get(),
+            self.hash,
+            self.key.clone(),
+            value,
+            self.guard,
+        ) {
+            InsertResult::Inserted(_) => None,
+            InsertResult::Replaced(value) => Some(value),
+            InsertResult::Error { .. } => unreachable!(),
+        }
+    }
+
+    /// Removes this entry from the map, returning the value, or `None` if the entry had
+    /// since been removed.
+    ///
+    /// See [`HashMap::remove`] for details.
+    #[inline]
+    pub fn remove(self) -> Option<&'g V> {
+        match self
+            .map
+            .raw
+            .remove_at_slot(self.slot.get(), self.hash, self.key, self.guard)
+        {
+            Some((_, value)) => Some(value),
+            None => None,
+        }
+    }
+}
+
+impl<K, V, S, G> fmt::Debug for OccupiedEntry<'_, K, V, S, G>
+where
+    K: Hash + Eq + fmt::Debug,
+    V: fmt::Debug,
+    S: BuildHasher,
+    G: Guard,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", &self.key)
+            .field("value", &self.get())
+            .finish()
+    }
+}
+
+/// An error returned by [`try_insert`](HashMap::try_insert) when the key already exists.
+///
+/// Contains the existing value, and the value that was not inserted.
+#[derive(Debug, PartialEq, Eq)]
+pub struct OccupiedError<'a, V: 'a> {
+    /// The value in the map that was already present.
+    pub current: &'a V,
+    /// The value which was not inserted, because the entry was already occupied.
+    pub not_inserted: V,
+}
+
+/// An error returned by [`insert_bounded`](HashMap::insert_bounded) when the map is at capacity.
+///
+/// Contains the key and value that were not inserted.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CapacityError<K, V> {
+    /// The key which was not inserted.
+    pub key: K,
+    /// The value which was not inserted.
+    pub value: V,
+}
+
+/// An error returned by [`try_insert_no_resize`](HashMap::try_insert_no_resize) when the
+/// insert would resize the table.
+///
+/// Contains the key and value that were not inserted.
+#[derive(Debug, PartialEq, Eq)]
+pub struct WouldResize<K, V> {
+    /// The key which was not inserted.
+    pub key: K,
+    /// The value which was not inserted.
+    pub value: V,
+}
+
+impl<K, V, S> PartialEq for HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: PartialEq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        let (guard1, guard2) = (&self.guard(), &other.guard());
+
+        let mut iter = self.iter(guard1);
+        iter.all(|(key, value)| other.get(key, guard2).is_some_and(|v| *value == *v))
+    }
+}
+
+impl<K, V, S> Eq for HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<K, V, S> fmt::Debug for HashMap<K, V, S>
+where
+    K: Hash + Eq + fmt::Debug,
+    V: fmt::Debug,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = self.guard();
+        f.debug_map().entries(self.iter(&guard)).finish()
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for &HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        // from `hashbrown::HashMap::extend`:
+        // Keys may be already present or show multiple times in the iterator.
+        // Reserve the entire hint lower bound if the map is empty.
+        // Otherwise reserve half the hint (rounded up), so the map
+        // will only resize twice in the worst case.
+        let iter = iter.into_iter();
+        let reserve = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            (iter.size_hint().0 + 1) / 2
+        };
+
+        let guard = self.guard();
+        self.reserve(reserve, &guard);
+
+        for (key, value) in iter {
+            self.insert(key, value, &guard);
+        }
+    }
+}
+
+impl<'a, K, V, S> Extend<(&'a K, &'a V)> for &HashMap<K, V, S>
+where
+    K: Copy + Hash + Eq,
+    V: Copy,
+    S: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
+    }
+}
+
+impl<K, V, const N: usize> From<[(K, V); N]> for HashMap<K, V, RandomState>
+where
+    K: Hash + Eq,
+{
+    fn from(arr: [(K, V); N]) -> Self {
+        HashMap::from_iter(arr)
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut iter = iter.into_iter();
+
+        if let Some((key, value)) = iter.next() {
+            let (lower, _) = iter.size_hint();
+            let map = HashMap::with_capacity_and_hasher(lower.saturating_add(1), S::default());
+
+            // Ideally we could use an unprotected guard here. However, `insert`
+            // returns references to values that were replaced and retired, so
+            // we need a "real" guard. A `raw_insert` method that strictly returns
+            // pointers would fix this.
+            {
+                let map = map.pin();
+                map.insert(key, value);
+                for (key, value) in iter {
+                    map.insert(key, value);
+                }
+            }
+
+            map
+        } else {
+            Self::default()
+        }
+    }
+}
+
+impl<K, V, S> Clone for HashMap<K, V, S>
+where
+    K: Clone + Hash + Eq,
+    V: Clone,
+    S: BuildHasher + Clone,
+{
+    fn clone(&self) -> HashMap<K, V, S> {
+        let other = HashMap::builder()
+            .capacity(self.len())
+            .hasher(self.raw.hasher.clone())
+            .collector(self.raw.collector().clone())
+            .build();
+
+        {
+            let (guard1, guard2) = (&self.guard(), &other.guard());
+            for (key, value) in self.iter(guard1) {
+                other.insert(key.clone(), value.clone(), guard2);
+            }
+        }
+
+        other
+    }
+}
+
+/// A read-only view of a [`HashMap`].
+///
+/// This type is created with [`HashMap::as_readonly`], and only exposes methods that read from
+/// the map, such as [`get`](ReadOnlyView::get) and [`iter`](ReadOnlyView::iter). As all
+/// [`HashMap`] methods take a shared reference, a bare `&HashMap` allows callers to mutate the map.
+/// Passing a `ReadOnlyView` instead enforces read-only access at the type level.
+///
+/// # Examples
+///
+/// ```
+/// use papaya::{HashMap, ReadOnlyView};
+///
+/// fn total(view: ReadOnlyView<'_, &str, i32>) -> i32 {
+///     let guard = view.guard();
+///     view.values(&guard).sum()
+/// }
+///
+/// let map = HashMap::new();
+/// map.pin().insert("a", 1);
+/// map.pin().insert("b", 2);
+/// assert_eq!(total(map.as_readonly()), 3);
+/// ```
+///
+/// Attempting to modify the map through a view fails to compile:
+///
+/// ```compile_fail
+/// let map = papaya::HashMap::new();
+/// let view = map.as_readonly();
+/// view.insert(1, "a", &view.guard());
+/// ```
+pub struct ReadOnlyView<'map, K, V, S = RandomState> {
+    map: &'map HashMap<K, V, S>,
+}
+
+impl<K, V, S> Clone for ReadOnlyView<'_, K, V, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V, S> Copy for ReadOnlyView<'_, K, V, S> {}
+
+impl<'map, K, V, S> ReadOnlyView<'map, K, V, S> {
+    /// Returns a guard for use with this view.
+    ///
+    /// See [`HashMap::guard`] for details.
+    #[inline]
+    pub fn guard(&self) -> LocalGuard<'map> {
+        self.map.guard()
+    }
+
+    /// Returns an owned guard for use with this view.
+    ///
+    /// See [`HashMap::owned_guard`] for details.
+    #[inline]
+    pub fn owned_guard(&self) -> OwnedGuard<'map> {
+        self.map.owned_guard()
+    }
+
+    /// Returns a reference to the map's [`BuildHasher`].
+    ///
+    /// See [`HashMap::hasher`] for details.
+    #[inline]
+    pub fn hasher(&self) -> &'map S {
+        self.map.hasher()
+    }
+}
+
+impl<'map, K, V, S> ReadOnlyView<'map, K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns the number of entries in the map.
+    ///
+    /// See [`HashMap::len`] for details.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map is empty. Otherwise returns `false`.
+    ///
+    /// See [`HashMap::is_empty`] for details.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// See [`HashMap::contains_key`] for details.
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q, guard: &impl Guard) -> bool
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.map.contains_key(key, guard)
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// See [`HashMap::get`] for details.
+    #[inline]
+    pub fn get<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> Option<&'g V>
+    where
+        K: 'g,
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.map.get(key, guard)
+    }
+
+    /// Returns a reference to the key and value corresponding to the key.
+    ///
+    /// See [`HashMap::get_key_value`] for details.
+    #[inline]
+    pub fn get_key_value<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> Option<(&'g K, &'g V)>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.map.get_key_value(key, guard)
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order.
+    ///
+    /// See [`HashMap::iter`] for details.
+    #[inline]
+    pub fn iter<'g, G>(&self, guard: &'g G) -> Iter<'g, K, V, G>
+    where
+        G: Guard,
+    {
+        self.map.iter(guard)
+    }
+
+    /// An iterator visiting all keys in arbitrary order.
+    ///
+    /// See [`HashMap::keys`] for details.
+    #[inline]
+    pub fn keys<'g, G>(&self, guard: &'g G) -> Keys<'g, K, V, G>
+    where
+        G: Guard,
+    {
+        self.map.keys(guard)
+    }
+
+    /// An iterator visiting all values in arbitrary order.
+    ///
+    /// See [`HashMap::values`] for details.
+    #[inline]
+    pub fn values<'g, G>(&self, guard: &'g G) -> Values<'g, K, V, G>
+    where
+        G: Guard,
+    {
+        self.map.values(guard)
+    }
+}
+
+impl<K, V, S> fmt::Debug for ReadOnlyView<'_, K, V, S>
+where
+    K: Hash + Eq + fmt::Debug,
+    V: fmt::Debug,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.map, f)
+    }
+}
+
+/// A [`HashMap`] that can no longer be modified.
+///
+/// This type is created with [`HashMap::freeze`]. As the map cannot be modified, reads do not
+/// require a guard and never enter the collector, avoiding the cost of memory reclamation. The
+/// map can be converted back into a [`HashMap`] with [`thaw`](FrozenHashMap::thaw).
+///
+/// # Examples
+///
+/// ```
+/// use papaya::HashMap;
+///
+/// let map = HashMap::from([("a", 1), ("b", 2)]);
+/// let frozen = map.freeze();
+///
+/// assert_eq!(frozen.get("a"), Some(&1));
+/// assert_eq!(frozen.values().sum::<i32>(), 3);
+/// ```
+pub struct FrozenHashMap<K, V, S = RandomState> {
+    map: HashMap<K, V, S>,
+    guard: UnprotectedGuard,
+}
+
+impl<K, V, S> FrozenHashMap<K, V, S> {
+    /// Converts the map back into a [`HashMap`] that can be modified.
+    #[inline]
+    pub fn thaw(self) -> HashMap<K, V, S> {
+        self.map
+    }
+
+    /// Returns a reference to the map's [`BuildHasher`].
+    ///
+    /// See [`HashMap::hasher`] for details.
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+}
+
+impl<K, V, S> FrozenHashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns the number of entries in the map.
+    ///
+    /// See [`HashMap::len`] for details.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map is empty. Otherwise returns `false`.
+    ///
+    /// See [`HashMap::is_empty`] for details.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// See [`HashMap::contains_key`] for details.
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.map.contains_key(key, &self.guard)
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// See [`HashMap::get`] for details.
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.map.get(key, &self.guard)
+    }
+
+    /// Returns a reference to the key and value corresponding to the key.
+    ///
+    /// See [`HashMap::get_key_value`] for details.
+    #[inline]
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.map.get_key_value(key, &self.guard)
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order.
+    ///
+    /// See [`HashMap::iter`] for details.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V, impl Guard> {
+        self.map.iter(&self.guard)
+    }
+
+    /// An iterator visiting all keys in arbitrary order.
+    ///
+    /// See [`HashMap::keys`] for details.
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, K, V, impl Guard> {
+        self.map.keys(&self.guard)
+    }
+
+    /// An iterator visiting all values in arbitrary order.
+    ///
+    /// See [`HashMap::values`] for details.
+    #[inline]
+    pub fn values(&self) -> Values<'_, K, V, impl Guard> {
+        self.map.values(&self.guard)
+    }
+}
+
+impl<K, V, S> fmt::Debug for FrozenHashMap<K, V, S>
+where
+    K: Hash + Eq + fmt::Debug,
+    V: fmt::Debug,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// A pinned reference to a [`HashMap`].
+///
+/// This type is created with [`HashMap::pin`] and can be used to easily access a [`HashMap`]
+/// without explicitly managing a guard. See the [crate-level documentation](crate#usage) for details.
+///
+/// Every method on [`HashMap`] that accepts a guard is mirrored by a method on the pinned reference
+/// that uses the guard held by the reference instead. References returned by the pinned reference,
+/// including those produced by iterators such as [`HashMapRef::iter`], live as long as the pinned
+/// reference itself.
+pub struct HashMapRef<'map, K, V, S, G> {
+    guard: MapGuard<G>,
+    map: &'map HashMap<K, V, S>,
+}
+
+/// The pinned reference returned by [`HashMap::pin`].
+///
+/// This is a shorthand for a [`HashMapRef`] holding a [`LocalGuard`], useful for naming the
+/// pinned reference in function signatures.
+pub type HashMapPin<'map, K, V, S = RandomState> = HashMapRef<'map, K, V, S, LocalGuard<'map>>;
+
+/// The pinned reference returned by [`HashMap::pin_owned`].
+///
+/// This is a shorthand for a [`HashMapRef`] holding an [`OwnedGuard`], useful for naming the
+/// pinned reference in function signatures.
+pub type HashMapPinOwned<'map, K, V, S = RandomState> = HashMapRef<'map, K, V, S, OwnedGuard<'map>>;
+
+impl<'map, K, V, S, G> HashMapRef<'map, K, V, S, G>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    G: Guard,
+{
+    /// Returns a reference to the inner [`HashMap`].
+    #[inline]
+    pub fn map(&self) -> &'map HashMap<K, V, S> {
+        self.map
+    }
+
+    /// Returns a reference to the map's [`BuildHasher`].
+    ///
+    /// See [`HashMap::hasher`] for details.
+    #[inline]
+    pub fn hasher(&self) -> &'map S {
+        self.map.hasher()
+    }
+
+    /// Returns the hash that the map uses to locate entries, given the output of the
+    /// map's hasher.
+    ///
+    /// See [`HashMap::finish_hash`] for details.
+    #[inline]
+    pub fn finish_hash(&self, hash: u64) -> u64 {
+        self.map.finish_hash(hash)
+    }
+
+    /// Returns the number of times the map has been resized over its lifetime.
+    ///
+    /// See [`HashMap::resize_count`] for details.
+    #[inline]
+    pub fn resize_count(&self) -> (u64, u64) {
+        self.map.resize_count()
+    }
+
+    /// Returns the number of guards created for this map over its lifetime.
+    ///
+    /// See [`HashMap::guard_count`] for details.
+    #[inline]
+    #[cfg(feature = "stats")]
+    pub fn guard_count(&self) -> (u64, u64) {
+        self.map.guard_count()
+    }
+
+    /// Returns the number of entries in the map.
+    ///
+    /// See [`HashMap::len`] for details.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.raw.len()
+    }
+
+    /// Returns `true` if the map is empty. Otherwise returns `false`.
+    ///
+    /// See [`HashMap::is_empty`] for details.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// See [`HashMap::contains_key`] for details.
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Returns whether each of the given keys is present in the map.
+    ///
+    /// See [`HashMap::contains_mask`] for details.
+    #[inline]
+    pub fn contains_mask<Q>(&self, keys: &[&Q]) -> Vec<bool>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        keys.iter()
+            .map(|key| self.map.raw.get(*key, &self.guard).is_some())
+            .collect()
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// See [`HashMap::get`] for details.
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        match self.map.raw.get(key, &self.guard) {
+            Some((_, v)) => Some(v),
+            None => None,
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the key, panicking if the key
+    /// is not present.
+    ///
+    /// See [`HashMap::get_expect`] for details.
+    #[inline]
+    #[track_caller]
+    pub fn get_expect<Q>(&self, key: &Q) -> &V
+    where
+        Q: Equivalent<K> + Hash + fmt::Debug + ?Sized,
+    {
+        match self.get(key) {
+            Some(value) => value,
+            None => panic!("key {key:?} not found in map"),
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the key, using a precomputed hash.
+    ///
+    /// See [`HashMap::get_with_hash`] for details.
+    #[inline]
+    pub fn get_with_hash<Q>(&self, hash: u64, key: &Q) -> Option<&V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.map.get_with_hash(hash, key, &self.guard)
+    }
+
+    /// Returns `true` if the map contains a value for the specified key, using a precomputed hash.
+    ///
+    /// See [`HashMap::contains_key_with_hash`] for details.
+    #[inline]
+    pub fn contains_key_with_hash<Q>(&self, hash: u64, key: &Q) -> bool
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.get_with_hash(hash, key).is_some()
+    }
+
+    /// Returns a reference to the value corresponding to the key, without
+    /// searching through any in-progress resizes.
+    ///
+    /// See [`HashMap::try_get`] for details.
+    #[inline]
+    pub fn try_get<Q>(&self, key: &Q) -> GetResult<'_, V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.map.raw.try_get(key, &self.guard)
+    }
+
+    /// Returns a pinned reference to the value corresponding to the key.
+    ///
+    /// See [`HashMap::get_pin`] for details.
+    #[inline]
+    pub fn get_pin<Q>(&self, key: &Q) -> Option<Pin<&V>>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        match self.map.raw.get(key, &self.guard) {
+            // Safety: See `HashMap::get_pin`.
+            Some((_, v)) => Some(unsafe { Pin::new_unchecked(v) }),
+            None => None,
+        }
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    ///
+    /// See [`HashMap::get_key_value`] for details.
+    #[inline]
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.map.raw.get(key, &self.guard)
+    }
+
+    /// Returns references to the values corresponding to multiple keys, as observed
+    /// at a single point in time.
+    ///
+    /// See [`HashMap::get_consistent`] for details.
+    #[inline]
+    pub fn get_consistent<Q, const N: usize>(&self, keys: [&Q; N]) -> [Option<&V>; N]
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.map
+            .raw
+            .get_consistent(keys, &self.guard)
+            .map(|entry| entry.map(|(_, value)| value))
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// See [`HashMap::insert`] for details.
+    #[inline]
+    pub fn insert(&self, key: K, value: V) -> Option<&V> {
+        match self.map.raw.insert(key, value, true, &self.guard) {
+            InsertResult::Inserted(_) => None,
+            InsertResult::Replaced(value) => Some(value),
+            InsertResult::Error { .. } => unreachable!(),
+        }
+    }
+
+    /// Inserts a key-value pair into the map, converting the key and value with [`Into`].
+    ///
+    /// See [`HashMap::insert_into`] for details.
+    #[inline]
+    pub fn insert_into(&self, key: impl Into<K>, value: impl Into<V>) -> Option<&V> {
+        self.insert(key.into(), value.into())
+    }
+
+    /// Inserts a key-value pair into the map, also returning whether the insert grew the table.
+    ///
+    /// See [`HashMap::insert_tracked`] for details.
+    #[inline]
+    pub fn insert_tracked(&self, key: K, value: V) -> (Option<&V>, bool) {
+        match self.map.raw.insert_tracked(key, value, &self.guard) {
+            (InsertResult::Inserted(_), grew) => (None, grew),
+            (InsertResult::Replaced(value), grew) => (Some(value), grew),
+            (InsertResult::Error { .. }, _) => unreachable!(),
+        }
+    }
+
+    /// Inserts a key-value pair into the map, unless the key is not present and the map
+    /// already contains at least `capacity` entries.
+    ///
+    /// See [`HashMap::insert_bounded`] for details.
+    #[inline]
+    pub fn insert_bounded(
+        &self,
+        key: K,
+        value: V,
+        capacity: usize,
+    ) -> Result<Option<&V>, CapacityError<K, V>> {
+        self.map.insert_bounded(key, value, capacity, &self.guard)
+    }
+
+    /// Inserts a key-value pair into the map, evicting entries chosen by `select_victim` to
+    /// keep the map within `capacity` entries.
+    ///
+    /// See [`HashMap::insert_evicting`] for details.
+    #[inline]
+    pub fn insert_evicting<F, E>(
+        &self,
+        key: K,
+        value: V,
+        capacity: usize,
+        select_victim: F,
+        on_evict: E,
+    ) -> Result<Option<&V>, CapacityError<K, V>>
+    where
+        F: FnMut() -> Option<K>,
+        E: FnMut(&K, &V),
+    {
+        self.map
+            .insert_evicting(key, value, capacity, select_victim, on_evict, &self.guard)
+    }
+
+    /// Inserts a key-value pair into the map, unless doing so would resize the table.
+    ///
+    /// See [`HashMap::try_insert_no_resize`] for details.
+    #[inline]
+    pub fn try_insert_no_resize(&self, key: K, value: V) -> Result<Option<&V>, WouldResize<K, V>> {
+        self.map.try_insert_no_resize(key, value, &self.guard)
+    }
+
+    /// Inserts the key-value pairs from an iterator, assuming that every key is distinct and
+    /// not already present in the map.
+    ///
+    /// See [`HashMap::extend_unique`] for details.
+    #[inline]
+    pub fn extend_unique<I>(&self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        self.map.extend_unique(iter, &self.guard)
+    }
+
+    /// Inserts a key-value pair into the map, using a precomputed hash.
+    ///
+    /// See [`HashMap::insert_with_hash`] for details.
+    #[inline]
+    pub fn insert_with_hash(&self, hash: u64, key: K, value: V) -> Option<&V> {
+        self.map.insert_with_hash(hash, key, value, &self.guard)
+    }
+
+    /// Inserts a key-value pair into the map, unless the key is already mapped to an equal value.
+    ///
+    /// See [`HashMap::insert_if_changed`] for details.
+    #[inline]
+    pub fn insert_if_changed(&self, key: K, value: V) -> bool
+    where
+        V: PartialEq + Clone,
+    {
+        self.map.insert_if_changed(key, value, &self.guard)
+    }
+
+    /// Tries to insert a key-value pair into the map, and returns
+    /// a reference to the value that was inserted.
+    ///
+    /// See [`HashMap::try_insert`] for details.
+    #[inline]
+    pub fn try_insert(&self, key: K, value: V) -> Result<&V, OccupiedError<'_, V>> {
+        match self.map.raw.insert(key, value, false, &self.guard) {
+            InsertResult::Inserted(value) => Ok(value),
+            InsertResult::Error {
+                current,
+                not_inserted,
+            } => Err(OccupiedError {
+                current,
+                not_inserted,
+            }),
+            InsertResult::Replaced(_) => unreachable!(),
+        }
+    }
+
+    /// Tries to insert a key and value computed from a closure into the map,
+    /// and returns a reference to the value that was inserted.
+    ///
+    /// See [`HashMap::try_insert_with`] for details.
+    #[inline]
+    pub fn try_insert_with<F>(&self, key: K, f: F) -> Result<&V, &V>
+    where
+        F: FnOnce() -> V,
+    {
+        self.map.raw.try_insert_with(key, f, &self.guard)
+    }
+
+    /// Returns a reference to the value corresponding to the key, or inserts a default value.
+    ///
+    /// See [`HashMap::get_or_insert`] for details.
+    #[inline]
+    pub fn get_or_insert(&self, key: K, value: V) -> &V {
+        // Note that we use `try_insert` instead of `compute` or `get_or_insert_with` here, as it
+        // allows us to avoid the closure indirection.
+        match self.try_insert(key, value) {
+            Ok(inserted) => inserted,
+            Err(OccupiedError { current, .. }) => current,
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the key, or inserts a default value
+    /// computed from a closure.
+    ///
+    /// See [`HashMap::get_or_insert_with`] for details.
+    #[inline]
+    pub fn get_or_insert_with<F>(&self, key: K, f: F) -> &V
+    where
+        F: FnOnce() -> V,
+    {
+        self.map.raw.get_or_insert_with(key, f, &self.guard)
+    }
+
+    /// Returns a reference to the value corresponding to the key, or inserts a default value
+    /// computed from a closure that is given a reference to the key.
+    ///
+    /// See [`HashMap::get_or_insert_with_key`] for details.
+    #[inline]
+    pub fn get_or_insert_with_key<F>(&self, key: K, f: F) -> &V
+    where
+        F: FnOnce(&K) -> V,
+    {
+        self.map.raw.get_or_insert_with_key(key, f, &self.guard)
+    }
+
+    /// Updates an existing entry atomically.
+    ///
+    /// See [`HashMap::update`] for details.
+    #[inline]
+    pub fn update<F>(&self, key: K, update: F) -> Option<&V>
+    where
+        F: Fn(&V) -> V,
+    {
+        self.map.raw.update(key, update, &self.guard)
+    }
+
+    /// Updates an existing entry atomically, using a function that is also given the key.
+    ///
+    /// See [`HashMap::update_with_key`] for details.
+    #[inline]
+    pub fn update_with_key<F>(&self, key: K, update: F) -> Option<&V>
+    where
+        F: Fn(&K, &V) -> V,
+    {
+        self.map.update_with_key(key, update, &self.guard)
+    }
+
+    /// Updates an existing entry atomically with a fallible update function.
+    ///
+    /// See [`HashMap::try_update`] for details.
+    #[inline]
+    pub fn try_update<F, E>(&self, key: K, update: F) -> Result<Option<&V>, E>
+    where
+        F: Fn(&V) -> Result<V, E>,
+    {
+        self.map.try_update(key, update, &self.guard)
+    }
+
+    /// Updates an existing entry atomically by mutating a copy of the current value.
+    ///
+    /// See [`HashMap::update_in_place`] for details.
+    #[inline]
+    pub fn update_in_place<F>(&self, key: K, update: F) -> Option<&V>
+    where
+        F: FnMut(&mut V),
+        V: Clone,
+    {
+        self.map.update_in_place(key, update, &self.guard)
+    }
+
+    /// Updates an existing entry or inserts a default value.
+    ///
+    /// See [`HashMap::update_or_insert`] for details.
+    #[inline]
+    pub fn update_or_insert<F>(&self, key: K, update: F, value: V) -> &V
+    where
+        F: Fn(&V) -> V,
+    {
+        self.update_or_insert_with(key, update, || value)
+    }
+
+    /// Updates an existing entry or inserts a default value computed from a closure.
+    ///
+    /// See [`HashMap::update_or_insert_with`] for details.
+    #[inline]
+    pub fn update_or_insert_with<U, F>(&self, key: K, update: U, f: F) -> &V
+    where
+        F: FnOnce() -> V,
+        U: Fn(&V) -> V,
+    {
+        self.map
+            .raw
+            .update_or_insert_with(key, update, f, &self.guard)
+    }
+
+    /// Applies a delta to the value of each key, inserting a default value for any
+    /// key that is not present.
+    ///
+    /// See [`HashMap::apply_deltas`] for details.
+    #[inline]
+    pub fn apply_deltas<I, D, F>(&self, deltas: I, combine: F, default: V)
+    where
+        I: IntoIterator<Item = (K, D)>,
+        F: Fn(&V, &D) -> V,
+    {
+        self.map.apply_deltas(deltas, combine, default, &self.guard)
+    }
+
+    /// Atomically replaces the value for a key with its default value, returning the
+    /// previous value.
+    ///
+    /// See [`HashMap::take_value`] for details.
+    #[inline]
+    pub fn take_value(&self, key: K) -> Option<&V>
+    where
+        V: Default,
+    {
+        self.map.take_value(key, &self.guard)
+    }
+
+    /// Atomically decrements the value for a key, removing the entry once it reaches zero.
+    ///
+    /// See [`HashMap::decrement_or_remove`] for details.
+    #[inline]
+    pub fn decrement_or_remove(&self, key: K, amount: V) -> Option<&V>
+    where
+        V: Copy + PartialOrd + Sub<Output = V>,
+    {
+        self.map.decrement_or_remove(key, amount, &self.guard)
+    }
+
+    // Updates an entry with a compare-and-swap (CAS) function.
+    //
+    /// See [`HashMap::compute`] for details.
+    #[inline]
+    pub fn compute<'g, F, T>(&'g self, key: K, compute: F) -> Compute<'g, K, V, T>
+    where
+        F: FnMut(Option<(&'g K, &'g V)>) -> Operation<V, T>,
+    {
+        self.map.raw.compute(key, compute, &self.guard)
+    }
+
+    /// Updates an entry with a compare-and-swap (CAS) function, returning the number
+    /// of times the operation was retried.
+    ///
+    /// See [`HashMap::compute_verbose`] for details.
+    #[inline]
+    pub fn compute_verbose<'g, F, T>(&'g self, key: K, compute: F) -> (Compute<'g, K, V, T>, u32)
+    where
+        F: FnMut(Option<(&'g K, &'g V)>) -> Operation<V, T>,
+    {
+        self.map.raw.compute_verbose(key, compute, &self.guard)
+    }
+
+    /// Returns a handle to the entry for the given key, for performing multiple
+    /// operations on the same key.
+    ///
+    /// See [`HashMap::entry`] for details.
+    #[inline]
+    pub fn entry(&self, key: K) -> Entry<'_, K, V, S, G> {
+        Entry {
+            hash: self.map.raw.hasher.hash_one(&key),
+            key,
+            slot: Cell::new(None),
+            map: self.map,
+            guard: &self.guard,
+        }
+    }
 
-    /// The given entry was removed.
-    Removed(&'g K, &'g V),
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map.
+    ///
+    /// See [`HashMap::remove`] for details.
+    #[inline]
+    pub fn remove<Q>(&self, key: &Q) -> Option<&V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        match self.map.raw.remove(key, &self.guard) {
+            Some((_, value)) => Some(value),
+            None => None,
+        }
+    }
 
-    /// The operation was aborted with the given value.
-    Aborted(T),
-}
+    /// Removes a key from the map, returning the stored key and value if the
+    /// key was previously in the map.
+    ///
+    /// See [`HashMap::remove_entry`] for details.
+    #[inline]
+    pub fn remove_entry<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.map.raw.remove(key, &self.guard)
+    }
 
-/// An error returned by [`try_insert`](HashMap::try_insert) when the key already exists.
-///
-/// Contains the existing value, and the value that was not inserted.
-#[derive(Debug, PartialEq, Eq)]
-pub struct OccupiedError<'a, V: 'a> {
-    /// The value in the map that was already present.
-    pub current: &'a V,
-    /// The value which was not inserted, because the entry was already occupied.
-    pub not_inserted: V,
-}
+    /// Clears the map, removing all key-value pairs.
+    ///
+    /// See [`HashMap::clear`] for details.
+    #[inline]
+    pub fn clear(&self) {
+        self.map.raw.clear(&self.guard)
+    }
 
-impl<K, V, S> PartialEq for HashMap<K, V, S>
-where
-    K: Hash + Eq,
-    V: PartialEq,
-    S: BuildHasher,
-{
-    fn eq(&self, other: &Self) -> bool {
-        if self.len() != other.len() {
-            return false;
-        }
+    /// Clears the map, replacing the table with an empty table that has room for
+    /// at least `capacity` entries.
+    ///
+    /// See [`HashMap::clear_and_reserve`] for details.
+    #[inline]
+    pub fn clear_and_reserve(&self, capacity: usize) {
+        self.map.raw.clear_and_reserve(capacity, &self.guard)
+    }
 
-        let (guard1, guard2) = (&self.guard(), &other.guard());
+    /// Updates every entry in the map with a closure.
+    ///
+    /// See [`HashMap::for_each_update`] for details.
+    #[inline]
+    pub fn for_each_update<F>(&self, f: F)
+    where
+        K: Clone,
+        F: FnMut(&K, &V) -> Option<V>,
+    {
+        self.map.raw.for_each_update(f, &self.guard)
+    }
 
-        let mut iter = self.iter(guard1);
-        iter.all(|(key, value)| other.get(key, guard2).map_or(false, |v| *value == *v))
+    /// Updates every value in the map with a closure, sweeping the table in parallel on the
+    /// rayon thread pool.
+    ///
+    /// See [`HashMap::par_update_all`] for details.
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub fn par_update_all<F>(&self, f: F)
+    where
+        K: Clone + Send + Sync,
+        V: Send + Sync,
+        S: Sync,
+        F: Fn(&V) -> V + Sync,
+    {
+        self.map.raw.par_update_all(f, &self.guard)
     }
-}
 
-impl<K, V, S> Eq for HashMap<K, V, S>
-where
-    K: Hash + Eq,
-    V: Eq,
-    S: BuildHasher,
-{
-}
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// See [`HashMap::retain`] for details.
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.map.raw.retain(f, &self.guard)
+    }
 
-impl<K, V, S> fmt::Debug for HashMap<K, V, S>
-where
-    K: Hash + Eq + fmt::Debug,
-    V: fmt::Debug,
-    S: BuildHasher,
-{
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let guard = self.guard();
-        f.debug_map().entries(self.iter(&guard)).finish()
+    /// Retains only the elements specified by the predicate, calling `on_removed` with
+    /// every entry that was removed.
+    ///
+    /// See [`HashMap::retain_with`] for details.
+    #[inline]
+    pub fn retain_with<'g, F, R>(&'g mut self, f: F, on_removed: R)
+    where
+        K: 'g,
+        V: 'g,
+        F: FnMut(&K, &V) -> bool,
+        R: FnMut(&'g K, &'g V),
+    {
+        self.map.raw.retain_with(f, on_removed, &self.guard)
     }
-}
 
-impl<K, V, S> Extend<(K, V)> for &HashMap<K, V, S>
-where
-    K: Hash + Eq,
-    S: BuildHasher,
-{
-    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
-        // from `hashbrown::HashMap::extend`:
-        // Keys may be already present or show multiple times in the iterator.
-        // Reserve the entire hint lower bound if the map is empty.
-        // Otherwise reserve half the hint (rounded up), so the map
-        // will only resize twice in the worst case.
-        let iter = iter.into_iter();
-        let reserve = if self.is_empty() {
-            iter.size_hint().0
-        } else {
-            (iter.size_hint().0 + 1) / 2
-        };
+    /// Tries to reserve capacity for `additional` more elements to be inserted
+    /// in the map.
+    ///
+    /// See [`HashMap::reserve`] for details.
+    #[inline]
+    pub fn reserve(&self, additional: usize) {
+        self.map.raw.reserve(additional, &self.guard)
+    }
 
-        let guard = self.guard();
-        self.reserve(reserve, &guard);
+    /// Completes any in-progress resizes of the map.
+    ///
+    /// See [`HashMap::finalize_resize`] for details.
+    #[inline]
+    pub fn finalize_resize(&self) {
+        self.map.raw.finalize_resize(&self.guard)
+    }
 
-        for (key, value) in iter {
-            self.insert(key, value, &guard);
-        }
+    /// Immediately grows the map's table to double its capacity.
+    ///
+    /// See [`HashMap::force_resize`] for details.
+    #[inline]
+    pub fn force_resize(&self) {
+        self.map.raw.force_resize(&self.guard)
     }
-}
 
-impl<'a, K, V, S> Extend<(&'a K, &'a V)> for &HashMap<K, V, S>
-where
-    K: Copy + Hash + Eq,
-    V: Copy,
-    S: BuildHasher,
-{
-    fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
-        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
+    /// Rehashes the map into a table of the same capacity, removing any tombstones.
+    ///
+    /// See [`HashMap::compact`] for details.
+    #[inline]
+    pub fn compact(&self) {
+        self.map.raw.compact(&self.guard)
     }
-}
 
-impl<K, V, const N: usize> From<[(K, V); N]> for HashMap<K, V, RandomState>
-where
-    K: Hash + Eq,
-{
-    fn from(arr: [(K, V); N]) -> Self {
-        HashMap::from_iter(arr)
+    /// Returns the number of tombstones in the map's table.
+    ///
+    /// See [`HashMap::tombstone_count`] for details.
+    #[inline]
+    pub fn tombstone_count(&self) -> usize {
+        self.map.raw.tombstone_count(&self.guard)
     }
-}
 
-impl<K, V, S> FromIterator<(K, V)> for HashMap<K, V, S>
-where
-    K: Hash + Eq,
-    S: BuildHasher + Default,
-{
-    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
-        let mut iter = iter.into_iter();
+    /// Returns the number of slots in the map's table.
+    ///
+    /// See [`HashMap::capacity`] for details.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.map.raw.capacity(&self.guard)
+    }
 
-        if let Some((key, value)) = iter.next() {
-            let (lower, _) = iter.size_hint();
-            let map = HashMap::with_capacity_and_hasher(lower.saturating_add(1), S::default());
+    /// Returns statistics about hash collisions in the map.
+    ///
+    /// See [`HashMap::collision_report`] for details.
+    #[inline]
+    pub fn collision_report(&self) -> CollisionReport {
+        self.map.raw.collision_report(&self.guard)
+    }
 
-            // Ideally we could use an unprotected guard here. However, `insert`
-            // returns references to values that were replaced and retired, so
-            // we need a "real" guard. A `raw_insert` method that strictly returns
-            // pointers would fix this.
-            {
-                let map = map.pin();
-                map.insert(key, value);
-                for (key, value) in iter {
-                    map.insert(key, value);
-                }
-            }
+    /// Asserts the internal invariants of the map.
+    ///
+    /// See [`HashMap::check_invariants`] for details.
+    #[inline]
+    #[cfg(feature = "testing")]
+    pub fn check_invariants(&self) {
+        self.map.raw.check_invariants(&self.guard)
+    }
 
-            map
-        } else {
-            Self::default()
+    /// An iterator visiting all key-value pairs in arbitrary order.
+    /// The iterator element type is `(&K, &V)`.
+    ///
+    /// See [`HashMap::iter`] for details.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V, G> {
+        Iter {
+            raw: self.map.raw.iter(&self.guard),
         }
     }
-}
-
-impl<K, V, S> Clone for HashMap<K, V, S>
-where
-    K: Clone + Hash + Eq,
-    V: Clone,
-    S: BuildHasher + Clone,
-{
-    fn clone(&self) -> HashMap<K, V, S> {
-        let other = HashMap::builder()
-            .capacity(self.len())
-            .hasher(self.raw.hasher.clone())
-            .collector(self.raw.collector().clone())
-            .build();
 
-        {
-            let (guard1, guard2) = (&self.guard(), &other.guard());
-            for (key, value) in self.iter(guard1) {
-                other.insert(key.clone(), value.clone(), guard2);
-            }
+    /// An iterator visiting all key-value pairs in arbitrary order, following entries
+    /// that are migrated by concurrent resizes.
+    ///
+    /// See [`HashMap::iter_live`] for details.
+    #[inline]
+    pub fn iter_live(&self) -> LiveIter<'_, K, V, S, G> {
+        LiveIter {
+            raw: self.map.raw.iter_live(&self.guard),
         }
+    }
 
-        other
+    /// An iterator visiting all key-value pairs in arbitrary order, that never helps
+    /// with in-progress resizes.
+    ///
+    /// See [`HashMap::iter_passive`] for details.
+    #[inline]
+    pub fn iter_passive(&self) -> LiveIter<'_, K, V, S, G> {
+        LiveIter {
+            raw: self.map.raw.iter_passive(&self.guard),
+        }
     }
-}
 
-/// A pinned reference to a [`HashMap`].
-///
-/// This type is created with [`HashMap::pin`] and can be used to easily access a [`HashMap`]
-/// without explicitly managing a guard. See the [crate-level documentation](crate#usage) for details.
-pub struct HashMapRef<'map, K, V, S, G> {
-    guard: MapGuard<G>,
-    map: &'map HashMap<K, V, S>,
-}
+    /// An iterator visiting all entries in arbitrary order, yielding handles that can remove
+    /// the entry if it has not changed since it was observed.
+    ///
+    /// See [`HashMap::iter_removable`] for details.
+    #[inline]
+    pub fn iter_removable(&self) -> IterRemovable<'_, K, V, S, G> {
+        IterRemovable {
+            raw: self.map.raw.iter(&self.guard),
+            map: self.map,
+            guard: &self.guard,
+        }
+    }
 
-impl<'map, K, V, S, G> HashMapRef<'map, K, V, S, G>
-where
-    K: Hash + Eq,
-    S: BuildHasher,
-    G: Guard,
-{
-    /// Returns a reference to the inner [`HashMap`].
+    /// An iterator visiting all keys in arbitrary order.
+    /// The iterator element type is `&K`.
+    ///
+    /// See [`HashMap::keys`] for details.
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, K, V, G> {
+        Keys { iter: self.iter() }
+    }
+
+    /// An iterator visiting all values in arbitrary order.
+    /// The iterator element type is `&V`.
+    ///
+    /// See [`HashMap::values`] for details.
     #[inline]
-    pub fn map(&self) -> &'map HashMap<K, V, S> {
-        self.map
+    pub fn values(&self) -> Values<'_, K, V, G> {
+        Values { iter: self.iter() }
     }
 
-    /// Returns the number of entries in the map.
+    /// An iterator visiting all key-value pairs in the order they are stored in the table.
+    /// The iterator element type is `(&K, &V)`.
     ///
-    /// See [`HashMap::len`] for details.
+    /// See [`HashMap::iter_in_table_order`] for details.
     #[inline]
-    pub fn len(&self) -> usize {
-        self.map.raw.len()
+    pub fn iter_in_table_order(&self) -> Iter<'_, K, V, G> {
+        Iter {
+            raw: self.map.raw.iter(&self.guard),
+        }
     }
 
-    /// Returns `true` if the map is empty. Otherwise returns `false`.
+    /// An iterator visiting all key-value pairs along with the index of the slot they are
+    /// stored in. The iterator element type is `(usize, &K, &V)`.
     ///
-    /// See [`HashMap::is_empty`] for details.
+    /// See [`HashMap::iter_enumerate`] for details.
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
+    pub fn iter_enumerate(&self) -> IterEnumerate<'_, K, V, G> {
+        IterEnumerate {
+            raw: self.map.raw.iter(&self.guard),
+        }
     }
 
-    /// Returns `true` if the map contains a value for the specified key.
+    /// Returns an iterator over a snapshot of the map's entries.
     ///
-    /// See [`HashMap::contains_key`] for details.
+    /// See [`HashMap::snapshot`] for details.
     #[inline]
-    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    pub fn snapshot(&self) -> Snapshot<'_, K, V> {
+        self.map.snapshot(&self.guard)
+    }
+
+    /// Writes the map to `writer` in a versioned binary format.
+    ///
+    /// See [`HashMap::save_to`] for details.
+    pub fn save_to<W, F>(&self, writer: W, encode: F) -> io::Result<()>
     where
-        Q: Equivalent<K> + Hash + ?Sized,
+        W: io::Write,
+        F: FnMut(&K, &V, &mut Vec<u8>) -> io::Result<()>,
     {
-        self.get(key).is_some()
+        self.map.save_to(writer, encode, &self.guard)
     }
 
-    /// Returns a reference to the value corresponding to the key.
+    /// Returns the number of entries visited by iterating over the map.
     ///
-    /// See [`HashMap::get`] for details.
+    /// See [`HashMap::snapshot_len`] for details.
     #[inline]
-    pub fn get<Q>(&self, key: &Q) -> Option<&V>
-    where
-        Q: Equivalent<K> + Hash + ?Sized,
-    {
-        match self.map.raw.get(key, &self.guard) {
-            Some((_, v)) => Some(v),
-            None => None,
-        }
+    pub fn snapshot_len(&self) -> usize {
+        self.map.snapshot_len(&self.guard)
     }
 
-    /// Returns the key-value pair corresponding to the supplied key.
+    /// Returns the number of entries matching a predicate, counted over a single table
+    /// generation.
     ///
-    /// See [`HashMap::get_key_value`] for details.
+    /// See [`HashMap::count_matching_snapshot`] for details.
     #[inline]
-    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    pub fn count_matching_snapshot<F>(&self, f: F) -> usize
     where
-        Q: Equivalent<K> + Hash + ?Sized,
+        F: FnMut(&K, &V) -> bool,
     {
-        self.map.raw.get(key, &self.guard)
+        self.map.count_matching_snapshot(f, &self.guard)
     }
 
-    /// Inserts a key-value pair into the map.
+    /// Returns a vector containing clones of all key-value pairs in the map.
     ///
-    /// See [`HashMap::insert`] for details.
+    /// The vector is allocated up-front based on the length of the map, so it is
+    /// allocated only once unless the map grows concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([(1, "a"), (2, "b")]);
+    ///
+    /// let mut entries = map.pin().to_vec();
+    /// entries.sort();
+    /// assert_eq!(entries, [(1, "a"), (2, "b")]);
+    /// ```
     #[inline]
-    pub fn insert(&self, key: K, value: V) -> Option<&V> {
-        match self.map.raw.insert(key, value, true, &self.guard) {
-            InsertResult::Inserted(_) => None,
-            InsertResult::Replaced(value) => Some(value),
-            InsertResult::Error { .. } => unreachable!(),
-        }
+    pub fn to_vec(&self) -> Vec<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut vec = Vec::with_capacity(self.len());
+        vec.extend(self.iter().map(|(k, v)| (k.clone(), v.clone())));
+        vec
     }
 
-    /// Tries to insert a key-value pair into the map, and returns
-    /// a reference to the value that was inserted.
+    /// Returns a vector containing clones of all keys in the map.
     ///
-    /// See [`HashMap::try_insert`] for details.
+    /// The vector is allocated up-front based on the length of the map, so it is
+    /// allocated only once unless the map grows concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::from([(1, "a"), (2, "b")]);
+    ///
+    /// let mut keys = map.pin().keys_cloned();
+    /// keys.sort();
+    /// assert_eq!(keys, [1, 2]);
+    /// ```
     #[inline]
-    pub fn try_insert(&self, key: K, value: V) -> Result<&V, OccupiedError<'_, V>> {
-        match self.map.raw.insert(key, value, false, &self.guard) {
-            InsertResult::Inserted(value) => Ok(value),
-            InsertResult::Error {
-                current,
-                not_inserted,
-            } => Err(OccupiedError {
-                current,
-                not_inserted,
-            }),
-            InsertResult::Replaced(_) => unreachable!(),
-        }
+    pub fn keys_cloned(&self) -> Vec<K>
+    where
+        K: Clone,
+    {
+        let mut vec = Vec::with_capacity(self.len());
+        vec.extend(self.keys().cloned());
+        vec
     }
 
-    /// Tries to insert a key and value computed from a closure into the map,
-    /// and returns a reference to the value that was inserted.
+    /// Returns all key-value pairs with keys in the given range, sorted by key.
     ///
-    /// See [`HashMap::try_insert_with`] for details.
+    /// See [`HashMap::range_scan`] for details.
     #[inline]
-    pub fn try_insert_with<F>(&self, key: K, f: F) -> Result<&V, &V>
+    pub fn range_scan<R>(&self, range: R) -> Vec<(&K, &V)>
     where
-        F: FnOnce() -> V,
+        K: Ord,
+        R: RangeBounds<K>,
     {
-        self.map.raw.try_insert_with(key, f, &self.guard)
+        self.map.range_scan(range, &self.guard)
     }
 
-    /// Returns a reference to the value corresponding to the key, or inserts a default value.
+    /// Returns up to `n` arbitrary key-value pairs from the map.
     ///
-    /// See [`HashMap::get_or_insert`] for details.
+    /// See [`HashMap::sample`] for details.
     #[inline]
-    pub fn get_or_insert(&self, key: K, value: V) -> &V {
-        // Note that we use `try_insert` instead of `compute` or `get_or_insert_with` here, as it
-        // allows us to avoid the closure indirection.
-        match self.try_insert(key, value) {
-            Ok(inserted) => inserted,
-            Err(OccupiedError { current, .. }) => current,
-        }
+    pub fn sample(&self, n: usize) -> Vec<(&K, &V)> {
+        self.map.sample(n, &self.guard)
     }
 
-    /// Returns a reference to the value corresponding to the key, or inserts a default value
-    /// computed from a closure.
+    /// Calls a closure on each key-value pair in the map, stopping as soon as the closure
+    /// returns [`ControlFlow::Break`].
     ///
-    /// See [`HashMap::get_or_insert_with`] for details.
+    /// See [`HashMap::try_for_each`] for details.
     #[inline]
-    pub fn get_or_insert_with<F>(&self, key: K, f: F) -> &V
+    pub fn try_for_each<'g, F, B>(&'g self, f: F) -> ControlFlow<B>
     where
-        F: FnOnce() -> V,
+        F: FnMut(&'g K, &'g V) -> ControlFlow<B>,
     {
-        self.map.raw.get_or_insert_with(key, f, &self.guard)
+        self.map.try_for_each(f, &self.guard)
     }
+}
 
-    /// Updates an existing entry atomically.
+impl<K, T, S, G> HashMapRef<'_, K, Arc<T>, S, G>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    G: Guard,
+{
+    /// Returns a clone of the [`Arc`] corresponding to the key.
     ///
-    /// See [`HashMap::update`] for details.
+    /// See [`HashMap::get_arc`] for details.
     #[inline]
-    pub fn update<F>(&self, key: K, update: F) -> Option<&V>
+    pub fn get_arc<Q>(&self, key: &Q) -> Option<Arc<T>>
     where
-        F: Fn(&V) -> V,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
-        self.map.raw.update(key, update, &self.guard)
+        self.get(key).cloned()
     }
+}
 
-    /// Updates an existing entry or inserts a default value.
+impl<K, T, S, G> HashMapRef<'_, K, Weak<T>, S, G>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    G: Guard,
+{
+    /// Returns the value corresponding to the key, if it is still alive.
     ///
-    /// See [`HashMap::update_or_insert`] for details.
+    /// See [`HashMap::get_upgraded`] for details.
     #[inline]
-    pub fn update_or_insert<F>(&self, key: K, update: F, value: V) -> &V
+    pub fn get_upgraded<Q>(&self, key: &Q) -> Option<Arc<T>>
     where
-        F: Fn(&V) -> V,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
-        self.update_or_insert_with(key, update, || value)
+        self.get(key).and_then(Weak::upgrade)
     }
 
-    /// Updates an existing entry or inserts a default value computed from a closure.
+    /// Removes all entries whose values have been dropped, returning the number of
+    /// entries removed.
     ///
-    /// See [`HashMap::update_or_insert_with`] for details.
+    /// See [`HashMap::prune_dead`] for details.
     #[inline]
-    pub fn update_or_insert_with<U, F>(&self, key: K, update: U, f: F) -> &V
+    pub fn prune_dead(&self) -> usize {
+        self.map.prune_dead(&self.guard)
+    }
+}
+
+impl<K, T, S, G> HashMapRef<'_, K, Box<T>, S, G>
+where
+    K: Hash + Eq,
+    T: ?Sized,
+    S: BuildHasher,
+    G: Guard,
+{
+    /// Returns a reference to the boxed value corresponding to the key.
+    ///
+    /// See [`HashMap::get_dyn`] for details.
+    #[inline]
+    pub fn get_dyn<Q>(&self, key: &Q) -> Option<&T>
     where
-        F: FnOnce() -> V,
-        U: Fn(&V) -> V,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
-        self.map
-            .raw
-            .update_or_insert_with(key, update, f, &self.guard)
+        self.get(key).map(|value| &**value)
     }
+}
 
-    // Updates an entry with a compare-and-swap (CAS) function.
-    //
-    /// See [`HashMap::compute`] for details.
+impl<K, S, G> HashMapRef<'_, K, usize, S, G>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    G: Guard,
+{
+    /// Interns a key, incrementing its reference count, and returns a reference to the key
+    /// stored in the map.
+    ///
+    /// See [`HashMap::intern`] for details.
     #[inline]
-    pub fn compute<'g, F, T>(&'g self, key: K, compute: F) -> Compute<'g, K, V, T>
+    pub fn intern(&self, key: K) -> &K
     where
-        F: FnMut(Option<(&'g K, &'g V)>) -> Operation<V, T>,
+        K: Clone,
     {
-        self.map.raw.compute(key, compute, &self.guard)
+        self.map.intern(key, &self.guard)
     }
 
-    /// Removes a key from the map, returning the value at the key if the key
-    /// was previously in the map.
+    /// Releases a reference to an interned key, decrementing its reference count and removing
+    /// the key once the count reaches zero.
     ///
-    /// See [`HashMap::remove`] for details.
+    /// See [`HashMap::release`] for details.
     #[inline]
-    pub fn remove<Q>(&self, key: &Q) -> Option<&V>
+    pub fn release<Q>(&self, key: &Q) -> Option<usize>
     where
+        K: Clone,
         Q: Equivalent<K> + Hash + ?Sized,
     {
-        match self.map.raw.remove(key, &self.guard) {
-            Some((_, value)) => Some(value),
-            None => None,
-        }
+        self.map.release(key, &self.guard)
     }
+}
 
-    /// Removes a key from the map, returning the stored key and value if the
-    /// key was previously in the map.
+impl<K, S, G> HashMapRef<'_, K, (), S, G>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    G: Guard,
+{
+    /// Claims a key, returning `true` if the key was not already claimed.
     ///
-    /// See [`HashMap::remove_entry`] for details.
+    /// See [`HashMap::claim`] for details.
     #[inline]
-    pub fn remove_entry<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    pub fn claim(&self, key: K) -> bool {
+        self.map.claim(key, &self.guard)
+    }
+
+    /// Releases a claimed key, allowing it to be claimed again.
+    ///
+    /// See [`HashMap::unclaim`] for details.
+    #[inline]
+    pub fn unclaim<Q>(&self, key: &Q) -> bool
     where
         Q: Equivalent<K> + Hash + ?Sized,
     {
-        self.map.raw.remove(key, &self.guard)
+        self.map.unclaim(key, &self.guard)
     }
+}
 
-    /// Clears the map, removing all key-value pairs.
+impl<K, V, S, G> HashMapRef<'_, K, Expiring<V>, S, G>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    G: Guard,
+{
+    /// Inserts a key-value pair into the map that expires after the given duration.
     ///
-    /// See [`HashMap::clear`] for details.
+    /// See [`HashMap::insert_with_ttl`] for details.
     #[inline]
-    pub fn clear(&self) {
-        self.map.raw.clear(&self.guard)
+    pub fn insert_with_ttl(&self, key: K, value: V, ttl: Duration) -> Option<&V> {
+        self.map.insert_with_ttl(key, value, ttl, &self.guard)
     }
 
-    /// Retains only the elements specified by the predicate.
+    /// Returns a reference to the value corresponding to the key, if it has not expired.
     ///
-    /// See [`HashMap::retain`] for details.
+    /// See [`HashMap::get_unexpired`] for details.
     #[inline]
-    pub fn retain<F>(&mut self, f: F)
+    pub fn get_unexpired<Q>(&self, key: &Q) -> Option<&V>
     where
-        F: FnMut(&K, &V) -> bool,
+        Q: Equivalent<K> + Hash + ?Sized,
     {
-        self.map.raw.retain(f, &self.guard)
+        self.map.get_unexpired(key, &self.guard)
     }
 
-    /// Tries to reserve capacity for `additional` more elements to be inserted
-    /// in the map.
+    /// Removes all expired entries from the map, returning the number of entries removed.
     ///
-    /// See [`HashMap::reserve`] for details.
+    /// See [`HashMap::purge_expired`] for details.
     #[inline]
-    pub fn reserve(&self, additional: usize) {
-        self.map.raw.reserve(additional, &self.guard)
+    pub fn purge_expired(&self) -> usize {
+        self.map.purge_expired(&self.guard)
     }
+}
 
-    /// An iterator visiting all key-value pairs in arbitrary order.
-    /// The iterator element type is `(&K, &V)`.
+impl<'map, K, V, S> HashMapRef<'map, K, V, S, OwnedGuard<'map>> {
+    /// Returns a pinned reference to the map that uses a thread-local guard.
     ///
-    /// See [`HashMap::iter`] for details.
+    /// Owned guards are more expensive to use than local guards, as they must synchronize
+    /// with other threads that may be using the same guard. This method allows a thread to
+    /// temporarily switch to a local guard for a hot synchronous section, while the owned
+    /// reference continues to be held across `.await` points.
+    ///
+    /// Note that the returned reference enters a new local guard, independent of the owned
+    /// guard. References returned by the local reference are tied to its own lifetime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let owned = map.pin_owned();
+    /// owned.insert(1, "a");
+    ///
+    /// {
+    ///     let local = owned.as_local();
+    ///     assert_eq!(local.get(&1), Some(&"a"));
+    /// }
+    ///
+    /// assert_eq!(owned.get(&1), Some(&"a"));
+    /// ```
     #[inline]
-    pub fn iter(&self) -> Iter<'_, K, V, G> {
-        Iter {
-            raw: self.map.raw.iter(&self.guard),
-        }
+    pub fn as_local(&self) -> HashMapRef<'map, K, V, S, LocalGuard<'map>> {
+        self.map.pin()
     }
+}
 
-    /// An iterator visiting all keys in arbitrary order.
-    /// The iterator element type is `&K`.
+#[cfg(feature = "async")]
+impl<'map, K, V, S> HashMapRef<'map, K, V, S, OwnedGuard<'map>>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns a reference to the value corresponding to the key, or inserts a value
+    /// computed from an asynchronous closure.
     ///
-    /// See [`HashMap::keys`] for details.
-    #[inline]
-    pub fn keys(&self) -> Keys<'_, K, V, G> {
-        Keys { iter: self.iter() }
+    /// See [`HashMap::get_or_insert_future`] for details.
+    pub async fn get_or_insert_future<F, Fut>(&self, key: K, f: F) -> &V
+    where
+        K: Clone,
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = V>,
+    {
+        self.map
+            .get_or_insert_future_inner(key, f, &self.guard)
+            .await
     }
+}
 
-    /// An iterator visiting all values in arbitrary order.
-    /// The iterator element type is `&V`.
+impl<'map, K, V, S> HashMapRef<'map, K, V, S, LocalGuard<'map>> {
+    /// Converts this reference into a pinned reference that uses an owned guard.
+    ///
+    /// The owned guard is acquired before the local guard is released, so the thread
+    /// remains protected throughout. The returned reference implements `Send` and `Sync`,
+    /// allowing it to be held across `.await` points in work-stealing schedulers.
+    ///
+    /// See [`HashMap::pin_owned`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashMap;
     ///
-    /// See [`HashMap::values`] for details.
+    /// let map = HashMap::new();
+    /// let local = map.pin();
+    /// local.insert(1, "a");
+    ///
+    /// let owned = local.into_owned();
+    /// assert_eq!(owned.get(&1), Some(&"a"));
+    /// ```
     #[inline]
-    pub fn values(&self) -> Values<'_, K, V, G> {
-        Values { iter: self.iter() }
+    pub fn into_owned(self) -> HashMapRef<'map, K, V, S, OwnedGuard<'map>> {
+        self.map.pin_owned()
     }
 }
 
@@ -1504,6 +6626,55 @@ where
     }
 }
 
+/// Creates a consuming iterator, that is, one that moves each key-value pair out of the
+/// map in arbitrary order. The map cannot be used after calling this.
+///
+/// Values may have been pinned with [`get_pin`](HashMap::get_pin), so this requires `V: Unpin`
+/// as it moves values out of the map. See [`HashMap::into_keys`] for a consuming iterator over
+/// the keys that does not.
+///
+/// # Examples
+///
+/// ```
+/// use papaya::HashMap;
+///
+/// let map = HashMap::from([
+///     ("a", 1),
+///     ("b", 2),
+///     ("c", 3),
+/// ]);
+///
+/// let mut entries: Vec<(&str, i32)> = map.into_iter().collect();
+/// entries.sort_unstable();
+/// assert_eq!(entries, [("a", 1), ("b", 2), ("c", 3)]);
+/// ```
+///
+/// Values that are `!Unpin` cannot be moved out:
+///
+/// ```compile_fail
+/// use papaya::HashMap;
+/// use std::marker::PhantomPinned;
+///
+/// let map = HashMap::new();
+/// map.pin().insert(1, PhantomPinned);
+/// let _ = map.into_iter();
+/// ```
+impl<K, V, S> IntoIterator for HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: Unpin,
+    S: BuildHasher,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            raw: self.raw.into_entries(),
+        }
+    }
+}
+
 impl<'a, K, V, S, G> IntoIterator for &'a HashMapRef<'_, K, V, S, G>
 where
     K: Hash + Eq,
@@ -1521,6 +6692,9 @@ where
 /// An iterator over a map's entries.
 ///
 /// This struct is created by the [`iter`](HashMap::iter) method on [`HashMap`]. See its documentation for details.
+///
+/// The lower bound returned by `size_hint` is based on the length of the map when the
+/// iterator was created, and so may not be accurate if the map is concurrently modified.
 pub struct Iter<'g, K, V, G> {
     raw: raw::Iter<'g, K, V, MapGuard<G>>,
 }
@@ -1535,6 +6709,11 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         self.raw.next()
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
 }
 
 impl<K, V, G> fmt::Debug for Iter<'_, K, V, G>
@@ -1552,6 +6731,196 @@ where
     }
 }
 
+/// An iterator over a map's entries and the indices of the slots they are stored in.
+///
+/// This struct is created by the [`iter_enumerate`](HashMap::iter_enumerate) method on [`HashMap`]. See its documentation for details.
+pub struct IterEnumerate<'g, K, V, G> {
+    raw: raw::Iter<'g, K, V, MapGuard<G>>,
+}
+
+impl<'g, K: 'g, V: 'g, G> Iterator for IterEnumerate<'g, K, V, G>
+where
+    G: Guard,
+{
+    type Item = (usize, &'g K, &'g V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw.next_indexed()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<K, V, G> fmt::Debug for IterEnumerate<'_, K, V, G>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+    G: Guard,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(IterEnumerate {
+                raw: self.raw.clone(),
+            })
+            .finish()
+    }
+}
+
+/// An iterator over a map's entries that follows entries migrated by concurrent resizes.
+///
+/// This struct is created by the [`iter_live`](HashMap::iter_live) and [`iter_passive`](HashMap::iter_passive)
+/// methods on [`HashMap`]. See their documentation for details.
+pub struct LiveIter<'g, K, V, S, G> {
+    raw: raw::LiveIter<'g, K, V, S, MapGuard<G>>,
+}
+
+impl<'g, K: 'g, V: 'g, S, G> Iterator for LiveIter<'g, K, V, S, G>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    G: Guard,
+{
+    type Item = (&'g K, &'g V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<K, V, S, G> fmt::Debug for LiveIter<'_, K, V, S, G>
+where
+    K: fmt::Debug + Hash + Eq,
+    V: fmt::Debug,
+    S: BuildHasher,
+    G: Guard,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(LiveIter {
+                raw: self.raw.clone(),
+            })
+            .finish()
+    }
+}
+
+/// An iterator over a map's entries that yields removal handles.
+///
+/// This struct is created by the [`iter_removable`](HashMap::iter_removable) method on [`HashMap`].
+/// See its documentation for details.
+pub struct IterRemovable<'g, K, V, S, G> {
+    raw: raw::Iter<'g, K, V, MapGuard<G>>,
+    map: &'g HashMap<K, V, S>,
+    guard: &'g MapGuard<G>,
+}
+
+impl<'g, K: 'g, V: 'g, S, G> Iterator for IterRemovable<'g, K, V, S, G>
+where
+    G: Guard,
+{
+    type Item = RemovalHandle<'g, K, V, S, G>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.raw.next()?;
+
+        Some(RemovalHandle {
+            key,
+            value,
+            map: self.map,
+            guard: self.guard,
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<K, V, S, G> fmt::Debug for IterRemovable<'_, K, V, S, G>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+    G: Guard,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(Iter {
+                raw: self.raw.clone(),
+            })
+            .finish()
+    }
+}
+
+/// A handle to an observed entry in a [`HashMap`].
+///
+/// This struct is yielded by the iterator returned from [`iter_removable`](HashMap::iter_removable).
+/// See its documentation for details.
+pub struct RemovalHandle<'g, K, V, S, G> {
+    key: &'g K,
+    value: &'g V,
+    map: &'g HashMap<K, V, S>,
+    guard: &'g MapGuard<G>,
+}
+
+impl<'g, K, V, S, G> RemovalHandle<'g, K, V, S, G>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    G: Guard,
+{
+    /// Returns a reference to the key of the observed entry.
+    #[inline]
+    pub fn key(&self) -> &'g K {
+        self.key
+    }
+
+    /// Returns a reference to the value of the observed entry.
+    ///
+    /// Note that the value may have since been updated or removed.
+    #[inline]
+    pub fn value(&self) -> &'g V {
+        self.value
+    }
+
+    /// Removes the entry if it has not been updated or removed since it was observed, returning
+    /// `true` if the entry was removed.
+    ///
+    /// See [`HashMap::iter_removable`] for details.
+    #[inline]
+    pub fn remove_if_unchanged(&self) -> bool {
+        // The guard keeps the observed entry from being reclaimed, so its address cannot be
+        // reused by a newer entry.
+        self.map
+            .raw
+            .remove_if(self.key, |_, value| ptr::eq(value, self.value), self.guard)
+            .is_some()
+    }
+}
+
+impl<K, V, S, G> fmt::Debug for RemovalHandle<'_, K, V, S, G>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemovalHandle")
+            .field("key", &self.key)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
 /// An iterator over a map's keys.
 ///
 /// This struct is created by the [`keys`](HashMap::keys) method on [`HashMap`]. See its documentation for details.
@@ -1570,6 +6939,11 @@ where
         let (key, _) = self.iter.next()?;
         Some(key)
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 }
 
 impl<K, V, G> fmt::Debug for Keys<'_, K, V, G>
@@ -1601,6 +6975,11 @@ where
         let (_, value) = self.iter.next()?;
         Some(value)
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 }
 
 impl<K, V, G> fmt::Debug for Values<'_, K, V, G>
@@ -1613,3 +6992,153 @@ where
         f.debug_tuple("Values").field(&self.iter).finish()
     }
 }
+
+/// An iterator over a snapshot of a map's entries.
+///
+/// This struct is created by the [`snapshot`](HashMap::snapshot) method on [`HashMap`].
+/// See its documentation for details.
+pub struct Snapshot<'g, K, V> {
+    entries: std::vec::IntoIter<(&'g K, &'g V)>,
+}
+
+impl<'g, K: 'g, V: 'g> Iterator for Snapshot<'g, K, V> {
+    type Item = (&'g K, &'g V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Snapshot<'_, K, V> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+impl<K, V> Clone for Snapshot<'_, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Snapshot {
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<K, V> fmt::Debug for Snapshot<'_, K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.entries.as_slice()).finish()
+    }
+}
+
+/// An owning iterator over the entries of a map.
+///
+/// This struct is created by the [`into_iter`](IntoIterator::into_iter) method on [`HashMap`]
+/// (provided by the [`IntoIterator`] trait). See its documentation for details.
+pub struct IntoIter<K, V, S> {
+    raw: raw::IntoEntries<K, V, S>,
+}
+
+impl<K, V, S> Iterator for IntoIter<K, V, S> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw.take()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.raw.remaining(), Some(self.raw.remaining()))
+    }
+}
+
+impl<K, V, S> fmt::Debug for IntoIter<K, V, S>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = self.raw.guard();
+        f.debug_list().entries(self.raw.iter(&guard)).finish()
+    }
+}
+
+/// An owning iterator over the keys of a map.
+///
+/// This struct is created by the [`into_keys`](HashMap::into_keys) method on [`HashMap`].
+/// See its documentation for details.
+pub struct IntoKeys<K, V, S> {
+    raw: raw::IntoEntries<K, V, S>,
+}
+
+impl<K, V, S> Iterator for IntoKeys<K, V, S> {
+    type Item = K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw.take_key()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.raw.remaining(), Some(self.raw.remaining()))
+    }
+}
+
+impl<K, V, S> fmt::Debug for IntoKeys<K, V, S>
+where
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = self.raw.guard();
+        f.debug_list()
+            .entries(self.raw.iter(&guard).map(|(key, _)| key))
+            .finish()
+    }
+}
+
+/// An owning iterator over the values of a map.
+///
+/// This struct is created by the [`into_values`](HashMap::into_values) method on [`HashMap`].
+/// See its documentation for details.
+pub struct IntoValues<K, V, S> {
+    iter: IntoIter<K, V, S>,
+}
+
+impl<K, V, S> Iterator for IntoValues<K, V, S> {
+    type Item = V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, value) = self.iter.next()?;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V, S> fmt::Debug for IntoValues<K, V, S>
+where
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = self.iter.raw.guard();
+        f.debug_list()
+            .entries(self.iter.raw.iter(&guard).map(|(_, value)| value))
+            .finish()
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::sync::Arc;
+
+use seize::{Guard, LocalGuard};
+
+use crate::{Compute, Equivalent, HashMap, Operation};
+
+/// A concurrent map that stores multiple values per key.
+///
+/// Storing a `Vec<V>` in a [`HashMap`] and appending to it with [`update`](HashMap::update)
+/// clones the entire vector on every insertion, as values in the map are immutable. Instead,
+/// a `MultiMap` stores the values of every key in a persistent linked list. Pushing a value
+/// only allocates a new node that shares the existing values, so every push takes constant time
+/// regardless of the number of values, and no value is ever cloned.
+///
+/// Every operation on a key is performed atomically, with the same compare-and-swap semantics
+/// as [`HashMap::compute`]. Values are yielded by [`get_all`](MultiMap::get_all) in the reverse
+/// order that they were pushed, starting with the most recently pushed value.
+///
+/// # Examples
+///
+/// ```
+/// use papaya::MultiMap;
+///
+/// let map = MultiMap::new();
+/// let guard = map.guard();
+///
+/// map.push("a", 1, &guard);
+/// map.push("a", 2, &guard);
+/// map.push("b", 3, &guard);
+///
+/// assert_eq!(map.get_all("a", &guard).collect::<Vec<_>>(), [&2, &1]);
+///
+/// assert!(map.remove_value("a", &2, &guard));
+/// assert_eq!(map.get_all("a", &guard).collect::<Vec<_>>(), [&1]);
+/// ```
+pub struct MultiMap<K, V, S = RandomState> {
+    map: HashMap<K, List<V>, S>,
+}
+
+// The values of a key, stored as a persistent list.
+//
+// The list is never empty, as keys are removed once their last value is removed.
+struct List<V> {
+    head: Arc<Node<V>>,
+}
+
+// A node in a list of values.
+//
+// Values are stored behind an `Arc` so that removing a value from the middle of the list
+// only has to copy the preceding nodes, without cloning the values themselves.
+struct Node<V> {
+    value: Arc<V>,
+    next: Option<Arc<Node<V>>>,
+}
+
+impl<K, V> MultiMap<K, V> {
+    /// Creates an empty `MultiMap`.
+    ///
+    /// The map is initially created with a capacity of 0, so it will not allocate
+    /// until it is first inserted into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::MultiMap;
+    /// let map: MultiMap<&str, i32> = MultiMap::new();
+    /// ```
+    pub fn new() -> MultiMap<K, V> {
+        MultiMap {
+            map: HashMap::new(),
+        }
+    }
+}
+
+impl<K, V, S> Default for MultiMap<K, V, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        MultiMap::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S> MultiMap<K, V, S> {
+    /// Creates an empty `MultiMap` which will use the given hash builder to hash keys.
+    ///
+    /// See [`HashMap::with_hasher`] for details.
+    pub fn with_hasher(hash_builder: S) -> MultiMap<K, V, S> {
+        MultiMap {
+            map: HashMap::with_hasher(hash_builder),
+        }
+    }
+
+    /// Returns a guard for use with this map.
+    ///
+    /// See [`HashMap::guard`] for details.
+    #[inline]
+    pub fn guard(&self) -> LocalGuard<'_> {
+        self.map.guard()
+    }
+}
+
+impl<K, V, S> MultiMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns the number of keys in the map.
+    ///
+    /// See [`HashMap::len`] for details.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map contains no keys.
+    ///
+    /// See [`HashMap::is_empty`] for details.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Pushes a value onto the values of a key.
+    ///
+    /// If the key is not present, it is inserted with `value` as its only value. Pushing a
+    /// value takes constant time, regardless of the number of values stored for the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::MultiMap;
+    ///
+    /// let map = MultiMap::new();
+    /// let guard = map.guard();
+    ///
+    /// map.push(1, "a", &guard);
+    /// map.push(1, "b", &guard);
+    /// assert_eq!(map.get_all(&1, &guard).count(), 2);
+    /// ```
+    #[inline]
+    pub fn push(&self, key: K, value: V, guard: &impl Guard) {
+        let value = Arc::new(value);
+
+        let compute = |entry: Option<(&K, &List<V>)>| {
+            let node = Node {
+                value: value.clone(),
+                next: entry.map(|(_, list)| list.head.clone()),
+            };
+
+            Operation::Insert::<_, ()>(List {
+                head: Arc::new(node),
+            })
+        };
+
+        self.map.compute(key, compute, guard);
+    }
+
+    /// Returns an iterator over the values of a key, starting with the most recently
+    /// pushed value.
+    ///
+    /// The iterator is empty if the key is not present. It yields the values as observed
+    /// at a single point in time, so values pushed or removed concurrently are not reflected.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::MultiMap;
+    ///
+    /// let map = MultiMap::new();
+    /// let guard = map.guard();
+    ///
+    /// map.push("a", 1, &guard);
+    /// map.push("a", 2, &guard);
+    ///
+    /// assert_eq!(map.get_all("a", &guard).collect::<Vec<_>>(), [&2, &1]);
+    /// assert_eq!(map.get_all("b", &guard).next(), None);
+    /// ```
+    #[inline]
+    pub fn get_all<'g, Q>(&self, key: &Q, guard: &'g impl Guard) -> GetAll<'g, V>
+    where
+        K: 'g,
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        GetAll {
+            next: self.map.get(key, guard).map(|list| &*list.head),
+        }
+    }
+
+    /// Removes the most recently pushed occurrence of a value from the values of a key.
+    ///
+    /// Returns `true` if the value was present. The key is removed once its last value is
+    /// removed. Removing a value copies the nodes of the list that precede it, but the
+    /// values themselves are never cloned.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::MultiMap;
+    ///
+    /// let map = MultiMap::new();
+    /// let guard = map.guard();
+    ///
+    /// map.push("a", 1, &guard);
+    /// assert!(!map.remove_value("a", &2, &guard));
+    /// assert!(map.remove_value("a", &1, &guard));
+    /// assert!(map.is_empty());
+    /// ```
+    #[inline]
+    pub fn remove_value<Q>(&self, key: &Q, value: &V, guard: &impl Guard) -> bool
+    where
+        K: Clone,
+        V: PartialEq,
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        // Updating the list requires an owned key for the new entry.
+        let Some((key, _)) = self.map.get_key_value(key, guard) else {
+            return false;
+        };
+
+        let compute = |entry: Option<(&K, &List<V>)>| match entry {
+            // The key was concurrently removed.
+            None => Operation::Abort(()),
+            Some((_, list)) => match list.without(value) {
+                // The value is not present.
+                None => Operation::Abort(()),
+                // This was the last value, remove the key.
+                Some(None) => Operation::Remove,
+                Some(Some(list)) => Operation::Insert(list),
+            },
+        };
+
+        !matches!(
+            self.map.compute(key.clone(), compute, guard),
+            Compute::Aborted(_)
+        )
+    }
+
+    /// Removes a key and all of its values from the map, returning `true` if the key
+    /// was present.
+    ///
+    /// See [`HashMap::remove`] for details.
+    #[inline]
+    pub fn remove<Q>(&self, key: &Q, guard: &impl Guard) -> bool
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.map.remove(key, guard).is_some()
+    }
+}
+
+impl<V> List<V> {
+    // Returns a copy of the list with the first occurrence of `value` removed, or `None`
+    // if the value is not present.
+    //
+    // The inner option is `None` if the resulting list is empty.
+    fn without(&self, value: &V) -> Option<Option<List<V>>>
+    where
+        V: PartialEq,
+    {
+        // Find the node to remove, collecting the nodes that precede it.
+        let mut prefix = Vec::new();
+        let mut current = &self.head;
+        while *current.value != *value {
+            prefix.push(current);
+            current = current.next.as_ref()?;
+        }
+
+        // Copy the preceding nodes onto the remainder of the list.
+        let mut next = current.next.clone();
+        for node in prefix.into_iter().rev() {
+            next = Some(Arc::new(Node {
+                value: node.value.clone(),
+                next,
+            }));
+        }
+
+        Some(next.map(|head| List { head }))
+    }
+}
+
+impl<V> Drop for List<V> {
+    fn drop(&mut self) {
+        // Drop uniquely owned nodes iteratively to avoid overflowing the stack
+        // on long lists.
+        let mut next = Arc::get_mut(&mut self.head).and_then(|node| node.next.take());
+        while let Some(node) = next {
+            next = match Arc::try_unwrap(node) {
+                Ok(mut node) => node.next.take(),
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+/// An iterator over the values of a key in a [`MultiMap`].
+///
+/// This struct is created by the [`get_all`](MultiMap::get_all) method on [`MultiMap`].
+/// See its documentation for details.
+pub struct GetAll<'g, V> {
+    next: Option<&'g Node<V>>,
+}
+
+impl<'g, V> Iterator for GetAll<'g, V> {
+    type Item = &'g V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        self.next = node.next.as_deref();
+        Some(&node.value)
+    }
+}
+
+impl<V> Clone for GetAll<'_, V> {
+    fn clone(&self) -> Self {
+        GetAll { next: self.next }
+    }
+}
+
+impl<V> fmt::Debug for GetAll<'_, V>
+where
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<K, V, S> fmt::Debug for MultiMap<K, V, S>
+where
+    K: Hash + Eq + fmt::Debug,
+    V: fmt::Debug,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = self.map.guard();
+        let entries = self.map.iter(&guard).map(|(key, list)| {
+            let values = GetAll {
+                next: Some(&*list.head),
+            };
+            (key, values)
+        });
+
+        f.debug_map().entries(entries).finish()
+    }
+}
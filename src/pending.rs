@@ -0,0 +1,131 @@
+use std::collections::hash_map::{self, HashMap};
+use std::future::Future;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+// The set of keys with an in-progress asynchronous initialization.
+//
+// Initialization is expected to be expensive relative to a lookup, so in-progress keys are
+// tracked with a simple lock. The lock is only taken on the slow path after a missed lookup,
+// and is never held across an await point.
+pub struct Pending<K> {
+    keys: Mutex<HashMap<K, Arc<Signal>>>,
+}
+
+impl<K> Default for Pending<K> {
+    fn default() -> Self {
+        Pending {
+            keys: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K> Pending<K>
+where
+    K: Hash + Eq + Clone,
+{
+    // Try to claim the initialization of the given key.
+    //
+    // Returns a `Claim` if the key was not already being initialized, and otherwise
+    // a `Signal` that is notified when the current initialization completes.
+    pub fn claim<'a>(&'a self, key: &K) -> Result<Claim<'a, K>, Arc<Signal>> {
+        match self.lock().entry(key.clone()) {
+            hash_map::Entry::Occupied(entry) => Err(entry.get().clone()),
+            hash_map::Entry::Vacant(entry) => {
+                let signal = Arc::new(Signal::default());
+                entry.insert(signal.clone());
+
+                Ok(Claim {
+                    pending: self,
+                    key: key.clone(),
+                    signal,
+                })
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<K, Arc<Signal>>> {
+        // The table is never left in an inconsistent state, so poisoning can be ignored.
+        self.keys.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// An exclusive claim on the initialization of a key.
+//
+// The claim is released when dropped, including if the initializing future is cancelled
+// or panics, waking any waiters so that one of them can retry.
+pub struct Claim<'a, K: Hash + Eq + Clone> {
+    pending: &'a Pending<K>,
+    key: K,
+    signal: Arc<Signal>,
+}
+
+impl<K> Drop for Claim<'_, K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn drop(&mut self) {
+        self.pending.lock().remove(&self.key);
+        self.signal.notify();
+    }
+}
+
+// A one-shot notification for the completion of an initialization.
+#[derive(Default)]
+pub struct Signal {
+    done: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl Signal {
+    // Wait for the signal to be notified.
+    pub fn wait(&self) -> Wait<'_> {
+        Wait { signal: self }
+    }
+
+    fn notify(&self) {
+        self.done.store(true, Ordering::Release);
+
+        // Waiters register themselves under the lock before checking `done` a second time,
+        // so any waiter that did not observe the store will be woken here.
+        let wakers = std::mem::take(&mut *self.lock());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Waker>> {
+        self.wakers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// A future that resolves once a `Signal` is notified.
+pub struct Wait<'a> {
+    signal: &'a Signal,
+}
+
+impl Future for Wait<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.signal.done.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+
+        let mut wakers = self.signal.lock();
+
+        // Check again while holding the lock to avoid missing a notification.
+        if self.signal.done.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+
+        Poll::Pending
+    }
+}
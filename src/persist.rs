@@ -0,0 +1,76 @@
+use std::io::{self, Read, Write};
+
+// The magic bytes at the start of every saved map.
+const MAGIC: [u8; 6] = *b"PAPAYA";
+
+// The current version of the format.
+//
+// This must be incremented whenever the layout of the header or entries changes.
+const VERSION: u16 = 1;
+
+// The maximum number of entries to preallocate space for when loading a map.
+//
+// The entry count in the header is untrusted, so a corrupted count must not cause a
+// large allocation. Maps with more entries than this are grown as entries are read.
+pub const MAX_PREALLOCATE: usize = 1 << 16;
+
+// Writes the header of a saved map.
+pub fn write_header(writer: &mut impl Write, len: usize) -> io::Result<()> {
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&(len as u64).to_le_bytes())
+}
+
+// Reads the header of a saved map, returning the number of entries.
+pub fn read_header(reader: &mut impl Read) -> io::Result<usize> {
+    let mut magic = [0; MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(invalid_data("not a saved `HashMap`"));
+    }
+
+    let mut version = [0; 2];
+    reader.read_exact(&mut version)?;
+    let version = u16::from_le_bytes(version);
+    if version != VERSION {
+        return Err(invalid_data(format!(
+            "unsupported format version {version}, expected {VERSION}"
+        )));
+    }
+
+    let len = read_u64(reader)?;
+    usize::try_from(len).map_err(|_| invalid_data("entry count exceeds the address space"))
+}
+
+// Writes a length-prefixed entry.
+pub fn write_entry(writer: &mut impl Write, entry: &[u8]) -> io::Result<()> {
+    writer.write_all(&(entry.len() as u64).to_le_bytes())?;
+    writer.write_all(entry)
+}
+
+// Reads a length-prefixed entry into the buffer, replacing its contents.
+pub fn read_entry(reader: &mut impl Read, buf: &mut Vec<u8>) -> io::Result<()> {
+    let len = read_u64(reader)?;
+
+    // Read through `take` rather than allocating the buffer upfront, so a corrupted
+    // length fails with an unexpected EOF instead of a large allocation.
+    buf.clear();
+    reader.take(len).read_to_end(buf)?;
+    if buf.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    Ok(())
+}
+
+// Reads a little-endian `u64`.
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+// Returns an error for malformed input.
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
@@ -0,0 +1,153 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+use crate::Equivalent;
+
+/// A projection of a key onto the part of it that determines its identity.
+///
+/// Keys are hashed and compared in full by the map. For large keys where only a subset of the
+/// fields determine identity, wrapping keys in [`Projected`] makes the map hash and compare the
+/// projected key instead, while still storing the full key. Entries can then be looked up by the
+/// projected key alone with [`ByProjection`].
+///
+/// # Examples
+///
+/// ```
+/// use papaya::{ByProjection, HashMap, KeyProjection, Projected};
+///
+/// struct User {
+///     id: u64,
+///     name: String,
+/// }
+///
+/// struct ById;
+///
+/// impl KeyProjection<User> for ById {
+///     type Key = u64;
+///
+///     fn project(user: &User) -> &u64 {
+///         &user.id
+///     }
+/// }
+///
+/// let map: HashMap<Projected<User, ById>, u32> = HashMap::new();
+/// let user = User { id: 1, name: "a".to_owned() };
+/// map.pin().insert(Projected::new(user), 10);
+///
+/// let map = map.pin();
+/// let (key, value) = map.get_key_value(&ByProjection(&1)).unwrap();
+/// assert_eq!(key.key().name, "a");
+/// assert_eq!(*value, 10);
+/// ```
+pub trait KeyProjection<K> {
+    /// The projected key.
+    type Key: Hash + Eq + ?Sized;
+
+    /// Projects the full key onto the projected key.
+    fn project(key: &K) -> &Self::Key;
+}
+
+/// A key that is hashed and compared using a [`KeyProjection`].
+///
+/// See [`KeyProjection`] for details.
+pub struct Projected<K, P> {
+    key: K,
+    _projection: PhantomData<fn() -> P>,
+}
+
+impl<K, P> Projected<K, P> {
+    /// Wraps a key to be hashed and compared using the projection `P`.
+    #[inline]
+    pub fn new(key: K) -> Projected<K, P> {
+        Projected {
+            key,
+            _projection: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the full key.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Consumes the wrapper, returning the full key.
+    #[inline]
+    pub fn into_key(self) -> K {
+        self.key
+    }
+}
+
+impl<K, P> Projected<K, P>
+where
+    P: KeyProjection<K>,
+{
+    /// Returns a reference to the projected key.
+    #[inline]
+    pub fn projection(&self) -> &P::Key {
+        P::project(&self.key)
+    }
+}
+
+impl<K, P> Hash for Projected<K, P>
+where
+    P: KeyProjection<K>,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.projection().hash(state)
+    }
+}
+
+impl<K, P> PartialEq for Projected<K, P>
+where
+    P: KeyProjection<K>,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.projection() == other.projection()
+    }
+}
+
+impl<K, P> Eq for Projected<K, P> where P: KeyProjection<K> {}
+
+impl<K: Clone, P> Clone for Projected<K, P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Projected::new(self.key.clone())
+    }
+}
+
+impl<K: fmt::Debug, P> fmt::Debug for Projected<K, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Projected").field(&self.key).finish()
+    }
+}
+
+/// A lookup key for a map of [`Projected`] keys, consisting of only the projected key.
+///
+/// See [`KeyProjection`] for details.
+#[derive(Debug, Clone, Copy)]
+pub struct ByProjection<'a, Q: ?Sized>(pub &'a Q);
+
+impl<Q> Hash for ByProjection<'_, Q>
+where
+    Q: Hash + ?Sized,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Hash exactly like `Projected`.
+        self.0.hash(state)
+    }
+}
+
+impl<K, P> Equivalent<Projected<K, P>> for ByProjection<'_, P::Key>
+where
+    P: KeyProjection<K>,
+{
+    #[inline]
+    fn equivalent(&self, key: &Projected<K, P>) -> bool {
+        *self.0 == *key.projection()
+    }
+}
@@ -64,14 +64,15 @@ impl<T> Clone for Table<T> {
 }
 
 impl<T> Table<T> {
-    // Allocate a table with the provided length and collector.
-    pub fn alloc(len: usize, collector: &Collector) -> Table<T> {
+    // Allocate a table with the provided length and collector, capping the probe
+    // limit at `max_probe` if provided.
+    pub fn alloc(len: usize, max_probe: Option<usize>, collector: &Collector) -> Table<T> {
         assert!(len.is_power_of_two());
 
         // Pad the meta table to fulfill the alignment requirement of an entry.
         let len = len.max(mem::align_of::<AtomicPtr<T>>());
         let mask = len - 1;
-        let limit = probe::limit(len);
+        let limit = probe::limit(len).min(max_probe.unwrap_or(usize::MAX));
 
         let layout = Table::<T>::layout(len);
 
@@ -236,7 +237,7 @@ impl<T> Table<T> {
 fn layout() {
     unsafe {
         let collector = seize::Collector::new();
-        let table: Table<u8> = Table::alloc(4, &collector);
+        let table: Table<u8> = Table::alloc(4, None, &collector);
         let table: Table<u8> = Table::from_raw(table.raw);
 
         // The capacity is padded for pointer alignment.
@@ -2,21 +2,30 @@ mod alloc;
 mod probe;
 pub(crate) mod utils;
 
+use std::cell::Cell;
 use std::hash::{BuildHasher, Hash};
-use std::mem::MaybeUninit;
-use std::sync::atomic::{AtomicPtr, AtomicU8, AtomicUsize, Ordering};
+use std::mem::{self, MaybeUninit};
+use std::sync::atomic::{AtomicPtr, AtomicU64, AtomicU8, AtomicUsize, Ordering};
 use std::sync::Mutex;
 use std::{hint, panic, ptr};
 
 use self::alloc::{RawTable, Table};
 use self::probe::Probe;
-use self::utils::{untagged, AtomicPtrFetchOps, Counter, Parker, Shared, StrictProvenance, Tagged};
-use crate::map::{Compute, Operation, ResizeMode};
+use self::utils::{
+    pool, untagged, AtomicPtrFetchOps, Counter, Parker, Shared, StrictProvenance, Tagged,
+};
+use crate::map::{CollisionReport, Compute, GetResult, Operation, ProbeStrategy, ResizeMode};
 use crate::Equivalent;
 
 use seize::{AsLink, Collector, Link, LocalGuard, OwnedGuard};
 use utils::{MapGuard, VerifiedGuard};
 
+thread_local! {
+    // Set when the current thread allocates a table that grows the map, allowing
+    // operations to report whether they resized the table.
+    static GREW: Cell<bool> = const { Cell::new(false) };
+}
+
 /// A lock-free hash-table.
 pub struct HashMap<K, V, S> {
     /// A pointer to the root table.
@@ -34,6 +43,32 @@ pub struct HashMap<K, V, S> {
     /// An atomic counter of the number of keys in the table.
     count: Counter,
 
+    /// The number of resizes that increased the capacity of the table.
+    grows: AtomicU64,
+
+    /// The number of resizes that decreased the capacity of the table.
+    shrinks: AtomicU64,
+
+    /// The number of local guards created for this map.
+    #[cfg(feature = "stats")]
+    local_guards: Counter,
+
+    /// The number of owned guards created for this map.
+    #[cfg(feature = "stats")]
+    owned_guards: Counter,
+
+    /// Whether entry allocations are recycled through a thread-local pool.
+    pool: bool,
+
+    /// The probe sequence used for table operations.
+    probe: ProbeStrategy,
+
+    /// The ratio of tombstones to capacity at which the table is compacted, if enabled.
+    auto_compact: Option<f64>,
+
+    /// The maximum probe length of a table, if capped.
+    max_probe: Option<usize>,
+
     /// Hasher for keys.
     pub hasher: S,
 }
@@ -53,6 +88,9 @@ pub struct State<T> {
     /// but not necessarily copied.
     pub claim: AtomicUsize,
 
+    /// The number of entries removed from this table, tracked if auto-compaction is enabled.
+    pub tombstones: AtomicUsize,
+
     /// The status of the resize.
     pub status: AtomicU8,
 
@@ -73,6 +111,7 @@ impl<T> Default for State<T> {
             allocating: Mutex::new(()),
             copied: AtomicUsize::new(0),
             claim: AtomicUsize::new(0),
+            tombstones: AtomicUsize::new(0),
             status: AtomicU8::new(State::PENDING),
             parker: Parker::default(),
             deferred: seize::Deferred::new(),
@@ -117,6 +156,9 @@ pub enum RawInsertResult<'g, K, V> {
         current: &'g V,
         not_inserted: *mut Entry<K, V>,
     },
+
+    /// The insert would have required resizing the table.
+    WouldResize(*mut Entry<K, V>),
 }
 
 // An entry in the hash-table.
@@ -130,6 +172,11 @@ pub struct Entry<K, V> {
     pub key: K,
 
     /// The value for this entry.
+    ///
+    /// Note that the value must never be moved out of the entry once it has been
+    /// inserted into the table, as it may have been pinned by `HashMap::get_pin`,
+    /// unless `V: Unpin`. Public methods that move values out are listed in the
+    /// documentation of `HashMap::get_pin`.
     pub value: V,
 }
 
@@ -170,6 +217,23 @@ impl Entry<(), ()> {
         // Safety: The caller guarantees that the pointer is valid.
         let _entry = unsafe { Box::from_raw(entry) };
     }
+
+    /// Reclaims an entry, releasing the allocation to the thread-local pool.
+    ///
+    /// # Safety
+    ///
+    /// The retired pointer must have been a valid pointer of type `*mut Entry<K, V>`.
+    #[inline]
+    unsafe fn reclaim_pooled<K, V>(link: *mut Link) {
+        let entry: *mut Entry<K, V> = link.cast();
+
+        // Safety: The caller guarantees that the pointer is valid, and entry allocations
+        // are always made by the global allocator, either through `Box` or the pool.
+        unsafe {
+            ptr::drop_in_place(entry);
+            pool::dealloc(entry);
+        }
+    }
 }
 
 impl<K, V> utils::Unpack for Entry<K, V> {
@@ -233,13 +297,27 @@ enum InsertStatus<K, V> {
 impl<K, V, S> HashMap<K, V, S> {
     /// Creates new hash-table with the given options.
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         capacity: usize,
         hasher: S,
         collector: Collector,
         resize: ResizeMode,
+        pool: bool,
+        probe: ProbeStrategy,
+        auto_compact: Option<f64>,
+        concurrency: Option<usize>,
+        max_probe: Option<usize>,
     ) -> HashMap<K, V, S> {
+        if let Some(ratio) = auto_compact {
+            assert!(
+                ratio > 0.0 && ratio.is_finite(),
+                "auto-compaction ratio must be positive and finite"
+            );
+        }
+
         let collector = Shared::from(collector);
+        let concurrency = concurrency.unwrap_or_else(Counter::default_shards);
 
         // The table is lazily allocated.
         if capacity == 0 {
@@ -248,12 +326,22 @@ impl<K, V, S> HashMap<K, V, S> {
                 resize,
                 hasher,
                 table: AtomicPtr::new(ptr::null_mut()),
-                count: Counter::default(),
+                count: Counter::new(concurrency),
+                grows: AtomicU64::new(0),
+                shrinks: AtomicU64::new(0),
+                #[cfg(feature = "stats")]
+                local_guards: Counter::new(concurrency),
+                #[cfg(feature = "stats")]
+                owned_guards: Counter::new(concurrency),
+                pool,
+                probe,
+                auto_compact,
+                max_probe,
             };
         }
 
         // Initialize the table and mark it as the root.
-        let mut table = Table::alloc(probe::entries_for(capacity), &collector);
+        let mut table = Table::alloc(probe::entries_for(capacity), max_probe, &collector);
         *table.state_mut().status.get_mut() = State::PROMOTED;
 
         HashMap {
@@ -261,20 +349,66 @@ impl<K, V, S> HashMap<K, V, S> {
             resize,
             collector,
             table: AtomicPtr::new(table.raw),
-            count: Counter::default(),
+            count: Counter::new(concurrency),
+            grows: AtomicU64::new(0),
+            shrinks: AtomicU64::new(0),
+            #[cfg(feature = "stats")]
+            local_guards: Counter::new(concurrency),
+            #[cfg(feature = "stats")]
+            owned_guards: Counter::new(concurrency),
+            pool,
+            probe,
+            auto_compact,
+            max_probe,
         }
     }
 
     /// Returns a guard for this collector
     pub fn guard(&self) -> MapGuard<LocalGuard<'_>> {
         // Safety: Created the guard from our collector.
-        unsafe { MapGuard::new(self.collector().enter()) }
+        unsafe { MapGuard::new(self.enter()) }
     }
 
     /// Returns an owned guard for this collector
     pub fn owned_guard(&self) -> MapGuard<OwnedGuard<'_>> {
         // Safety: Created the guard from our collector.
-        unsafe { MapGuard::new(self.collector().enter_owned()) }
+        unsafe { MapGuard::new(self.enter_owned()) }
+    }
+
+    /// Enters the collector, returning a local guard.
+    #[inline]
+    pub fn enter(&self) -> LocalGuard<'_> {
+        let guard = self.collector.enter();
+
+        #[cfg(feature = "stats")]
+        self.local_guards
+            .get(seize::Guard::thread_id(&guard))
+            .fetch_add(1, Ordering::Relaxed);
+
+        guard
+    }
+
+    /// Enters the collector, returning an owned guard.
+    #[inline]
+    pub fn enter_owned(&self) -> OwnedGuard<'_> {
+        let guard = self.collector.enter_owned();
+
+        #[cfg(feature = "stats")]
+        self.owned_guards
+            .get(seize::Guard::thread_id(&guard))
+            .fetch_add(1, Ordering::Relaxed);
+
+        guard
+    }
+
+    /// Returns the number of local and owned guards created for this map, respectively.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn guard_count(&self) -> (u64, u64) {
+        (
+            self.local_guards.sum() as u64,
+            self.owned_guards.sum() as u64,
+        )
     }
 
     /// Verify a guard is valid to use with this map.
@@ -314,11 +448,36 @@ impl<K, V, S> HashMap<K, V, S> {
         self.count.sum()
     }
 
+    /// Returns the number of resizes that grew and shrunk the table, respectively.
+    #[inline]
+    pub fn resize_count(&self) -> (u64, u64) {
+        (
+            self.grows.load(Ordering::Relaxed),
+            self.shrinks.load(Ordering::Relaxed),
+        )
+    }
+
     /// Returns true if incremental resizing is enabled.
     #[inline]
     fn is_incremental(&self) -> bool {
         matches!(self.resize, ResizeMode::Incremental(_))
     }
+
+    /// Allocates an entry, reusing a pooled allocation if enabled.
+    #[inline]
+    fn alloc_entry<T>(&self, entry: T) -> *mut T {
+        alloc_entry(self.pool, entry)
+    }
+
+    /// Returns the function used to reclaim retired entries.
+    #[inline]
+    fn reclaim_entry(&self) -> unsafe fn(*mut Link) {
+        if self.pool {
+            Entry::reclaim_pooled::<K, V>
+        } else {
+            Entry::reclaim::<K, V>
+        }
+    }
 }
 
 impl<K, V, S> HashMap<K, V, S>
@@ -329,6 +488,82 @@ where
     /// Returns a reference to the entry corresponding to the key.
     #[inline]
     pub fn get<'g, Q>(&self, key: &Q, guard: &'g impl VerifiedGuard) -> Option<(&'g K, &'g V)>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.get_with_hash(self.hasher.hash_one(key), key, guard)
+    }
+
+    /// Returns references to the entries corresponding to the keys, as observed at
+    /// a single point in time.
+    #[inline]
+    pub fn get_consistent<'g, Q, const N: usize>(
+        &self,
+        keys: [&Q; N],
+        guard: &'g impl VerifiedGuard,
+    ) -> [Option<(&'g K, &'g V)>; N]
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        // Every write to a key installs a new entry, and entries cannot be reclaimed while
+        // we hold the guard, so the address of an entry uniquely identifies a write for the
+        // duration of this operation. If two consecutive reads observe the same entries for
+        // every key, none of the keys were written to in between, and so there is a point
+        // between the two reads at which the entries were all present in the map.
+        let mut prev = keys.map(|key| self.get(key, guard));
+
+        loop {
+            let next = keys.map(|key| self.get(key, guard));
+
+            let unchanged = prev
+                .iter()
+                .zip(next.iter())
+                .all(|(prev, next)| match (prev, next) {
+                    (Some((prev, _)), Some((next, _))) => ptr::eq(*prev, *next),
+                    (None, None) => true,
+                    _ => false,
+                });
+
+            if unchanged {
+                return next;
+            }
+
+            prev = next;
+        }
+    }
+
+    /// Returns a reference to the entry corresponding to the key, using a precomputed hash.
+    ///
+    /// The hash must be equal to the hash of the key produced by the hasher.
+    #[inline]
+    pub fn get_with_hash<'g, Q>(
+        &self,
+        hash: u64,
+        key: &Q,
+        guard: &'g impl VerifiedGuard,
+    ) -> Option<(&'g K, &'g V)>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        let entry = self.find_with_hash(hash, key, guard)?;
+
+        // Safety: `find_with_hash` returns a pointer that was loaded with a verified guard,
+        // meaning it is valid for reads as long as we hold the guard.
+        let entry = unsafe { &(*entry) };
+
+        Some((&entry.key, &entry.value))
+    }
+
+    /// Returns a pointer to the entry corresponding to the key, using a precomputed hash.
+    ///
+    /// The returned pointer is valid for reads as long as the guard is held.
+    #[inline]
+    fn find_with_hash<Q>(
+        &self,
+        hash: u64,
+        key: &Q,
+        guard: &impl VerifiedGuard,
+    ) -> Option<*mut Entry<K, V>>
     where
         Q: Equivalent<K> + Hash + ?Sized,
     {
@@ -340,11 +575,11 @@ where
             return None;
         }
 
-        let (h1, h2) = self.hash(key);
+        let (h1, h2) = (meta::h1(hash), meta::h2(hash));
 
         loop {
             // Initialize the probe state.
-            let mut probe = Probe::start(h1, table.mask);
+            let mut probe = Probe::start(h1, table.mask, self.probe);
 
             // Probe until we reach the limit.
             'probe: while probe.len <= table.limit {
@@ -381,8 +616,8 @@ where
                             break 'probe;
                         }
 
-                        // Found the correct entry, return the key and value.
-                        return Some((&entry_ref.key, &entry_ref.value));
+                        // Found the correct entry.
+                        return Some(entry.ptr);
                     }
                 }
 
@@ -410,6 +645,76 @@ where
         }
     }
 
+    /// Returns a reference to the value corresponding to the key, without searching
+    /// tables that are being migrated to.
+    #[inline]
+    pub fn try_get<'g, Q>(&self, key: &Q, guard: &'g impl VerifiedGuard) -> GetResult<'g, V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        // Load the root table.
+        let table = self.root(guard);
+
+        // The table has not been initialized yet.
+        if table.raw.is_null() {
+            return GetResult::Absent;
+        }
+
+        let (h1, h2) = self.hash(key);
+
+        // Initialize the probe state.
+        let mut probe = Probe::start(h1, table.mask, self.probe);
+
+        // Probe until we reach the limit.
+        'probe: while probe.len <= table.limit {
+            // Safety: `probe.i` is always in-bounds for the table length.
+            let meta = unsafe { table.meta(probe.i) }.load(Ordering::Acquire);
+
+            if meta == h2 {
+                // Load the full entry.
+                let entry = guard
+                    // Safety: `probe.i` is always in-bounds for the table length.
+                    .protect(unsafe { table.entry(probe.i) }, Ordering::Acquire)
+                    .unpack();
+
+                // The entry was deleted, keep probing.
+                if entry.ptr.is_null() {
+                    probe.next(table.mask);
+                    continue 'probe;
+                }
+
+                // Safety: We performed a protected load of the pointer using a verified guard with
+                // `Acquire` and ensured that it is non-null, meaning it is valid for reads as long
+                // as we hold the guard.
+                let entry_ref = unsafe { &(*entry.ptr) };
+
+                // Check for a full match.
+                if key.equivalent(&entry_ref.key) {
+                    // The entry was copied to the new table.
+                    if entry.tag() & Entry::COPIED != 0 {
+                        break 'probe;
+                    }
+
+                    return GetResult::Found(&entry_ref.value);
+                }
+            }
+
+            // The key is not in the table, or any of the next tables.
+            if meta == meta::EMPTY {
+                return GetResult::Absent;
+            }
+
+            probe.next(table.mask);
+        }
+
+        // In incremental resize mode, the key may be in the next table.
+        if self.is_incremental() && table.next_table().is_some() {
+            return GetResult::Migrating;
+        }
+
+        GetResult::Absent
+    }
+
     /// Inserts a key-value pair into the table.
     #[inline]
     pub fn insert<'g>(
@@ -418,9 +723,66 @@ where
         value: V,
         replace: bool,
         guard: &'g impl VerifiedGuard,
+    ) -> InsertResult<'g, V> {
+        self.insert_with_hash(self.hasher.hash_one(&key), key, value, replace, guard)
+    }
+
+    /// Inserts a key-value pair into the table, returning whether the insert grew the table.
+    #[inline]
+    pub fn insert_tracked<'g>(
+        &self,
+        key: K,
+        value: V,
+        guard: &'g impl VerifiedGuard,
+    ) -> (InsertResult<'g, V>, bool) {
+        let prev = GREW.with(|grew| grew.replace(false));
+        let result = self.insert(key, value, true, guard);
+        let grew = GREW.with(|grew| grew.replace(prev));
+        (result, grew)
+    }
+
+    /// Inserts a key-value pair into the table, assuming the key is not already present.
+    ///
+    /// If the key is present, a duplicate entry will be inserted into the table.
+    #[inline]
+    pub fn insert_unique<'g>(&self, key: K, value: V, guard: &'g impl VerifiedGuard) -> &'g V {
+        debug_assert!(
+            self.get(&key, guard).is_none(),
+            "`insert_unique` called with a key that is already present"
+        );
+
+        let hash = self.hasher.hash_one(&key);
+        match self.insert_inner(hash, key, value, true, true, true, guard) {
+            RawInsertResult::Inserted(value) => {
+                // Increment the table length.
+                self.count
+                    .get(guard.thread_id())
+                    .fetch_add(1, Ordering::Relaxed);
+
+                value
+            }
+
+            // Unique inserts never match an existing entry.
+            RawInsertResult::Replaced(_) | RawInsertResult::Error { .. } => unreachable!(),
+
+            RawInsertResult::WouldResize(_) => unreachable!(),
+        }
+    }
+
+    /// Inserts a key-value pair into the table, using a precomputed hash.
+    ///
+    /// The hash must be equal to the hash of the key produced by the hasher.
+    #[inline]
+    pub fn insert_with_hash<'g>(
+        &self,
+        hash: u64,
+        key: K,
+        value: V,
+        replace: bool,
+        guard: &'g impl VerifiedGuard,
     ) -> InsertResult<'g, V> {
         // Perform the insert.
-        let raw_result = self.insert_inner(key, value, replace, guard);
+        let raw_result = self.insert_inner(hash, key, value, replace, false, true, guard);
 
         let result = match raw_result {
             // Updated an entry.
@@ -449,26 +811,75 @@ where
                     not_inserted: not_inserted.value,
                 }
             }
+
+            RawInsertResult::WouldResize(_) => unreachable!(),
         };
 
         result
     }
 
+    /// Inserts a key-value pair into the table, unless doing so would require resizing
+    /// the table.
+    ///
+    /// Returns the key and value if the insert would resize the table, or help with an
+    /// in-progress resize.
+    #[inline]
+    pub fn insert_no_resize<'g>(
+        &self,
+        key: K,
+        value: V,
+        guard: &'g impl VerifiedGuard,
+    ) -> Result<Option<&'g V>, (K, V)> {
+        let hash = self.hasher.hash_one(&key);
+
+        match self.insert_inner(hash, key, value, true, false, false, guard) {
+            // Updated an entry.
+            RawInsertResult::Replaced(value) => Ok(Some(value)),
+
+            // Inserted a new entry.
+            RawInsertResult::Inserted(_) => {
+                // Increment the table length.
+                self.count
+                    .get(guard.thread_id())
+                    .fetch_add(1, Ordering::Relaxed);
+
+                Ok(None)
+            }
+
+            // The table needs to be resized.
+            RawInsertResult::WouldResize(not_inserted) => {
+                // Safety: We allocated this box above and it was not inserted into the table.
+                let not_inserted = unsafe { Box::from_raw(not_inserted) };
+                Err((not_inserted.key, not_inserted.value))
+            }
+
+            // Inserts that replace existing entries never fail.
+            RawInsertResult::Error { .. } => unreachable!(),
+        }
+    }
+
     /// Inserts an entry into the map.
+    ///
+    /// If `resize` is `false`, the insert fails instead of allocating or resizing the table,
+    /// or helping with an in-progress resize.
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     fn insert_inner<'g>(
         &self,
+        hash: u64,
         key: K,
         value: V,
         should_replace: bool,
+        unique: bool,
+        resize: bool,
         guard: &'g impl VerifiedGuard,
     ) -> RawInsertResult<'g, K, V> {
         // Allocate the entry to be inserted.
-        let new_entry = untagged(Box::into_raw(Box::new(Entry {
+        let new_entry = untagged(self.alloc_entry(Entry {
             key,
             value,
             link: self.collector.link(),
-        })));
+        }));
 
         // Safety: We just allocated the entry above.
         let new_ref = unsafe { &(*new_entry.ptr) };
@@ -478,15 +889,19 @@ where
 
         // Allocate the table if it has not been initialized yet.
         if table.raw.is_null() {
+            if !resize {
+                return RawInsertResult::WouldResize(new_entry.ptr);
+            }
+
             table = self.init(None);
         }
 
-        let (h1, h2) = self.hash(&new_ref.key);
+        let (h1, h2) = (meta::h1(hash), meta::h2(hash));
 
         let mut help_copy = true;
         loop {
             // Initialize the probe state.
-            let mut probe = Probe::start(h1, table.mask);
+            let mut probe = Probe::start(h1, table.mask, self.probe);
 
             // Probe until we reach the limit.
             let copying = 'probe: loop {
@@ -513,7 +928,18 @@ where
                         //
                         // If the key matches, we might be able to update the value.
                         InsertStatus::Found(EntryStatus::Value(found))
-                        | InsertStatus::Found(EntryStatus::Copied(found)) => found,
+                        | InsertStatus::Found(EntryStatus::Copied(found))
+                            if !unique =>
+                        {
+                            found
+                        }
+
+                        // Otherwise, continue probing.
+                        InsertStatus::Found(EntryStatus::Value(_))
+                        | InsertStatus::Found(EntryStatus::Copied(_)) => {
+                            probe.next(table.mask);
+                            continue 'probe;
+                        }
 
                         // Otherwise, continue probing.
                         InsertStatus::Found(EntryStatus::Null) => {
@@ -523,7 +949,9 @@ where
                     }
                 }
                 // Found a potential match.
-                else if meta == h2 {
+                //
+                // If the key is known to be unique, there is no need to check for a match.
+                else if meta == h2 && !unique {
                     // Load the full entry.
                     let entry = guard
                         // Safety: `probe.i` is always in-bounds for the table length.
@@ -596,6 +1024,11 @@ where
                 }
             };
 
+            // The insert requires resizing, or continuing in a table that is being resized into.
+            if !resize {
+                return RawInsertResult::WouldResize(new_entry.ptr);
+            }
+
             // Prepare to retry in the next table.
             table = self.prepare_retry_insert(copying, &mut help_copy, table, guard);
         }
@@ -684,6 +1117,54 @@ where
     pub fn remove<'g, Q>(&self, key: &Q, guard: &'g impl VerifiedGuard) -> Option<(&'g K, &'g V)>
     where
         Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.remove_with_hash(self.hasher.hash_one(key), key, guard)
+    }
+
+    /// Removes a key from the map using a precomputed hash, returning the entry for the key
+    /// if the key was previously in the map.
+    #[inline]
+    pub fn remove_with_hash<'g, Q>(
+        &self,
+        hash: u64,
+        key: &Q,
+        guard: &'g impl VerifiedGuard,
+    ) -> Option<(&'g K, &'g V)>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.remove_if_with_hash(hash, key, |_, _| true, guard)
+    }
+
+    /// Removes a key from the map if the predicate returns `true` for its entry, returning
+    /// the removed entry.
+    #[inline]
+    pub fn remove_if<'g, Q, F>(
+        &self,
+        key: &Q,
+        should_remove: F,
+        guard: &'g impl VerifiedGuard,
+    ) -> Option<(&'g K, &'g V)>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.remove_if_with_hash(self.hasher.hash_one(key), key, should_remove, guard)
+    }
+
+    /// Removes a key from the map using a precomputed hash if the predicate returns `true`
+    /// for its entry, returning the removed entry.
+    #[inline]
+    fn remove_if_with_hash<'g, Q, F>(
+        &self,
+        hash: u64,
+        key: &Q,
+        mut should_remove: F,
+        guard: &'g impl VerifiedGuard,
+    ) -> Option<(&'g K, &'g V)>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+        F: FnMut(&K, &V) -> bool,
     {
         // Load the root table.
         let mut table = self.root(guard);
@@ -693,12 +1174,12 @@ where
             return None;
         }
 
-        let (h1, h2) = self.hash(key);
+        let (h1, h2) = (meta::h1(hash), meta::h2(hash));
 
         let mut help_copy = true;
         loop {
             // Initialize the probe state.
-            let mut probe = Probe::start(h1, table.mask);
+            let mut probe = Probe::start(h1, table.mask, self.probe);
 
             // Probe until we reach the limit.
             let copying = 'probe: loop {
@@ -752,6 +1233,14 @@ where
                 }
 
                 loop {
+                    // Safety: `entry` is a valid non-null entry that we found in the map.
+                    let entry_ref = unsafe { &(*entry.ptr) };
+
+                    // The entry should be retained.
+                    if !should_remove(&entry_ref.key, &entry_ref.value) {
+                        return None;
+                    }
+
                     // Safety:
                     // - `probe.i` is always in-bounds for the table length
                     // - `entry` is a valid non-null entry that we found in the map.
@@ -778,6 +1267,11 @@ where
                             let count = self.count.get(guard.thread_id());
                             count.fetch_sub(1, Ordering::Relaxed);
 
+                            // Compact the table if we removed enough entries.
+                            if self.record_tombstone(&table) {
+                                self.compact(guard);
+                            }
+
                             // Safety: `entry` is a valid non-null entry that we found in the map
                             // before replacing it.
                             let entry_ref = unsafe { &(*entry.ptr) };
@@ -1000,6 +1494,29 @@ where
         UpdateStatus::Found(status)
     }
 
+    /// Grow the table, regardless of its load.
+    #[inline]
+    pub fn force_resize(&self, guard: &impl VerifiedGuard) {
+        // Complete any in-progress resizes, so that we grow the latest table.
+        self.finalize_resize(guard);
+
+        let mut table = self.root(guard);
+
+        // The table has not yet been allocated, initialize it.
+        if table.raw.is_null() {
+            table = self.init(None);
+        }
+
+        // Race to allocate a table of double the size.
+        //
+        // Note that if a concurrent resize allocated the next table first, we help
+        // with that resize instead.
+        self.get_or_alloc_next(Some(table.len() << 1), table);
+
+        // Help with the copy, which completes it in blocking mode.
+        self.help_copy(false, &table, guard);
+    }
+
     /// Reserve capacity for `additional` more elements.
     #[inline]
     pub fn reserve(&self, additional: usize, guard: &impl VerifiedGuard) {
@@ -1028,17 +1545,502 @@ where
         }
     }
 
-    /// Remove all entries from this table.
+    /// Complete any in-progress resizes.
     #[inline]
-    pub fn clear(&self, guard: &impl VerifiedGuard) {
-        // Load the root table.
+    pub fn finalize_resize(&self, guard: &impl VerifiedGuard) {
         let mut table = self.root(guard);
 
-        // The table has not been initialized yet.
+        // The table has not yet been allocated.
         if table.raw.is_null() {
             return;
         }
 
+        // Help along any pending copies until there is no next table.
+        while table.next_table().is_some() {
+            table = self.help_copy(true, &table, guard);
+        }
+    }
+
+    /// Rehash the table into a new table of the same size, removing any tombstones.
+    #[inline]
+    pub fn compact(&self, guard: &impl VerifiedGuard) {
+        // Complete any in-progress resizes, which removes tombstones from the old table.
+        self.finalize_resize(guard);
+
+        let table = self.root(guard);
+
+        // The table has not yet been allocated.
+        if table.raw.is_null() {
+            return;
+        }
+
+        // Avoid copying the table if there are no tombstones to remove.
+        let has_tombstones = (0..table.len()).any(|i| {
+            // Safety: `i` is in bounds for the table length.
+            unsafe { table.meta(i) }.load(Ordering::Acquire) == meta::TOMBSTONE
+        });
+
+        if !has_tombstones {
+            return;
+        }
+
+        // Race to allocate a table of the same size.
+        //
+        // Note that if a concurrent resize allocated the next table first, completing
+        // that resize will remove the tombstones instead.
+        self.get_or_alloc_next(Some(table.len()), table);
+
+        // Force the copy to complete.
+        self.finalize_resize(guard);
+    }
+
+    /// Records an entry removal from the given table, returning `true` if the table
+    /// should be compacted.
+    #[inline]
+    fn record_tombstone(&self, table: &Table<Entry<K, V>>) -> bool {
+        let Some(ratio) = self.auto_compact else {
+            return false;
+        };
+
+        let threshold = ((ratio * table.len() as f64).ceil() as usize).max(1);
+
+        // Only the removal that reaches the threshold triggers a compaction, the compacted
+        // table will have its own count.
+        table.state().tombstones.fetch_add(1, Ordering::Relaxed) + 1 == threshold
+    }
+
+    /// Returns the number of slots in the most recently allocated table.
+    #[inline]
+    pub fn capacity(&self, guard: &impl VerifiedGuard) -> usize {
+        let mut table = self.root(guard);
+
+        // The table has not yet been allocated.
+        if table.raw.is_null() {
+            return 0;
+        }
+
+        // Follow any in-progress resizes to the newest table.
+        while let Some(next) = table.next_table() {
+            table = next;
+        }
+
+        table.len()
+    }
+
+    /// Returns the number of tombstones in the root table.
+    #[inline]
+    pub fn tombstone_count(&self, guard: &impl VerifiedGuard) -> usize {
+        let table = self.root(guard);
+
+        // The table has not yet been allocated.
+        if table.raw.is_null() {
+            return 0;
+        }
+
+        (0..table.len())
+            .filter(|&i| {
+                // Safety: `i` is in bounds for the table length.
+                unsafe { table.meta(i) }.load(Ordering::Acquire) == meta::TOMBSTONE
+            })
+            .count()
+    }
+
+    /// Clear the table, replacing it with an empty table with room for at least `capacity` entries.
+    #[inline]
+    pub fn clear_and_reserve(&self, capacity: usize, guard: &impl VerifiedGuard) {
+        let capacity = probe::entries_for(capacity);
+        let table = self.root(guard);
+
+        // The table has not yet been allocated, initialize it with the requested capacity.
+        if table.raw.is_null() {
+            self.init(Some(capacity));
+            return;
+        }
+
+        // Remove all entries, which completes any in-progress resizes.
+        self.clear(guard);
+
+        // Race to allocate the right-sized table. The cleared table only contains
+        // tombstones, so the copy is cheap.
+        //
+        // Note that if a concurrent resize allocated the next table first, the map
+        // will end up with that table instead.
+        let table = self.root(guard);
+        self.get_or_alloc_next(Some(capacity), table);
+
+        // Force the copy to complete.
+        self.finalize_resize(guard);
+    }
+
+    /// Replace the hasher, rehashing all entries into a new table.
+    pub fn rehash_with(&mut self, hasher: S) {
+        use seize::Guard;
+
+        // Complete any in-progress resizes to ensure all entries are in the root table.
+        //
+        // Note that this must happen before the hasher is replaced, as copying entries
+        // requires rehashing them.
+        {
+            let guard = self.guard();
+            self.finalize_resize(&guard);
+        }
+
+        let raw = *self.table.get_mut();
+
+        // The table has not yet been allocated, there is nothing to rehash.
+        if raw.is_null() {
+            self.hasher = hasher;
+            return;
+        }
+
+        // Safety: The root table is always a valid pointer to a table allocation, or null.
+        let old = unsafe { Table::<Entry<K, V>>::from_raw(raw) };
+
+        // Copy every entry of the old table into `new`, hashing the keys with the new hasher.
+        //
+        // Returns `false` if an entry did not fit within the probe limit of the new table.
+        let copy = |new: &mut Table<Entry<K, V>>| {
+            'copy: for i in 0..old.len() {
+                // Safety: `i` is in bounds, and we have unique access to the map.
+                let entry = unsafe { old.entry(i) }.load(Ordering::Relaxed);
+
+                // The entry is empty or deleted.
+                if entry.unpack().ptr.is_null() {
+                    continue;
+                }
+
+                // Safety: The entry is non-null, and we have unique access to the map.
+                let hash = hasher.hash_one(unsafe { &(*entry.unpack().ptr).key });
+                let (h1, h2) = (meta::h1(hash), meta::h2(hash));
+
+                let mut probe = Probe::start(h1, new.mask, self.probe);
+                while probe.len <= new.limit {
+                    // Safety: `probe.i` is always in-bounds for the table length.
+                    let meta = unsafe { new.meta(probe.i) };
+
+                    if meta.load(Ordering::Relaxed) == meta::EMPTY {
+                        meta.store(h2, Ordering::Relaxed);
+
+                        // Safety: `probe.i` is always in-bounds for the table length.
+                        unsafe { new.entry(probe.i) }.store(entry, Ordering::Relaxed);
+                        continue 'copy;
+                    }
+
+                    probe.next(new.mask);
+                }
+
+                return false;
+            }
+
+            true
+        };
+
+        let mut len = old.len();
+        let new = loop {
+            let mut new =
+                Table::<Entry<K, V>>::alloc(len, self.max_probe_for(len), &self.collector);
+            *new.state_mut().status.get_mut() = State::PROMOTED;
+
+            // Note that the old table and hasher remain installed until every entry has been
+            // copied, so the map is left unchanged if the new hasher panics.
+            match panic::catch_unwind(panic::AssertUnwindSafe(|| copy(&mut new))) {
+                Ok(true) => break new,
+
+                // An entry did not fit within the probe limit, retry with a larger table.
+                //
+                // Safety: The table was never shared, and does not own any entries.
+                Ok(false) => unsafe { Table::dealloc(new) },
+
+                // Safety: The table was never shared, and does not own any entries.
+                Err(payload) => {
+                    unsafe { Table::dealloc(new) };
+                    panic::resume_unwind(payload);
+                }
+            }
+
+            len <<= 1;
+        };
+
+        self.record_resize(old.len(), new.len());
+
+        // Install the new table along with the hasher used to populate it.
+        //
+        // Release ordering is not required as we have unique access to the map, and any
+        // future access will synchronize with this write.
+        *self.table.get_mut() = new.raw;
+        self.hasher = hasher;
+
+        let guard = self.guard();
+
+        // Retire the old table, as any other table promotion would.
+        //
+        // Safety: The old table is now unreachable from the root pointer.
+        unsafe {
+            guard.defer_retire(old.raw, |link| {
+                let raw: *mut RawTable<Entry<K, V>> = link.cast();
+
+                // Note that we do not drop entries because they have been moved to the new root.
+                drop_table(Table::from_raw(raw));
+            });
+        }
+    }
+
+    /// Returns statistics about the probe sequences of entries in the table.
+    #[inline]
+    pub fn collision_report(&self, guard: &impl VerifiedGuard) -> CollisionReport {
+        // The number of consecutive slots that make up a group.
+        const GROUP: usize = 8;
+
+        let mut report = CollisionReport {
+            entries: 0,
+            max_probe_len: 0,
+            average_probe_len: 0.0,
+            displaced: 0,
+            full_groups: 0,
+        };
+
+        // Load the root table.
+        let root = self.root(guard);
+
+        // The table has not been initialized yet.
+        if root.raw.is_null() {
+            return report;
+        }
+
+        // Get a clean copy of the table to inspect.
+        let table = self.linearize(root, guard);
+
+        let mut total_probe_len = 0;
+        let mut occupied = 0;
+
+        for i in 0..table.len() {
+            // Safety: `i` is in bounds for the table length.
+            let meta = unsafe { table.meta(i) }.load(Ordering::Acquire);
+
+            // Tombstones lengthen probe sequences just like live entries.
+            if meta != meta::EMPTY {
+                occupied += 1;
+            }
+
+            if (i + 1) % GROUP == 0 {
+                if occupied == GROUP {
+                    report.full_groups += 1;
+                }
+
+                occupied = 0;
+            }
+
+            if matches!(meta, meta::EMPTY | meta::TOMBSTONE) {
+                continue;
+            }
+
+            let entry = guard
+                // Safety: `i` is in bounds for the table length.
+                .protect(unsafe { table.entry(i) }, Ordering::Acquire)
+                .unpack();
+
+            // The entry was deleted.
+            if entry.ptr.is_null() {
+                continue;
+            }
+
+            // Safety: We performed a protected load of the pointer using a verified guard with
+            // `Acquire` and ensured that it is non-null, meaning it is valid for reads as long
+            // as we hold the guard.
+            let key = unsafe { &(*entry.ptr).key };
+
+            // Follow the probe sequence of the key until we reach its slot.
+            let mut probe =
+                Probe::start(meta::h1(self.hasher.hash_one(key)), table.mask, self.probe);
+            while probe.i != i && probe.len < table.len() {
+                probe.next(table.mask);
+            }
+
+            report.entries += 1;
+            report.max_probe_len = report.max_probe_len.max(probe.len);
+            total_probe_len += probe.len;
+
+            if probe.len != 0 {
+                report.displaced += 1;
+            }
+        }
+
+        if report.entries != 0 {
+            report.average_probe_len = total_probe_len as f64 / report.entries as f64;
+        }
+
+        report
+    }
+
+    /// Validate the internal invariants of the table, panicking on any violation.
+    ///
+    /// The table must not be concurrently modified.
+    #[cfg(feature = "testing")]
+    pub fn check_invariants(&self, guard: &impl VerifiedGuard) {
+        use std::collections::HashSet;
+
+        // Load the root table.
+        let mut table = self.root(guard);
+
+        // The table has not been initialized yet.
+        if table.raw.is_null() {
+            assert_eq!(self.len(), 0, "uninitialized map reports a non-zero length");
+            return;
+        }
+
+        // Keys that are live in any table.
+        let mut live = HashSet::<&K>::new();
+
+        for depth in 0.. {
+            let (len, mask, limit) = (table.len(), table.mask, table.limit);
+
+            assert!(
+                len.is_power_of_two() && mask == len - 1,
+                "table {depth} has length {len} but mask {mask:#x}"
+            );
+            // The probe limit is capped by the maximum probe length, unless the cap was
+            // lifted for a sparse table.
+            let capped = probe::limit(len).min(self.max_probe.unwrap_or(usize::MAX));
+            assert!(
+                limit == capped || limit == probe::limit(len),
+                "table {depth} has an unexpected probe limit, found {limit} but expected {capped}"
+            );
+
+            // Keys that were found in this table.
+            let mut seen = HashSet::<&K>::new();
+            let (mut entries, mut tombstones, mut empty) = (0, 0, 0);
+
+            for i in 0..len {
+                // Safety: `i` is in bounds for the table length.
+                let meta = unsafe { table.meta(i) }.load(Ordering::Acquire);
+                let entry = guard
+                    // Safety: `i` is in bounds for the table length.
+                    .protect(unsafe { table.entry(i) }, Ordering::Acquire)
+                    .unpack();
+
+                if entry.ptr.is_null() {
+                    match meta {
+                        meta::EMPTY => {
+                            assert!(
+                                entry.raw.is_null(),
+                                "empty slot {i} in table {depth} (length {len}) has tagged entry {:p}",
+                                entry.raw
+                            );
+                            empty += 1;
+                        }
+                        meta::TOMBSTONE => tombstones += 1,
+                        _ => panic!(
+                            "null entry at slot {i} in table {depth} (length {len}) has metadata {meta:#x}"
+                        ),
+                    }
+
+                    continue;
+                }
+
+                entries += 1;
+
+                // Safety: We performed a protected load of the pointer using a verified guard with
+                // `Acquire` and ensured that it is non-null, meaning it is valid for reads as long
+                // as we hold the guard.
+                let key = unsafe { &(*entry.ptr).key };
+                let hash = self.hasher.hash_one(key);
+
+                assert_eq!(
+                    meta,
+                    meta::h2(hash),
+                    "entry at slot {i} in table {depth} (length {len}) has mismatched metadata"
+                );
+
+                assert!(
+                    seen.insert(key),
+                    "duplicate key at slot {i} in table {depth} (length {len})"
+                );
+
+                // Ensure the entry is reachable from the start of its probe sequence.
+                let mut probe = Probe::start(meta::h1(hash), mask, self.probe);
+                while probe.i != i {
+                    // Safety: `probe.i` is always in-bounds for the table length.
+                    let meta = unsafe { table.meta(probe.i) }.load(Ordering::Acquire);
+
+                    assert!(
+                        meta != meta::EMPTY,
+                        "entry at slot {i} in table {depth} (length {len}) is unreachable, \
+                         its probe sequence hits an empty slot at {}",
+                        probe.i
+                    );
+
+                    probe.next(mask);
+
+                    assert!(
+                        probe.len <= limit,
+                        "entry at slot {i} in table {depth} (length {len}) is past the probe limit {limit}"
+                    );
+                }
+
+                // The entry was copied to the next table.
+                if entry.tag() & Entry::COPIED != 0 {
+                    assert!(
+                        table.next_table().is_some(),
+                        "entry at slot {i} in table {depth} (length {len}) was copied, \
+                         but there is no next table"
+                    );
+
+                    continue;
+                }
+
+                assert!(
+                    entry.tag() & Entry::COPYING == 0,
+                    "entry at slot {i} in table {depth} (length {len}) is stuck mid-copy"
+                );
+
+                assert!(
+                    live.insert(key),
+                    "key at slot {i} in table {depth} (length {len}) is live in multiple tables"
+                );
+            }
+
+            assert_eq!(
+                entries + tombstones + empty,
+                len,
+                "table {depth} has {entries} entries, {tombstones} tombstones, and {empty} \
+                 empty slots, but length {len}"
+            );
+
+            match table.next_table() {
+                Some(next) => {
+                    assert!(
+                        self.is_incremental(),
+                        "blocking map has an in-progress resize from table {depth} (length {len}) \
+                         with {entries} entries and {tombstones} tombstones"
+                    );
+
+                    table = next;
+                }
+                None => break,
+            }
+        }
+
+        assert_eq!(
+            live.len(),
+            self.len(),
+            "map contains {} live entries but reports length {}",
+            live.len(),
+            self.len()
+        );
+    }
+
+    /// Remove all entries from this table.
+    #[inline]
+    pub fn clear(&self, guard: &impl VerifiedGuard) {
+        // Load the root table.
+        let mut table = self.root(guard);
+
+        // The table has not been initialized yet.
+        if table.raw.is_null() {
+            return;
+        }
+
+        let mut compact = false;
         loop {
             // Get a clean copy of the table to delete from.
             table = self.linearize(table, guard);
@@ -1091,6 +2093,9 @@ where
                             let count = self.count.get(guard.thread_id());
                             count.fetch_sub(1, Ordering::Relaxed);
 
+                            // Record the tombstone for auto-compaction.
+                            compact |= self.record_tombstone(&table);
+
                             // Safety: The caller guarantees that `current` is a valid non-null entry that was
                             // inserted into the map. Additionally, it is now unreachable from this table due
                             // to the CAS above.
@@ -1114,13 +2119,156 @@ where
             // Complete the resize and retry in the new table.
             table = self.help_copy(true, &table, guard);
         }
+
+        // Compact the table if we removed enough entries.
+        if compact {
+            self.compact(guard);
+        }
+    }
+
+    /// Updates every entry in the table with a closure, leaving entries unchanged
+    /// if the closure returns `None`.
+    #[inline]
+    pub fn for_each_update<F>(&self, mut f: F, guard: &impl VerifiedGuard)
+    where
+        K: Clone,
+        F: FnMut(&K, &V) -> Option<V>,
+    {
+        for (key, _) in self.iter(guard) {
+            let compute = |entry| match entry {
+                // The entry was removed after we observed it.
+                None => Operation::Abort(()),
+                Some((key, value)) => match f(key, value) {
+                    Some(value) => Operation::Insert(value),
+                    None => Operation::Abort(()),
+                },
+            };
+
+            // Perform the update against the current value of the entry.
+            self.compute(key.clone(), compute, guard);
+        }
+    }
+
+    /// Updates every entry in the table with a closure, splitting the sweep across
+    /// the rayon thread pool.
+    #[cfg(feature = "rayon")]
+    pub fn par_update_all<F>(&self, f: F, guard: &impl VerifiedGuard)
+    where
+        K: Clone + Send + Sync,
+        V: Send + Sync,
+        S: Sync,
+        F: Fn(&V) -> V + Sync,
+    {
+        use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+        use seize::Guard;
+
+        // The minimum number of slots swept by each rayon task.
+        const CHUNK: usize = 256;
+
+        // Shares the table and map with the worker threads.
+        struct Sweep<'a, K, V, S> {
+            table: Table<Entry<K, V>>,
+            map: &'a HashMap<K, V, S>,
+        }
+
+        // Safety: Workers only access the table and map through shared references, and
+        // update entries with owned keys and values, so it is enough that the map itself
+        // can be shared across threads.
+        unsafe impl<K: Send + Sync, V: Send + Sync, S: Sync> Sync for Sweep<'_, K, V, S> {}
+
+        // Accessing the fields through methods ensures closures capture the entire
+        // wrapper, rather than the individual fields.
+        impl<K, V, S> Sweep<'_, K, V, S> {
+            fn table(&self) -> Table<Entry<K, V>> {
+                self.table
+            }
+
+            fn map(&self) -> &HashMap<K, V, S> {
+                self.map
+            }
+        }
+
+        // Load the root table.
+        let root = self.root(guard);
+
+        // The table has not been initialized yet.
+        if root.raw.is_null() {
+            return;
+        }
+
+        // Get a clean copy of the table to sweep.
+        //
+        // The table is kept alive by the caller's guard for the duration of the sweep,
+        // while every worker thread protects the entries it loads with its own guard.
+        let sweep = &Sweep {
+            table: self.linearize(root, guard),
+            map: self,
+        };
+
+        (0..sweep.table().len())
+            .into_par_iter()
+            .with_min_len(CHUNK)
+            .for_each_init(
+                || sweep.map().enter(),
+                |guard, i| {
+                    let (table, map) = (sweep.table(), sweep.map());
+                    let guard = map.verify(guard);
+
+                    // Load the entry metadata first to ensure consistency with calls to `get`.
+                    //
+                    // Safety: `i` is in bounds for the table length.
+                    let meta = unsafe { table.meta(i) }.load(Ordering::Acquire);
+
+                    // The entry is empty or deleted.
+                    if matches!(meta, meta::EMPTY | meta::TOMBSTONE) {
+                        return;
+                    }
+
+                    let entry = guard
+                        // Safety: `i` is in bounds for the table length.
+                        .protect(unsafe { table.entry(i) }, Ordering::Acquire)
+                        .unpack();
+
+                    // The entry was deleted.
+                    if entry.ptr.is_null() {
+                        return;
+                    }
+
+                    // Safety: We performed a protected load of the pointer using a verified guard with
+                    // `Acquire` and ensured that it is non-null, meaning it is valid for reads as long
+                    // as we hold the guard.
+                    let key = unsafe { &(*entry.ptr).key };
+
+                    let compute = |entry: Option<(&K, &V)>| match entry {
+                        // The entry was removed after we observed it.
+                        None => Operation::Abort(()),
+                        Some((_, value)) => Operation::Insert(f(value)),
+                    };
+
+                    // Perform the update against the current value of the entry.
+                    map.compute(key.clone(), compute, guard);
+                },
+            );
     }
 
     /// Retains only the elements specified by the predicate.
     #[inline]
-    pub fn retain<F>(&self, mut f: F, guard: &impl VerifiedGuard)
+    pub fn retain<F>(&self, f: F, guard: &impl VerifiedGuard)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.retain_with(f, |_, _| {}, guard)
+    }
+
+    /// Retains only the elements specified by the predicate, calling `on_removed`
+    /// for every entry that was removed.
+    #[inline]
+    pub fn retain_with<'g, F, R>(&self, mut f: F, mut on_removed: R, guard: &'g impl VerifiedGuard)
     where
+        K: 'g,
+        V: 'g,
         F: FnMut(&K, &V) -> bool,
+        R: FnMut(&'g K, &'g V),
     {
         // Load the root table.
         let mut table = self.root(guard);
@@ -1130,6 +2278,7 @@ where
             return;
         }
 
+        let mut compact = false;
         loop {
             // Get a clean copy of the table to delete from.
             table = self.linearize(table, guard);
@@ -1169,7 +2318,7 @@ where
                     // Safety: We performed a protected load of the pointer using a verified guard with
                     // `Acquire` and ensured that it is non-null, meaning it is valid for reads as long
                     // as we hold the guard.
-                    let entry_ref = unsafe { &*entry.raw };
+                    let entry_ref = unsafe { &*entry.ptr };
 
                     // Should we retain this entry?
                     if f(&entry_ref.key, &entry_ref.value) {
@@ -1200,6 +2349,12 @@ where
                             let count = self.count.get(guard.thread_id());
                             count.fetch_sub(1, Ordering::Relaxed);
 
+                            // Record the tombstone for auto-compaction.
+                            compact |= self.record_tombstone(&table);
+
+                            // Notify the caller of the removal.
+                            on_removed(&entry_ref.key, &entry_ref.value);
+
                             // Safety: The caller guarantees that `current` is a valid non-null entry that was
                             // inserted into the map. Additionally, it is now unreachable from this table due
                             // to the CAS above.
@@ -1223,6 +2378,11 @@ where
             // Complete the resize and retry in the new table.
             table = self.help_copy(true, &table, guard);
         }
+
+        // Compact the table if we removed enough entries.
+        if compact {
+            self.compact(guard);
+        }
     }
 
     /// Returns an iterator over the keys and values of this table.
@@ -1238,15 +2398,61 @@ where
         if root.raw.is_null() {
             return Iter {
                 i: 0,
+                remaining: 0,
                 guard,
                 table: root,
             };
         }
 
         // Get a clean copy of the table to iterate over.
+        //
+        // If there is no resize in progress this is the root table. Otherwise, the resize is
+        // completed first. Either way, iteration only ever has to walk a single table.
         let table = self.linearize(root, guard);
 
-        Iter { i: 0, guard, table }
+        Iter {
+            i: 0,
+            remaining: self.len(),
+            guard,
+            table,
+        }
+    }
+
+    /// Returns an iterator over the keys and values of this table that follows entries
+    /// migrated by later resizes.
+    #[inline]
+    pub fn iter_live<'g, G>(&'g self, guard: &'g G) -> LiveIter<'g, K, V, S, G>
+    where
+        G: VerifiedGuard,
+    {
+        LiveIter {
+            iter: self.iter(guard),
+            map: self,
+        }
+    }
+
+    /// Returns an iterator over the keys and values of the root table that never helps
+    /// with in-progress resizes.
+    #[inline]
+    pub fn iter_passive<'g, G>(&'g self, guard: &'g G) -> LiveIter<'g, K, V, S, G>
+    where
+        G: VerifiedGuard,
+    {
+        // Unlike `iter`, we walk the root table directly instead of completing any
+        // in-progress resizes. Entries that have already been migrated are read from
+        // the newest table instead, which never requires copying.
+        //
+        // Entries inserted into a newer table are not yielded, so the length of the map
+        // is not a valid lower bound for the number of entries.
+        LiveIter {
+            iter: Iter {
+                i: 0,
+                remaining: 0,
+                guard,
+                table: self.root(guard),
+            },
+            map: self,
+        }
     }
 
     /// Returns the h1 and h2 hash for the given key.
@@ -1270,6 +2476,12 @@ struct ComputeState<F, K, V, T> {
 
     /// A cached update transition.
     update: Option<CachedUpdate<K, V, T>>,
+
+    /// The number of times the operation was retried.
+    retries: u32,
+
+    /// The slot of the entry that the operation completed on, if any.
+    slot: Option<Slot<K, V>>,
 }
 
 /// A cached update transition.
@@ -1294,6 +2506,8 @@ where
             compute,
             insert: None,
             update: None,
+            retries: 0,
+            slot: None,
         }
     }
 
@@ -1340,6 +2554,8 @@ where
     /// `compute(None)` being called at most once.
     #[inline]
     fn restore(&mut self, input: Option<*mut Entry<K, V>>, output: Operation<V, T>) {
+        self.retries = self.retries.saturating_add(1);
+
         match input {
             Some(input) => self.update = Some(CachedUpdate { input, output }),
             None => match output {
@@ -1350,6 +2566,24 @@ where
     }
 }
 
+/// The location of an entry in a table.
+///
+/// A slot allows later operations on the same key to access the entry directly, without
+/// probing. The slot is only a hint, the entry may have been concurrently removed or
+/// copied to a new table, in which case operations fall back to probing for the key.
+pub struct Slot<K, V> {
+    table: Table<Entry<K, V>>,
+    i: usize,
+}
+
+impl<K, V> Copy for Slot<K, V> {}
+
+impl<K, V> Clone for Slot<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
 /// A lazy initialized `Entry` allocation.
 enum LazyEntry<K, V> {
     /// An uninitialized entry, containing just the owned key.
@@ -1359,6 +2593,21 @@ enum LazyEntry<K, V> {
     Init(*mut Entry<K, MaybeUninit<V>>),
 }
 
+/// A lazy initialized `Entry` allocation that is deallocated on drop.
+///
+/// Ownership of the entry must be released with `mem::forget` once it is inserted into the map.
+struct PendingEntry<K, V>(LazyEntry<K, V>);
+
+impl<K, V> Drop for PendingEntry<K, V> {
+    fn drop(&mut self) {
+        if let LazyEntry::Init(entry) = self.0 {
+            // Safety: The entry was allocated but not inserted into the map. Note that the value
+            // is uninitialized, or was moved out of the entry, and so is not dropped.
+            let _ = unsafe { Box::from_raw(entry) };
+        }
+    }
+}
+
 impl<K, V> LazyEntry<K, V> {
     /// Returns a reference to the entry's key.
     #[inline]
@@ -1372,7 +2621,7 @@ impl<K, V> LazyEntry<K, V> {
     /// Initializes the entry if it has not already been initialized, returning the pointer
     /// to the entry allocation.
     #[inline]
-    fn init(&mut self, collector: &Collector) -> *mut Entry<K, MaybeUninit<V>> {
+    fn init(&mut self, collector: &Collector, pool: bool) -> *mut Entry<K, MaybeUninit<V>> {
         match self {
             LazyEntry::Init(entry) => *entry,
             LazyEntry::Uninit(key) => {
@@ -1382,11 +2631,14 @@ impl<K, V> LazyEntry<K, V> {
                 unsafe {
                     let key = ptr::read(key);
                     let entry = panic::catch_unwind(panic::AssertUnwindSafe(|| {
-                        Box::into_raw(Box::new(Entry {
-                            link: collector.link(),
-                            value: MaybeUninit::uninit(),
-                            key,
-                        }))
+                        alloc_entry(
+                            pool,
+                            Entry {
+                                link: collector.link(),
+                                value: MaybeUninit::uninit(),
+                                key,
+                            },
+                        )
                     }))
                     .unwrap_or_else(|_| std::process::abort());
                     ptr::write(self, LazyEntry::Init(entry));
@@ -1439,24 +2691,300 @@ where
         }
     }
 
-    /// Returns a reference to the value corresponding to the key, or inserts a default value
-    /// computed from a closure.
+    /// Returns a reference to the value corresponding to the key, or inserts a default value
+    /// computed from a closure.
+    #[inline]
+    pub fn get_or_insert_with<'g, F>(&self, key: K, f: F, guard: &'g impl VerifiedGuard) -> &'g V
+    where
+        F: FnOnce() -> V,
+        K: 'g,
+    {
+        match self.try_insert_with(key, f, guard) {
+            Ok(value) => value,
+            Err(value) => value,
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the key, or inserts a default value
+    /// computed from a closure that is given a reference to the key.
+    #[inline]
+    pub fn get_or_insert_with_key<'g, F>(
+        &self,
+        key: K,
+        f: F,
+        guard: &'g impl VerifiedGuard,
+    ) -> &'g V
+    where
+        F: FnOnce(&K) -> V,
+        K: 'g,
+    {
+        let hash = self.hasher.hash_one(&key);
+
+        // Avoid calling the closure if the key is already present.
+        if let Some((_, value)) = self.get_with_hash(hash, &key, guard) {
+            return value;
+        }
+
+        let value = f(&key);
+
+        // Note that the key may have been inserted concurrently, in which case the value
+        // we computed is dropped, similar to a cached insert in `compute`.
+        match self.insert_with_hash(hash, key, value, false, guard) {
+            InsertResult::Inserted(value) => value,
+            InsertResult::Error { current, .. } => current,
+            InsertResult::Replaced(_) => unreachable!(),
+        }
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    #[inline]
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        let hash = self.hasher.hash_one(key);
+        let entry = self.find_with_hash(hash, key, &self.guard())?;
+
+        // Safety: The entry is live in the map, and we hold a unique reference to the map,
+        // so there are no active guards or references to the entry. Retired entries are
+        // separate allocations, so stale copies cannot alias the value.
+        Some(unsafe { &mut (*entry).value })
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key, or inserts a default
+    /// value computed from a closure.
+    #[inline]
+    pub fn get_mut_or_insert_with<F>(&mut self, key: K, f: F) -> &mut V
+    where
+        F: FnOnce() -> V,
+    {
+        let hash = self.hasher.hash_one(&key);
+        let guard = self.guard();
+
+        let mut f = Some(f);
+        let compute = |entry| match entry {
+            // There is already an existing value.
+            Some((key, _)) => Operation::Abort(key),
+
+            // Insert the initial value.
+            //
+            // Note that this case is guaranteed to be executed at most
+            // once as insert values are cached, so this can never panic.
+            None => Operation::Insert((f.take().unwrap())()),
+        };
+
+        let key = match self.compute_with_hash(hash, key, compute, &guard).0 {
+            Compute::Aborted(key) | Compute::Inserted(key, _) => key,
+            _ => unreachable!(),
+        };
+
+        // The entry is present in the map, and cannot be removed as we have unique access.
+        let entry = self.find_with_hash(hash, key, &guard).unwrap();
+
+        // Safety: The entry is live in the map, and we hold a unique reference to the map,
+        // so there are no active guards or references to the entry. Retired entries are
+        // separate allocations, so stale copies cannot alias the value.
+        unsafe { &mut (*entry).value }
+    }
+
+    /// Updates an existing entry atomically, returning the value that was inserted.
+    #[inline]
+    pub fn update<'g, F>(&self, key: K, update: F, guard: &'g impl VerifiedGuard) -> Option<&'g V>
+    where
+        F: FnMut(&V) -> V,
+        K: 'g,
+    {
+        self.update_with_hash(self.hasher.hash_one(&key), key, update, guard)
+    }
+
+    /// Updates an existing entry atomically using a precomputed hash, returning the value
+    /// that was inserted.
+    #[inline]
+    pub fn update_with_hash<'g, F>(
+        &self,
+        hash: u64,
+        key: K,
+        mut update: F,
+        guard: &'g impl VerifiedGuard,
+    ) -> Option<&'g V>
+    where
+        F: FnMut(&V) -> V,
+        K: 'g,
+    {
+        let compute = |entry| match entry {
+            // There is nothing to update.
+            None => Operation::Abort(()),
+            // Perform the update.
+            Some((_, value)) => Operation::Insert(update(value)),
+        };
+
+        match self.compute_with_hash(hash, key, compute, guard).0 {
+            // Return the updated value.
+            Compute::Updated {
+                new: (_, value), ..
+            } => Some(value),
+
+            // There was nothing to update.
+            Compute::Aborted(_) => None,
+
+            _ => unreachable!(),
+        }
+    }
+
+    /// Updates an existing entry or inserts a default value computed from a closure.
+    #[inline]
+    pub fn update_or_insert_with<'g, U, F>(
+        &self,
+        key: K,
+        update: U,
+        f: F,
+        guard: &'g impl VerifiedGuard,
+    ) -> &'g V
+    where
+        F: FnOnce() -> V,
+        U: Fn(&V) -> V,
+        K: 'g,
+    {
+        let mut f = Some(f);
+        let compute = |entry| match entry {
+            // Perform the update.
+            Some((_, value)) => Operation::Insert::<_, ()>(update(value)),
+
+            // Insert the initial value.
+            //
+            // Note that this case is guaranteed to be executed at most
+            // once as insert values are cached, so this can never panic.
+            None => Operation::Insert((f.take().unwrap())()),
+        };
+
+        match self.compute(key, compute, guard) {
+            // Return the updated value.
+            Compute::Updated {
+                new: (_, value), ..
+            } => value,
+
+            // Return the value we inserted.
+            Compute::Inserted(_, value) => value,
+
+            _ => unreachable!(),
+        }
+    }
+
+    /// Update an entry with a CAS function.
+    ///
+    /// Note that `compute` closure is guaranteed to be called for a `None` input only once, allowing the
+    /// insertion of values that cannot be cloned or reconstructed.
+    #[inline]
+    pub fn compute<'g, F, T>(
+        &self,
+        key: K,
+        compute: F,
+        guard: &'g impl VerifiedGuard,
+    ) -> Compute<'g, K, V, T>
+    where
+        F: FnMut(Option<(&'g K, &'g V)>) -> Operation<V, T>,
+    {
+        self.compute_verbose(key, compute, guard).0
+    }
+
+    /// Update an entry with a CAS function, returning the number of times the
+    /// operation was retried.
+    #[inline]
+    pub fn compute_verbose<'g, F, T>(
+        &self,
+        key: K,
+        compute: F,
+        guard: &'g impl VerifiedGuard,
+    ) -> (Compute<'g, K, V, T>, u32)
+    where
+        F: FnMut(Option<(&'g K, &'g V)>) -> Operation<V, T>,
+    {
+        self.compute_with_hash(self.hasher.hash_one(&key), key, compute, guard)
+    }
+
+    /// Update an entry with a CAS function using a precomputed hash, returning the number
+    /// of times the operation was retried.
+    #[inline]
+    pub fn compute_with_hash<'g, F, T>(
+        &self,
+        hash: u64,
+        key: K,
+        compute: F,
+        guard: &'g impl VerifiedGuard,
+    ) -> (Compute<'g, K, V, T>, u32)
+    where
+        F: FnMut(Option<(&'g K, &'g V)>) -> Operation<V, T>,
+    {
+        // Lazy initialize the entry allocation.
+        //
+        // The entry is deallocated on drop if it is not inserted, including if the
+        // compute function panics.
+        let entry = PendingEntry(LazyEntry::Uninit(key));
+        let mut state = ComputeState::new(compute);
+        let result = self.compute_pending(hash, entry, &mut state, guard);
+        (result, state.retries)
+    }
+
+    /// Update an entry with a CAS function using a precomputed hash, returning the slot
+    /// of the entry that the operation completed on.
+    ///
+    /// The slot is returned if the entry was inserted, updated, or the operation was aborted
+    /// after observing an existing entry.
+    #[inline]
+    pub fn compute_with_slot<'g, F, T>(
+        &self,
+        hash: u64,
+        key: K,
+        compute: F,
+        guard: &'g impl VerifiedGuard,
+    ) -> (Compute<'g, K, V, T>, Option<Slot<K, V>>)
+    where
+        F: FnMut(Option<(&'g K, &'g V)>) -> Operation<V, T>,
+    {
+        let entry = PendingEntry(LazyEntry::Uninit(key));
+        let mut state = ComputeState::new(compute);
+        let result = self.compute_pending(hash, entry, &mut state, guard);
+        (result, state.slot)
+    }
+
+    /// Returns the entry at the given slot, or probes for the key if the slot is stale.
     #[inline]
-    pub fn get_or_insert_with<'g, F>(&self, key: K, f: F, guard: &'g impl VerifiedGuard) -> &'g V
-    where
-        F: FnOnce() -> V,
-        K: 'g,
-    {
-        match self.try_insert_with(key, f, guard) {
-            Ok(value) => value,
-            Err(value) => value,
+    pub fn get_at_slot<'g>(
+        &self,
+        slot: Slot<K, V>,
+        hash: u64,
+        key: &K,
+        guard: &'g impl VerifiedGuard,
+    ) -> Option<(&'g K, &'g V)> {
+        // Safety: The slot index is in-bounds for its table, which is protected by the guard.
+        let found = guard
+            .protect(unsafe { slot.table.entry(slot.i) }, Ordering::Acquire)
+            .unpack();
+
+        // Entries that are being copied are read from the new table.
+        if !found.ptr.is_null() && found.tag() & Entry::COPYING == 0 {
+            // Safety: We performed a protected load of the pointer using a verified guard with
+            // `Acquire` and ensured that it is non-null, meaning it is valid for reads as long
+            // as we hold the guard.
+            let entry = unsafe { &*found.ptr };
+
+            if entry.key == *key {
+                return Some((&entry.key, &entry.value));
+            }
         }
+
+        self.get_with_hash(hash, key, guard)
     }
 
-    /// Updates an existing entry atomically, returning the value that was inserted.
+    /// Updates the entry at the given slot, or probes for the key if the slot is stale,
+    /// returning the value that was inserted.
+    ///
+    /// If the update succeeds, the slot is set to the location of the new entry.
     #[inline]
-    pub fn update<'g, F>(
+    pub fn update_at_slot<'g, F>(
         &self,
+        slot: &mut Slot<K, V>,
+        hash: u64,
         key: K,
         mut update: F,
         guard: &'g impl VerifiedGuard,
@@ -1465,6 +2993,51 @@ where
         F: FnMut(&V) -> V,
         K: 'g,
     {
+        let mut entry = PendingEntry(LazyEntry::Uninit(key));
+        let table = slot.table;
+
+        // Safety: The slot index is in-bounds for its table, which is protected by the guard.
+        let current = guard
+            .protect(unsafe { table.entry(slot.i) }, Ordering::Acquire)
+            .unpack();
+
+        // Try to update the entry directly, as long as it has not been removed or copied.
+        //
+        // Safety: We performed a protected load of the pointer using a verified guard with
+        // `Acquire` and ensured that it is non-null, meaning it is valid for reads as long
+        // as we hold the guard.
+        if !current.ptr.is_null()
+            && current.tag() & Entry::COPYING == 0
+            && unsafe { (*current.ptr).key == *entry.0.key() }
+        {
+            // Safety: `current` is valid for reads.
+            let value = update(unsafe { &(*current.ptr).value });
+            let new_entry = entry.0.init(&self.collector, self.pool);
+
+            // Safety: `new_entry` was just allocated above and is valid for writes.
+            unsafe { (*new_entry).value = MaybeUninit::new(value) }
+
+            // Safety:
+            // - The slot index is in-bounds for its table.
+            // - `current` is a valid non-null entry that we found in the map.
+            // - `new_entry` was initialized above and never shared.
+            let status = unsafe { self.update_at(slot.i, current, new_entry.cast(), table, guard) };
+
+            if let UpdateStatus::Replaced(_) = status {
+                // The entry was inserted into the map, so it must not be deallocated.
+                mem::forget(entry);
+
+                // Safety: `new_entry` was initialized above.
+                let new_ref = unsafe { &*new_entry.cast::<Entry<K, V>>() };
+                return Some(&new_ref.value);
+            }
+
+            // Otherwise, drop the value we computed and retry by probing.
+            //
+            // Safety: `new_entry` was initialized above and was not inserted into the map.
+            drop(unsafe { (*new_entry).value.assume_init_read() });
+        }
+
         let compute = |entry| match entry {
             // There is nothing to update.
             None => Operation::Abort(()),
@@ -1472,11 +3045,16 @@ where
             Some((_, value)) => Operation::Insert(update(value)),
         };
 
-        match self.compute(key, compute, guard) {
+        let mut state = ComputeState::new(compute);
+        match self.compute_pending(hash, entry, &mut state, guard) {
             // Return the updated value.
             Compute::Updated {
                 new: (_, value), ..
-            } => Some(value),
+            } => {
+                // The slot is always recorded when an entry is updated.
+                *slot = state.slot.unwrap();
+                Some(value)
+            }
 
             // There was nothing to update.
             Compute::Aborted(_) => None,
@@ -1485,73 +3063,140 @@ where
         }
     }
 
-    /// Updates an existing entry or inserts a default value computed from a closure.
+    /// Inserts a value at the given slot, or probes for the key if the slot is stale,
+    /// returning the previous value.
+    ///
+    /// If the entry has since been removed, the key and value are inserted as a new entry.
     #[inline]
-    pub fn update_or_insert_with<'g, U, F>(
+    pub fn insert_at_slot<'g>(
         &self,
+        slot: Slot<K, V>,
+        hash: u64,
         key: K,
-        update: U,
-        f: F,
+        value: V,
         guard: &'g impl VerifiedGuard,
-    ) -> &'g V
-    where
-        F: FnOnce() -> V,
-        U: Fn(&V) -> V,
-        K: 'g,
-    {
-        let mut f = Some(f);
-        let compute = |entry| match entry {
-            // Perform the update.
-            Some((_, value)) => Operation::Insert::<_, ()>(update(value)),
+    ) -> InsertResult<'g, V> {
+        let table = slot.table;
 
-            // Insert the initial value.
-            //
-            // Note that this case is guaranteed to be executed at most
-            // once as insert values are cached, so this can never panic.
-            None => Operation::Insert((f.take().unwrap())()),
-        };
+        // Safety: The slot index is in-bounds for its table, which is protected by the guard.
+        let current = guard
+            .protect(unsafe { table.entry(slot.i) }, Ordering::Acquire)
+            .unpack();
 
-        match self.compute(key, compute, guard) {
-            // Return the updated value.
-            Compute::Updated {
-                new: (_, value), ..
-            } => value,
+        // Try to replace the entry directly, as long as it has not been removed or copied.
+        //
+        // Safety: We performed a protected load of the pointer using a verified guard with
+        // `Acquire` and ensured that it is non-null, meaning it is valid for reads as long
+        // as we hold the guard.
+        if current.ptr.is_null()
+            || current.tag() & Entry::COPYING != 0
+            || unsafe { (*current.ptr).key != key }
+        {
+            return self.insert_with_hash(hash, key, value, true, guard);
+        }
 
-            // Return the value we inserted.
-            Compute::Inserted(_, value) => value,
+        let new_entry = self.alloc_entry(Entry {
+            key,
+            value,
+            link: self.collector.link(),
+        });
+
+        // Safety:
+        // - The slot index is in-bounds for its table.
+        // - `current` is a valid non-null entry that we found in the map.
+        // - `new_entry` was allocated above and never shared.
+        match unsafe { self.update_at(slot.i, current, new_entry, table, guard) } {
+            UpdateStatus::Replaced(entry) => {
+                // Safety: `entry` is a valid non-null entry that we found in the map
+                // before replacing it.
+                InsertResult::Replaced(unsafe { &(*entry.ptr).value })
+            }
 
-            _ => unreachable!(),
+            // Otherwise, retry by probing.
+            UpdateStatus::Found(_) => {
+                // Safety: We allocated this box above and it was not inserted into the table.
+                let new_entry = unsafe { Box::from_raw(new_entry) };
+                self.insert_with_hash(hash, new_entry.key, new_entry.value, true, guard)
+            }
         }
     }
 
-    /// Update an entry with a CAS function.
-    ///
-    /// Note that `compute` closure is guaranteed to be called for a `None` input only once, allowing the
-    /// insertion of values that cannot be cloned or reconstructed.
+    /// Removes the entry at the given slot, or probes for the key if the slot is stale,
+    /// returning the removed entry.
     #[inline]
-    pub fn compute<'g, F, T>(
+    pub fn remove_at_slot<'g>(
         &self,
-        key: K,
-        compute: F,
+        slot: Slot<K, V>,
+        hash: u64,
+        key: &K,
+        guard: &'g impl VerifiedGuard,
+    ) -> Option<(&'g K, &'g V)> {
+        let table = slot.table;
+
+        // Safety: The slot index is in-bounds for its table, which is protected by the guard.
+        let current = guard
+            .protect(unsafe { table.entry(slot.i) }, Ordering::Acquire)
+            .unpack();
+
+        // Try to remove the entry directly, as long as it has not been removed or copied.
+        //
+        // Safety: We performed a protected load of the pointer using a verified guard with
+        // `Acquire` and ensured that it is non-null, meaning it is valid for reads as long
+        // as we hold the guard.
+        if !current.ptr.is_null()
+            && current.tag() & Entry::COPYING == 0
+            && unsafe { (*current.ptr).key == *key }
+        {
+            // Safety:
+            // - The slot index is in-bounds for its table.
+            // - `current` is a valid non-null entry that we found in the map.
+            let status = unsafe { self.update_at(slot.i, current, Entry::TOMBSTONE, table, guard) };
+
+            if let UpdateStatus::Replaced(entry) = status {
+                // Mark the entry as a tombstone, see `remove_if_with_hash` for details.
+                //
+                // Safety: The slot index is in-bounds for its table.
+                unsafe { table.meta(slot.i).store(meta::TOMBSTONE, Ordering::Release) };
+
+                // Decrement the table length.
+                let count = self.count.get(guard.thread_id());
+                count.fetch_sub(1, Ordering::Relaxed);
+
+                // Compact the table if we removed enough entries.
+                if self.record_tombstone(&table) {
+                    self.compact(guard);
+                }
+
+                // Safety: `entry` is a valid non-null entry that we found in the map
+                // before replacing it.
+                let entry_ref = unsafe { &(*entry.ptr) };
+                return Some((&entry_ref.key, &entry_ref.value));
+            }
+        }
+
+        self.remove_with_hash(hash, key, guard)
+    }
+
+    /// Update an entry with a CAS function, using the given pending entry allocation.
+    #[inline]
+    fn compute_pending<'g, F, T>(
+        &self,
+        hash: u64,
+        mut entry: PendingEntry<K, V>,
+        state: &mut ComputeState<F, K, V, T>,
         guard: &'g impl VerifiedGuard,
     ) -> Compute<'g, K, V, T>
     where
         F: FnMut(Option<(&'g K, &'g V)>) -> Operation<V, T>,
     {
-        // Lazy initialize the entry allocation.
-        let mut entry = LazyEntry::Uninit(key);
-
         // Perform the update.
         //
-        // Safety: We just allocated the entry above.
-        let result = unsafe { self.compute_with(&mut entry, ComputeState::new(compute), guard) };
+        // Safety: The entry is either uninitialized or was allocated and never shared.
+        let result = unsafe { self.compute_with(hash, &mut entry.0, state, guard) };
 
-        // Deallocate the entry if it was not inserted.
-        if matches!(result, Compute::Removed(..) | Compute::Aborted(_)) {
-            if let LazyEntry::Init(entry) = entry {
-                // Safety: The entry was allocated but not inserted into the map.
-                let _ = unsafe { Box::from_raw(entry) };
-            }
+        // The entry was inserted into the map, so it must not be deallocated.
+        if matches!(result, Compute::Inserted(..) | Compute::Updated { .. }) {
+            mem::forget(entry);
         }
 
         result
@@ -1565,8 +3210,9 @@ where
     #[inline]
     unsafe fn compute_with<'g, F, T>(
         &self,
+        hash: u64,
         new_entry: &mut LazyEntry<K, V>,
-        mut state: ComputeState<F, K, V, T>,
+        state: &mut ComputeState<F, K, V, T>,
         guard: &'g impl VerifiedGuard,
     ) -> Compute<'g, K, V, T>
     where
@@ -1581,7 +3227,8 @@ where
             //
             // Safety: Insert transitions are always sound.
             match unsafe { state.next(None) } {
-                op @ Operation::Insert(_) => state.restore(None, op),
+                // Cache the value to insert once the table is initialized.
+                Operation::Insert(value) => state.insert = Some(value),
                 Operation::Remove => panic!("Cannot remove `None` entry."),
                 Operation::Abort(value) => return Compute::Aborted(value),
             }
@@ -1590,12 +3237,12 @@ where
             table = self.init(None);
         }
 
-        let (h1, h2) = self.hash(new_entry.key());
+        let (h1, h2) = (meta::h1(hash), meta::h2(hash));
         let mut help_copy = false;
 
         loop {
             // Initialize the probe state.
-            let mut probe = Probe::start(h1, table.mask);
+            let mut probe = Probe::start(h1, table.mask, self.probe);
 
             // Probe until we reach the limit.
             let copying = 'probe: loop {
@@ -1619,7 +3266,7 @@ where
                         Operation::Abort(value) => return Compute::Aborted(value),
                     };
 
-                    let new_entry = new_entry.init(&self.collector);
+                    let new_entry = new_entry.init(&self.collector, self.pool);
 
                     // Safety: `new_entry` was just allocated above and is valid for writes.
                     unsafe { (*new_entry).value = MaybeUninit::new(value) }
@@ -1637,6 +3284,7 @@ where
 
                             // Safety: `new_entry` was initialized above.
                             let new_ref = unsafe { &*new_entry.cast::<Entry<K, V>>() };
+                            state.slot = Some(Slot { table, i: probe.i });
                             return Compute::Inserted(&new_ref.key, &new_ref.value);
                         }
 
@@ -1714,11 +3362,14 @@ where
                     // Safety: `entry` is valid for reads.
                     let failure = match unsafe { state.next(Some(entry.ptr)) } {
                         // The operation was aborted.
-                        Operation::Abort(value) => return Compute::Aborted(value),
+                        Operation::Abort(value) => {
+                            state.slot = Some(Slot { table, i: probe.i });
+                            return Compute::Aborted(value);
+                        }
 
                         // Update the value.
                         Operation::Insert(value) => {
-                            let new_entry = new_entry.init(&self.collector);
+                            let new_entry = new_entry.init(&self.collector, self.pool);
 
                             // Safety: `new_entry` was just allocated above and is valid for writes.
                             unsafe { (*new_entry).value = MaybeUninit::new(value) }
@@ -1743,6 +3394,7 @@ where
                                     // Safety: `new_entry` was initialized above.
                                     let new_ref = unsafe { &*new_entry.cast::<Entry<K, V>>() };
 
+                                    state.slot = Some(Slot { table, i: probe.i });
                                     return Compute::Updated {
                                         old: (&entry_ref.key, &entry_ref.value),
                                         new: (&new_ref.key, &new_ref.value),
@@ -1792,6 +3444,11 @@ where
                                     let count = self.count.get(guard.thread_id());
                                     count.fetch_sub(1, Ordering::Relaxed);
 
+                                    // Compact the table if we removed enough entries.
+                                    if self.record_tombstone(&table) {
+                                        self.compact(guard);
+                                    }
+
                                     // Safety: `entry` is a valid non-null entry that we found in the map
                                     // before replacing it.
                                     let entry_ref = unsafe { &(*entry.ptr) };
@@ -1878,7 +3535,11 @@ where
         const CAPACITY: usize = 32;
 
         // Allocate the table and mark it as the root.
-        let mut new = Table::alloc(capacity.unwrap_or(CAPACITY), &self.collector);
+        let mut new = Table::alloc(
+            capacity.unwrap_or(CAPACITY),
+            self.max_probe,
+            &self.collector,
+        );
         *new.state_mut().status.get_mut() = State::PROMOTED;
 
         // Race to write the initial table.
@@ -1957,6 +3618,9 @@ where
             return next;
         }
 
+        // Whether the current table exceeded the capped probe limit, rather than the default.
+        let capped = table.limit < probe::limit(table.len());
+
         let next_capacity = match cfg!(papaya_stress) {
             // Never grow the table to stress the incremental resizing algorithm.
             true => table.len(),
@@ -1967,6 +3631,11 @@ where
             // a probabilistic counter to detect high-deletion workloads.
             false if self.len() >= (table.len() >> 1) => table.len() << 1,
 
+            // Double the table capacity if the capped probe limit was exceeded, as a table of
+            // the same capacity is likely to exceed the probe limit again. Once the table grows
+            // too sparse, the cap is lifted instead and the capacity is kept the same.
+            false if capped && self.max_probe_for(table.len() << 1).is_some() => table.len() << 1,
+
             // Otherwise keep the capacity the same.
             //
             // This can occur due to poor hash distribution or frequent cycling of
@@ -1981,14 +3650,50 @@ where
             "`HashMap` exceeded maximum capacity"
         );
 
+        // Lift the probe length cap if the table is not growing, as a table of the same
+        // capacity is likely to exceed the capped probe limit again.
+        let max_probe = match capacity {
+            Some(capacity) => self.max_probe_for(capacity),
+            None if next_capacity > table.len() => self.max_probe_for(next_capacity),
+            None => None,
+        };
+
+        // Record the resize.
+        self.record_resize(table.len(), next_capacity);
+
         // Allocate the new table while holding the lock.
-        let next = Table::alloc(next_capacity, &self.collector);
+        let next = Table::alloc(next_capacity, max_probe, &self.collector);
         state.next.store(next.raw, Ordering::Release);
         drop(_allocating);
 
         next
     }
 
+    /// Record a resize from a table of length `old` to a table of length `new`.
+    #[inline]
+    fn record_resize(&self, old: usize, new: usize) {
+        if new > old {
+            self.grows.fetch_add(1, Ordering::Relaxed);
+            let _ = GREW.try_with(|grew| grew.set(true));
+        } else if new < old {
+            self.shrinks.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the maximum probe length for a new table of the given length, if capped.
+    ///
+    /// Growing the table does not separate keys with clustered or identical hashes, so
+    /// enforcing the cap can grow the table without bound. The cap is lifted once the
+    /// table would be less than 1/16th full, falling back to the default probe limit.
+    #[inline]
+    fn max_probe_for(&self, len: usize) -> Option<usize> {
+        // The minimum load factor, as a shift, of a table with a capped probe limit.
+        const MIN_LOAD_SHIFT: usize = 4;
+
+        self.max_probe
+            .filter(|_| self.len() >= (len >> MIN_LOAD_SHIFT))
+    }
+
     /// Help along with an existing resize operation, returning the new root table.
     ///
     /// If `copy_all` is `false` in incremental resize mode, this returns the current reference's next
@@ -2151,9 +3856,12 @@ where
         // Mark the entry as copying.
         //
         // Safety: The caller guarantees that the index is in-bounds.
-        let entry = unsafe { table.entry(i) }
-            .fetch_or(Entry::COPYING, Ordering::AcqRel)
-            .unpack();
+        let entry = AtomicPtrFetchOps::fetch_or(
+            unsafe { table.entry(i) },
+            Entry::COPYING,
+            Ordering::AcqRel,
+        )
+        .unpack();
 
         // The entry is a tombstone.
         if entry.raw == Entry::TOMBSTONE {
@@ -2293,7 +4001,7 @@ where
         let entry = unsafe { table.entry(i) };
 
         // Mark the entry as copying.
-        let found = entry.fetch_or(Entry::COPYING, Ordering::AcqRel).unpack();
+        let found = AtomicPtrFetchOps::fetch_or(entry, Entry::COPYING, Ordering::AcqRel).unpack();
 
         // The entry is a tombstone.
         if found.raw == Entry::TOMBSTONE {
@@ -2324,9 +4032,8 @@ where
         }
 
         // Mark the entry as copied.
-        let copied = found
-            .raw
-            .map_addr(|addr| addr | Entry::COPYING | Entry::COPIED);
+        let copied =
+            StrictProvenance::map_addr(found.raw, |addr| addr | Entry::COPYING | Entry::COPIED);
 
         // Note that we already wrote the COPYING bit, so no one is writing to the old
         // entry except us.
@@ -2359,7 +4066,7 @@ where
 
         loop {
             // Initialize the probe state.
-            let mut probe = Probe::start(h1, table.mask);
+            let mut probe = Probe::start(h1, table.mask, self.probe);
 
             // Probe until we reach the limit.
             while probe.len <= table.limit {
@@ -2543,7 +4250,9 @@ where
 
         // Park until the copy completes.
         let parker = &table.state().parker;
-        parker.park(entry, |entry| entry.addr() & Entry::COPIED == 0);
+        parker.park(entry, |entry| {
+            StrictProvenance::addr(entry) & Entry::COPIED == 0
+        });
     }
 
     /// Retire an entry that was removed from the current table, but may still be reachable from
@@ -2564,14 +4273,14 @@ where
             // Safety: In blocking resize mode, we only ever write to the root table, so the entry
             // is inaccessible from all tables.
             ResizeMode::Blocking => unsafe {
-                guard.defer_retire(entry.ptr, Entry::reclaim::<K, V>);
+                guard.defer_retire(entry.ptr, self.reclaim_entry());
             },
             // In incremental resize mode, the entry may be accessible in previous tables.
             ResizeMode::Incremental(_) => {
                 if entry.tag() & Entry::BORROWED == 0 {
                     // Safety: If the entry is not borrowed, meaning it is not in any previous tables,
                     // it is inaccessible even if the current table is not root. Thus we can safely retire.
-                    unsafe { guard.defer_retire(entry.ptr, Entry::reclaim::<K, V>) };
+                    unsafe { guard.defer_retire(entry.ptr, self.reclaim_entry()) };
                     return;
                 }
 
@@ -2583,7 +4292,7 @@ where
                     if table.raw == root.raw {
                         // Safety: The root table is our table or a table that succeeds ours.
                         // Thus any previous tables are unreachable from the root, so we can safely retire.
-                        unsafe { guard.defer_retire(entry.ptr, Entry::reclaim::<K, V>) };
+                        unsafe { guard.defer_retire(entry.ptr, self.reclaim_entry()) };
                         return;
                     }
 
@@ -2616,18 +4325,28 @@ where
 // An iterator over the keys and values of this table.
 pub struct Iter<'g, K, V, G> {
     i: usize,
+    // An estimate of the number of entries left to yield, based on the length
+    // of the map when the iterator was created.
+    remaining: usize,
     table: Table<Entry<K, V>>,
     guard: &'g G,
 }
 
-impl<'g, K: 'g, V: 'g, G> Iterator for Iter<'g, K, V, G>
+impl<'g, K: 'g, V: 'g, G> Iter<'g, K, V, G>
 where
     G: VerifiedGuard,
 {
-    type Item = (&'g K, &'g V);
+    // Returns the next entry in the table, along with its slot index.
+    #[inline]
+    pub fn next_indexed(&mut self) -> Option<(usize, &'g K, &'g V)> {
+        let (i, entry, _) = self.next_entry()?;
+        Some((i, &entry.key, &entry.value))
+    }
 
+    // Returns the next entry in the table, along with its slot index and whether it has
+    // been migrated to a newer table.
     #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
+    fn next_entry(&mut self) -> Option<(usize, &'g Entry<K, V>, bool)> {
         // The table has not yet been allocated.
         if self.table.raw.is_null() {
             return None;
@@ -2668,9 +4387,36 @@ where
             // as we hold the guard.
             let entry_ref = unsafe { &(*entry.ptr) };
 
+            let i = self.i;
             self.i += 1;
-            return Some((&entry_ref.key, &entry_ref.value));
+            self.remaining = self.remaining.saturating_sub(1);
+            return Some((i, entry_ref, entry.tag() & Entry::COPYING != 0));
+        }
+    }
+}
+
+impl<'g, K: 'g, V: 'g, G> Iterator for Iter<'g, K, V, G>
+where
+    G: VerifiedGuard,
+{
+    type Item = (&'g K, &'g V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, entry, _) = self.next_entry()?;
+        Some((&entry.key, &entry.value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // The table has not yet been allocated.
+        if self.table.raw.is_null() {
+            return (0, Some(0));
         }
+
+        // Every remaining entry must be in one of the unvisited slots.
+        let slots = self.table.len().saturating_sub(self.i);
+        (self.remaining.min(slots), Some(slots))
     }
 }
 
@@ -2697,17 +4443,229 @@ where
 {
 }
 
+// An iterator over the keys and values of this table that follows entries migrated
+// to newer tables.
+pub struct LiveIter<'g, K, V, S, G> {
+    iter: Iter<'g, K, V, G>,
+    map: &'g HashMap<K, V, S>,
+}
+
+impl<'g, K: 'g, V: 'g, S, G> Iterator for LiveIter<'g, K, V, S, G>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    G: VerifiedGuard,
+{
+    type Item = (&'g K, &'g V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (_, entry, migrated) = self.iter.next_entry()?;
+
+            if !migrated {
+                return Some((&entry.key, &entry.value));
+            }
+
+            // The entry was copied to a newer table after iteration started, and any later
+            // writes went there instead. Read the latest value from the current table, skipping
+            // the key if it has since been removed.
+            if let Some(entry) = self.map.get(&entry.key, self.iter.guard) {
+                return Some(entry);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Migrated keys may have been removed from the newer table.
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<K, V, S, G> Clone for LiveIter<'_, K, V, S, G> {
+    #[inline]
+    fn clone(&self) -> Self {
+        LiveIter {
+            iter: self.iter.clone(),
+            map: self.map,
+        }
+    }
+}
+
 impl<K, V, G> Clone for Iter<'_, K, V, G> {
     #[inline]
     fn clone(&self) -> Self {
         Iter {
             i: self.i,
+            remaining: self.remaining,
             table: self.table,
             guard: self.guard,
         }
     }
 }
 
+impl<K, V, S> HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns an iterator that moves entries out of the map.
+    #[inline]
+    pub fn into_entries(self) -> IntoEntries<K, V, S> {
+        // Complete any in-progress resizes to ensure all entries are in the root table.
+        let table = {
+            let guard = self.guard();
+            self.finalize_resize(&guard);
+            self.root(&guard)
+        };
+
+        IntoEntries {
+            i: 0,
+            remaining: self.len(),
+            table,
+            map: self,
+        }
+    }
+}
+
+/// An iterator that moves entries out of a map.
+///
+/// Entries are removed from the table as they are yielded, and any remaining entries
+/// are dropped along with the map.
+pub struct IntoEntries<K, V, S> {
+    i: usize,
+    // The number of entries left in the table. This is exact as we have unique
+    // access to the map.
+    remaining: usize,
+    table: Table<Entry<K, V>>,
+    map: HashMap<K, V, S>,
+}
+
+impl<K, V, S> IntoEntries<K, V, S> {
+    /// Moves the next key and value out of the map.
+    #[inline]
+    pub fn take(&mut self) -> Option<(K, V)> {
+        let entry = next_owned_entry(self.table, &mut self.i, &mut self.remaining)?;
+
+        // Safety: We removed the entry from the table, taking ownership of it.
+        Some(unsafe { take_owned_entry(entry) })
+    }
+
+    /// Moves the next key out of the map, dropping the value in place.
+    #[inline]
+    pub fn take_key(&mut self) -> Option<K> {
+        let entry = next_owned_entry(self.table, &mut self.i, &mut self.remaining)?;
+
+        // Safety: The value is initialized, and we own the entry allocation.
+        unsafe { ptr::drop_in_place(&mut (*entry).value) };
+
+        // Safety: We own the entry allocation, which was allocated by the global allocator
+        // either through `Box` or the pool. `MaybeUninit` has the same layout as the inner types.
+        let entry = unsafe { Box::from_raw(entry.cast::<Entry<MaybeUninit<K>, MaybeUninit<V>>>()) };
+
+        // Safety: The key is initialized, and is moved out of the entry before it is deallocated.
+        unsafe { Some(entry.key.assume_init_read()) }
+    }
+
+    /// Returns an iterator over the remaining entries.
+    #[inline]
+    pub fn iter<'g, G>(&self, guard: &'g G) -> Iter<'g, K, V, G>
+    where
+        G: VerifiedGuard,
+    {
+        Iter {
+            i: self.i,
+            remaining: self.remaining,
+            table: self.table,
+            guard,
+        }
+    }
+
+    /// Returns the number of entries left in the table.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns a guard for the underlying map.
+    #[inline]
+    pub fn guard(&self) -> MapGuard<LocalGuard<'_>> {
+        self.map.guard()
+    }
+}
+
+// Safety: The iterator owns the map and yields owned keys and values.
+unsafe impl<K, V, S> Send for IntoEntries<K, V, S>
+where
+    K: Send,
+    V: Send,
+    S: Send,
+{
+}
+
+// Safety: The iterator only allows shared access to the remaining entries through
+// a shared reference.
+unsafe impl<K, V, S> Sync for IntoEntries<K, V, S>
+where
+    K: Sync,
+    V: Sync,
+    S: Sync,
+{
+}
+
+// Removes the next entry from a table that is not accessible to any other threads,
+// returning the owned entry pointer.
+#[inline]
+fn next_owned_entry<K, V>(
+    table: Table<Entry<K, V>>,
+    i: &mut usize,
+    remaining: &mut usize,
+) -> Option<*mut Entry<K, V>> {
+    // The table has not yet been allocated.
+    if table.raw.is_null() {
+        return None;
+    }
+
+    while *i < table.len() {
+        // Safety: We verified that `i` is in-bounds above.
+        let slot = unsafe { table.entry(*i) };
+        *i += 1;
+
+        // We have unique access to the table, and all resizes were completed.
+        let entry = slot.load(Ordering::Relaxed).unpack();
+
+        // The entry was deleted.
+        if entry.ptr.is_null() {
+            continue;
+        }
+
+        // Remove the entry from the table to take ownership of it, ensuring it is
+        // not dropped along with the table.
+        slot.store(ptr::null_mut(), Ordering::Relaxed);
+        *remaining = remaining.saturating_sub(1);
+        return Some(entry.ptr);
+    }
+
+    None
+}
+
+// Moves the key and value out of an owned entry, deallocating it.
+//
+// # Safety
+//
+// The entry must be owned by the caller and not accessible to any other threads.
+#[inline]
+unsafe fn take_owned_entry<K, V>(entry: *mut Entry<K, V>) -> (K, V) {
+    // Safety: We own the entry allocation, which was allocated by the global allocator
+    // either through `Box` or the pool. `MaybeUninit` has the same layout as the inner types.
+    let entry = unsafe { Box::from_raw(entry.cast::<Entry<MaybeUninit<K>, MaybeUninit<V>>>()) };
+
+    // Safety: The key and value are initialized, and are moved out of the entry
+    // before it is deallocated.
+    unsafe { (entry.key.assume_init_read(), entry.value.assume_init_read()) }
+}
+
 impl<K, V, S> Drop for HashMap<K, V, S> {
     fn drop(&mut self) {
         let mut raw = *self.table.get_mut();
@@ -2743,6 +4701,20 @@ impl<K, V, S> Drop for HashMap<K, V, S> {
     }
 }
 
+// Allocates an entry, optionally reusing an allocation from the thread-local pool.
+#[inline]
+fn alloc_entry<T>(pool: bool, entry: T) -> *mut T {
+    if !pool {
+        return Box::into_raw(Box::new(entry));
+    }
+
+    let ptr = pool::alloc::<T>();
+
+    // Safety: The pool returns a valid allocation for a value of type `T`.
+    unsafe { ptr.write(entry) };
+    ptr
+}
+
 // Drop all entries in this table.
 //
 // # Safety
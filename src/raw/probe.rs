@@ -1,19 +1,23 @@
-// A quadratic probe sequence.
-#[derive(Default)]
+use crate::map::ProbeStrategy;
+
+// A probe sequence, either quadratic or linear.
 pub struct Probe {
     // The current index in the probe sequence.
     pub i: usize,
     // The current length of the probe sequence.
     pub len: usize,
+    // Whether this is a linear probe sequence.
+    linear: bool,
 }
 
 impl Probe {
     // Initialize the probe sequence.
     #[inline]
-    pub fn start(hash: usize, mask: usize) -> Probe {
+    pub fn start(hash: usize, mask: usize, strategy: ProbeStrategy) -> Probe {
         Probe {
             i: hash & mask,
             len: 0,
+            linear: strategy == ProbeStrategy::Linear,
         }
     }
 
@@ -21,7 +25,11 @@ impl Probe {
     #[inline]
     pub fn next(&mut self, mask: usize) {
         self.len += 1;
-        self.i = (self.i + self.len) & mask;
+
+        // Quadratic probing visits offsets following the triangular numbers, which
+        // is guaranteed to visit every slot for power-of-two table sizes.
+        let stride = if self.linear { 1 } else { self.len };
+        self.i = (self.i + stride) & mask;
     }
 }
 
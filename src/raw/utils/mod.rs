@@ -1,6 +1,8 @@
 mod parker;
 pub use parker::Parker;
 
+pub mod pool;
+
 use std::ops::Deref;
 use std::ptr::NonNull;
 use std::sync::atomic::{AtomicIsize, AtomicPtr, Ordering};
@@ -30,7 +32,7 @@ unsafe impl<T> StrictProvenance<T> for *mut T {
 
     #[inline(always)]
     fn map_addr(self, f: impl FnOnce(usize) -> usize) -> Self {
-        f(self.addr()) as Self
+        f(StrictProvenance::addr(self)) as Self
     }
 
     #[inline(always)]
@@ -40,7 +42,7 @@ unsafe impl<T> StrictProvenance<T> for *mut T {
     {
         Tagged {
             raw: self,
-            ptr: self.map_addr(|addr| addr & T::MASK),
+            ptr: StrictProvenance::map_addr(self, |addr| addr & T::MASK),
         }
     }
 }
@@ -70,14 +72,14 @@ where
     // Returns the tag portion of this pointer.
     #[inline]
     pub fn tag(self) -> usize {
-        self.raw.addr() & !T::MASK
+        StrictProvenance::addr(self.raw) & !T::MASK
     }
 
     // Maps the tag of this pointer.
     #[inline]
     pub fn map_tag(self, f: impl FnOnce(usize) -> usize) -> Self {
         Tagged {
-            raw: self.raw.map_addr(f),
+            raw: StrictProvenance::map_addr(self.raw, f),
             ptr: self.ptr,
         }
     }
@@ -177,17 +179,29 @@ pub struct Counter(Box<[CachePadded<AtomicIsize>]>);
 
 impl Default for Counter {
     fn default() -> Counter {
-        let num_cpus = std::thread::available_parallelism()
-            .map(usize::from)
-            .unwrap_or(1);
-        let shards = (0..num_cpus.next_power_of_two())
+        Counter::new(Counter::default_shards())
+    }
+}
+
+impl Counter {
+    // The maximum number of shards in a counter.
+    pub const MAX_SHARDS: usize = 1 << 16;
+
+    // Create a counter with at least the given number of shards, up to `MAX_SHARDS`.
+    pub fn new(shards: usize) -> Counter {
+        let shards = (0..shards.clamp(1, Counter::MAX_SHARDS).next_power_of_two())
             .map(|_| Default::default())
             .collect();
         Counter(shards)
     }
-}
 
-impl Counter {
+    // Returns the default number of shards, based on the available parallelism.
+    pub fn default_shards() -> usize {
+        std::thread::available_parallelism()
+            .map(usize::from)
+            .unwrap_or(1)
+    }
+
     // Return the shard for the given thread ID.
     #[inline]
     pub fn get(&self, thread: usize) -> &AtomicIsize {
@@ -0,0 +1,117 @@
+use std::alloc::{self, Layout};
+use std::cell::RefCell;
+use std::ptr::NonNull;
+
+// A thread-local pool of entry allocations.
+//
+// Entries are reclaimed through a function pointer without access to the map, so
+// free allocations are cached per-thread and keyed by their layout. Allocations may
+// migrate between threads as entries are reclaimed on a different thread than they
+// were allocated on, so the cache is bounded to avoid holding on to memory indefinitely.
+
+// The maximum number of allocations cached per layout on a given thread.
+const MAX_CACHED: usize = 1024;
+
+// The maximum number of distinct layouts cached on a given thread.
+const MAX_LAYOUTS: usize = 8;
+
+thread_local! {
+    // A thread-local cache of free allocations.
+    static CACHE: RefCell<Vec<FreeList>> = const { RefCell::new(Vec::new()) };
+}
+
+// A list of free allocations with a given layout.
+struct FreeList {
+    layout: Layout,
+    free: Vec<NonNull<u8>>,
+}
+
+impl Drop for FreeList {
+    fn drop(&mut self) {
+        for ptr in self.free.drain(..) {
+            // Safety: All allocations in the list were allocated by the global
+            // allocator with the given layout.
+            unsafe { alloc::dealloc(ptr.as_ptr(), self.layout) }
+        }
+    }
+}
+
+// Allocate uninitialized memory for a value of type `T`, reusing a cached
+// allocation if possible.
+//
+// The returned pointer is allocated by the global allocator with the layout of `T`,
+// and so can be freed with `Box::from_raw` once initialized.
+#[inline]
+pub fn alloc<T>() -> *mut T {
+    let layout = Layout::new::<T>();
+    debug_assert!(layout.size() != 0);
+
+    let cached = CACHE
+        .try_with(|cache| {
+            let mut cache = cache.try_borrow_mut().ok()?;
+            let list = cache.iter_mut().find(|list| list.layout == layout)?;
+            list.free.pop()
+        })
+        .ok()
+        .flatten();
+
+    if let Some(ptr) = cached {
+        return ptr.as_ptr().cast();
+    }
+
+    // Safety: `T` is not zero-sized.
+    let ptr = unsafe { alloc::alloc(layout) };
+    if ptr.is_null() {
+        alloc::handle_alloc_error(layout);
+    }
+
+    ptr.cast()
+}
+
+// Release an allocation for a value of type `T`, caching it for reuse if possible.
+//
+// # Safety
+//
+// The pointer must have been allocated by the global allocator with the layout of `T`,
+// and any value it contains must have already been dropped.
+#[inline]
+pub unsafe fn dealloc<T>(ptr: *mut T) {
+    let layout = Layout::new::<T>();
+
+    // Safety: The caller guarantees the pointer is a valid allocation.
+    let ptr = unsafe { NonNull::new_unchecked(ptr.cast::<u8>()) };
+
+    let cached = CACHE
+        .try_with(|cache| {
+            let Ok(mut cache) = cache.try_borrow_mut() else {
+                return false;
+            };
+
+            let list = match cache.iter().position(|list| list.layout == layout) {
+                Some(i) => &mut cache[i],
+                None if cache.len() < MAX_LAYOUTS => {
+                    cache.push(FreeList {
+                        layout,
+                        free: Vec::with_capacity(MAX_CACHED),
+                    });
+
+                    cache.last_mut().unwrap()
+                }
+                None => return false,
+            };
+
+            if list.free.len() >= MAX_CACHED {
+                return false;
+            }
+
+            list.free.push(ptr);
+            true
+        })
+        .unwrap_or(false);
+
+    if !cached {
+        // Safety: The caller guarantees the pointer was allocated by the global
+        // allocator with the layout of `T`.
+        unsafe { alloc::dealloc(ptr.as_ptr(), layout) }
+    }
+}
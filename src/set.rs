@@ -3,10 +3,11 @@ use crate::raw::{self, InsertResult};
 use crate::Equivalent;
 use seize::{Collector, Guard, LocalGuard, OwnedGuard};
 
-use crate::map::ResizeMode;
+use crate::map::{ProbeStrategy, ResizeMode, SeededState};
 use std::collections::hash_map::RandomState;
 use std::fmt;
 use std::hash::{BuildHasher, Hash};
+use std::iter::Chain;
 use std::marker::PhantomData;
 
 /// A concurrent hash set.
@@ -50,6 +51,11 @@ pub struct HashSetBuilder<K, S = RandomState> {
     capacity: usize,
     collector: Collector,
     resize_mode: ResizeMode,
+    entry_pool: bool,
+    probe_strategy: ProbeStrategy,
+    auto_compact: Option<f64>,
+    concurrency: Option<usize>,
+    max_probe: Option<usize>,
     _kv: PhantomData<K>,
 }
 
@@ -69,6 +75,11 @@ impl<K> HashSetBuilder<K> {
             capacity: self.capacity,
             collector: self.collector,
             resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
             _kv: PhantomData,
         }
     }
@@ -86,6 +97,11 @@ impl<K, S> HashSetBuilder<K, S> {
             hasher: self.hasher,
             collector: self.collector,
             resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
             _kv: PhantomData,
         }
     }
@@ -97,6 +113,11 @@ impl<K, S> HashSetBuilder<K, S> {
             hasher: self.hasher,
             capacity: self.capacity,
             collector: self.collector,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
             _kv: PhantomData,
         }
     }
@@ -113,6 +134,117 @@ impl<K, S> HashSetBuilder<K, S> {
             hasher: self.hasher,
             capacity: self.capacity,
             resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
+            _kv: PhantomData,
+        }
+    }
+
+    /// Set the number of retired entries that are batched before reclamation is attempted.
+    ///
+    /// See [`HashMapBuilder::reclaim_batch`](crate::HashMapBuilder::reclaim_batch) for details.
+    pub fn reclaim_batch(self, batch_size: usize) -> Self {
+        HashSetBuilder {
+            collector: self.collector.batch_size(batch_size),
+            hasher: self.hasher,
+            capacity: self.capacity,
+            resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
+            _kv: PhantomData,
+        }
+    }
+
+    /// Recycle entry allocations through a thread-local pool.
+    ///
+    /// See [`HashMapBuilder::entry_pool`](crate::HashMapBuilder::entry_pool) for details.
+    pub fn entry_pool(self, entry_pool: bool) -> Self {
+        HashSetBuilder {
+            entry_pool,
+            hasher: self.hasher,
+            capacity: self.capacity,
+            collector: self.collector,
+            resize_mode: self.resize_mode,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
+            _kv: PhantomData,
+        }
+    }
+
+    /// Set the probe sequence used by the set. See [`ProbeStrategy`] for details.
+    pub fn probe_strategy(self, probe_strategy: ProbeStrategy) -> Self {
+        HashSetBuilder {
+            probe_strategy,
+            hasher: self.hasher,
+            capacity: self.capacity,
+            collector: self.collector,
+            resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
+            _kv: PhantomData,
+        }
+    }
+
+    /// Automatically compact the set once removals leave behind enough tombstones.
+    ///
+    /// See [`HashMapBuilder::auto_compact_at`](crate::HashMapBuilder::auto_compact_at) for details.
+    pub fn auto_compact_at(self, ratio: f64) -> Self {
+        HashSetBuilder {
+            auto_compact: Some(ratio),
+            hasher: self.hasher,
+            capacity: self.capacity,
+            collector: self.collector,
+            resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            concurrency: self.concurrency,
+            max_probe: self.max_probe,
+            _kv: PhantomData,
+        }
+    }
+
+    /// Set the expected number of threads concurrently writing to the set.
+    ///
+    /// See [`HashMapBuilder::concurrency`](crate::HashMapBuilder::concurrency) for details.
+    pub fn concurrency(self, concurrency: usize) -> Self {
+        HashSetBuilder {
+            concurrency: Some(concurrency),
+            hasher: self.hasher,
+            capacity: self.capacity,
+            collector: self.collector,
+            resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            max_probe: self.max_probe,
+            _kv: PhantomData,
+        }
+    }
+
+    /// Set the maximum probe length of the set.
+    ///
+    /// See [`HashMapBuilder::max_probe_len`](crate::HashMapBuilder::max_probe_len) for details.
+    pub fn max_probe_len(self, max_probe_len: usize) -> Self {
+        HashSetBuilder {
+            max_probe: Some(max_probe_len),
+            hasher: self.hasher,
+            capacity: self.capacity,
+            collector: self.collector,
+            resize_mode: self.resize_mode,
+            entry_pool: self.entry_pool,
+            probe_strategy: self.probe_strategy,
+            auto_compact: self.auto_compact,
+            concurrency: self.concurrency,
             _kv: PhantomData,
         }
     }
@@ -120,7 +252,17 @@ impl<K, S> HashSetBuilder<K, S> {
     /// Construct a [`HashSet`] from the builder, using the configured options.
     pub fn build(self) -> HashSet<K, S> {
         HashSet {
-            raw: raw::HashMap::new(self.capacity, self.hasher, self.collector, self.resize_mode),
+            raw: raw::HashMap::new(
+                self.capacity,
+                self.hasher,
+                self.collector,
+                self.resize_mode,
+                self.entry_pool,
+                self.probe_strategy,
+                self.auto_compact,
+                self.concurrency,
+                self.max_probe,
+            ),
         }
     }
 }
@@ -131,6 +273,11 @@ impl<K, S> fmt::Debug for HashSetBuilder<K, S> {
             .field("capacity", &self.capacity)
             .field("collector", &self.collector)
             .field("resize_mode", &self.resize_mode)
+            .field("entry_pool", &self.entry_pool)
+            .field("probe_strategy", &self.probe_strategy)
+            .field("auto_compact", &self.auto_compact)
+            .field("concurrency", &self.concurrency)
+            .field("max_probe", &self.max_probe)
             .finish()
     }
 }
@@ -139,7 +286,10 @@ impl<K> HashSet<K> {
     /// Creates an empty `HashSet`.
     ///
     /// The hash map is initially created with a capacity of 0, so it will not allocate
-    /// until it is first inserted into.
+    /// a table until it is first inserted into.
+    ///
+    /// This function is not `const`. See [`HashMap::new`](crate::HashMap::new)
+    /// for how to lazily initialize a global set.
     ///
     /// # Examples
     ///
@@ -167,6 +317,29 @@ impl<K> HashSet<K> {
         HashSet::with_capacity_and_hasher(capacity, RandomState::new())
     }
 
+    /// Creates an empty `HashSet` that uses the given [`Collector`] for garbage collection.
+    ///
+    /// This is a shorthand for [`HashSet::builder`] with a custom
+    /// [`collector`](HashSetBuilder::collector). Note that a collector cannot be shared between
+    /// multiple sets, as dropping a set reclaims every object retired to its collector.
+    /// For a batch of short-lived sets, a collector configured once can instead be cloned for
+    /// each set, which creates an independent collector with the same configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    /// use seize::Collector;
+    ///
+    /// let template = Collector::new().batch_size(16);
+    /// let sets: Vec<HashSet<u64>> = (0..4)
+    ///     .map(|_| HashSet::with_collector(template.clone()))
+    ///     .collect();
+    /// ```
+    pub fn with_collector(collector: Collector) -> HashSet<K> {
+        HashSet::builder().collector(collector).build()
+    }
+
     /// Returns a builder for a `HashSet`.
     ///
     /// The builder can be used for more complex configuration, such as using
@@ -177,11 +350,32 @@ impl<K> HashSet<K> {
             hasher: RandomState::default(),
             collector: Collector::new(),
             resize_mode: ResizeMode::default(),
+            entry_pool: false,
+            probe_strategy: ProbeStrategy::default(),
+            auto_compact: None,
+            concurrency: None,
+            max_probe: None,
             _kv: PhantomData,
         }
     }
 }
 
+impl<K> HashSet<K, SeededState> {
+    /// Creates an empty `HashSet` that hashes keys with the given seed.
+    ///
+    /// See [`HashMap::with_seed`](crate::HashMap::with_seed) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    /// let set: HashSet<&str, _> = HashSet::with_seed(1);
+    /// ```
+    pub fn with_seed(seed: u64) -> HashSet<K, SeededState> {
+        HashSet::with_hasher(SeededState::new(seed))
+    }
+}
+
 impl<K, S> Default for HashSet<K, S>
 where
     S: Default,
@@ -249,6 +443,11 @@ impl<K, S> HashSet<K, S> {
                 hash_builder,
                 Collector::default(),
                 ResizeMode::default(),
+                false,
+                ProbeStrategy::default(),
+                None,
+                None,
+                None,
             ),
         }
     }
@@ -287,7 +486,7 @@ impl<K, S> HashSet<K, S> {
     /// See the [crate-level documentation](crate#usage) for details.
     #[inline]
     pub fn guard(&self) -> LocalGuard<'_> {
-        self.raw.collector().enter()
+        self.raw.enter()
     }
 
     /// Returns an owned guard for use with this set.
@@ -300,7 +499,27 @@ impl<K, S> HashSet<K, S> {
     /// See the [crate-level documentation](crate#usage) for details.
     #[inline]
     pub fn owned_guard(&self) -> OwnedGuard<'_> {
-        self.raw.collector().enter_owned()
+        self.raw.enter_owned()
+    }
+
+    /// Returns the number of guards created for this set over its lifetime.
+    ///
+    /// See [`HashMap::guard_count`](crate::HashMap::guard_count) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    ///
+    /// let set = HashSet::new();
+    /// set.pin().insert(1);
+    /// set.pin_owned().insert(2);
+    /// assert_eq!(set.guard_count(), (1, 1));
+    /// ```
+    #[inline]
+    #[cfg(feature = "stats")]
+    pub fn guard_count(&self) -> (u64, u64) {
+        self.raw.guard_count()
     }
 }
 
@@ -344,6 +563,46 @@ where
         self.len() == 0
     }
 
+    /// Returns the number of times the set has been resized over its lifetime.
+    ///
+    /// See [`HashMap::resize_count`](crate::HashMap::resize_count) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    ///
+    /// let set = HashSet::with_capacity(16);
+    /// assert_eq!(set.resize_count(), (0, 0));
+    ///
+    /// for i in 0..1000 {
+    ///     set.pin().insert(i);
+    /// }
+    ///
+    /// assert!(set.resize_count().0 > 0);
+    /// ```
+    #[inline]
+    pub fn resize_count(&self) -> (u64, u64) {
+        self.raw.resize_count()
+    }
+
+    /// Returns a reference to the set's [`BuildHasher`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let hasher = RandomState::new();
+    /// let set: HashSet<i32> = HashSet::with_hasher(hasher);
+    /// let hasher: &RandomState = set.hasher();
+    /// ```
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        &self.raw.hasher
+    }
+
     /// Returns `true` if the set contains a value for the specified key.
     ///
     /// The key may be any borrowed form of the set's key type, but
@@ -487,6 +746,70 @@ where
         self.raw.reserve(additional, self.raw.verify(guard))
     }
 
+    /// Completes any in-progress resizes of the `HashSet`.
+    ///
+    /// See [`HashMap::finalize_resize`](crate::HashMap::finalize_resize) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    ///
+    /// let set = HashSet::new();
+    /// for i in 0..1000 {
+    ///     set.pin().insert(i);
+    /// }
+    ///
+    /// set.pin().finalize_resize();
+    /// assert!(set.pin().contains(&500));
+    /// ```
+    #[inline]
+    pub fn finalize_resize(&self, guard: &impl Guard) {
+        self.raw.finalize_resize(self.raw.verify(guard))
+    }
+
+    /// Rehashes the set into a table of the same capacity, removing any tombstones.
+    ///
+    /// See [`HashMap::compact`](crate::HashMap::compact) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    ///
+    /// let set = HashSet::new();
+    /// for i in 0..1000 {
+    ///     set.pin().insert(i);
+    /// }
+    ///
+    /// for i in 0..500 {
+    ///     set.pin().remove(&i);
+    /// }
+    ///
+    /// set.pin().compact();
+    /// assert_eq!(set.len(), 500);
+    /// ```
+    #[inline]
+    pub fn compact(&self, guard: &impl Guard) {
+        self.raw.compact(self.raw.verify(guard))
+    }
+
+    /// Returns the number of tombstones in the set's table.
+    ///
+    /// See [`HashMap::tombstone_count`](crate::HashMap::tombstone_count) for details.
+    #[inline]
+    pub fn tombstone_count(&self, guard: &impl Guard) -> usize {
+        self.raw.tombstone_count(self.raw.verify(guard))
+    }
+
+    /// Returns the number of slots in the set's table.
+    ///
+    /// See [`HashMap::capacity`](crate::HashMap::capacity) for details.
+    #[inline]
+    pub fn capacity(&self, guard: &impl Guard) -> usize {
+        self.raw.capacity(self.raw.verify(guard))
+    }
+
     /// Clears the set, removing all values.
     ///
     /// Note that this method will block until any in-progress resizes are
@@ -509,6 +832,51 @@ where
         self.raw.clear(self.raw.verify(guard))
     }
 
+    /// Clears the set, replacing the table with an empty table that has room for
+    /// at least `capacity` values.
+    ///
+    /// See [`HashMap::clear_and_reserve`](crate::HashMap::clear_and_reserve) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    ///
+    /// let set = HashSet::new();
+    /// for i in 0..1000 {
+    ///     set.pin().insert(i);
+    /// }
+    ///
+    /// set.pin().clear_and_reserve(100);
+    /// assert!(set.pin().is_empty());
+    /// ```
+    #[inline]
+    pub fn clear_and_reserve(&self, capacity: usize, guard: &impl Guard) {
+        self.raw.clear_and_reserve(capacity, self.raw.verify(guard))
+    }
+
+    /// Replaces the set's hasher, rehashing all values into a new table.
+    ///
+    /// See [`HashMap::rehash_with`](crate::HashMap::rehash_with) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::{HashSet, SeededState};
+    ///
+    /// let mut set = HashSet::with_seed(1);
+    /// for i in 0..1000 {
+    ///     set.pin().insert(i);
+    /// }
+    ///
+    /// set.rehash_with(SeededState::new(2));
+    /// assert!(set.pin().contains(&7));
+    /// ```
+    #[inline]
+    pub fn rehash_with(&mut self, hasher: S) {
+        self.raw.rehash_with(hasher)
+    }
+
     /// Retains only the elements specified by the predicate.
     ///
     /// In other words, remove all values `v` for which `f(&v)` returns `false`.
@@ -710,11 +1078,28 @@ where
 ///
 /// This type is created with [`HashSet::pin`] and can be used to easily access a [`HashSet`]
 /// without explicitly managing a guard. See the [crate-level documentation](crate#usage) for details.
+///
+/// Every method on [`HashSet`] that accepts a guard is mirrored by a method on the pinned reference
+/// that uses the guard held by the reference instead. References returned by the pinned reference,
+/// including those produced by iterators such as [`HashSetRef::iter`], live as long as the pinned
+/// reference itself.
 pub struct HashSetRef<'set, K, S, G> {
     guard: MapGuard<G>,
     set: &'set HashSet<K, S>,
 }
 
+/// The pinned reference returned by [`HashSet::pin`].
+///
+/// This is a shorthand for a [`HashSetRef`] holding a [`LocalGuard`], useful for naming the
+/// pinned reference in function signatures.
+pub type HashSetPin<'set, K, S = RandomState> = HashSetRef<'set, K, S, LocalGuard<'set>>;
+
+/// The pinned reference returned by [`HashSet::pin_owned`].
+///
+/// This is a shorthand for a [`HashSetRef`] holding an [`OwnedGuard`], useful for naming the
+/// pinned reference in function signatures.
+pub type HashSetPinOwned<'set, K, S = RandomState> = HashSetRef<'set, K, S, OwnedGuard<'set>>;
+
 impl<'set, K, S, G> HashSetRef<'set, K, S, G>
 where
     K: Hash + Eq,
@@ -727,6 +1112,31 @@ where
         self.set
     }
 
+    /// Returns a reference to the set's [`BuildHasher`].
+    ///
+    /// See [`HashSet::hasher`] for details.
+    #[inline]
+    pub fn hasher(&self) -> &'set S {
+        self.set.hasher()
+    }
+
+    /// Returns the number of times the set has been resized over its lifetime.
+    ///
+    /// See [`HashSet::resize_count`] for details.
+    #[inline]
+    pub fn resize_count(&self) -> (u64, u64) {
+        self.set.resize_count()
+    }
+
+    /// Returns the number of guards created for this set over its lifetime.
+    ///
+    /// See [`HashSet::guard_count`] for details.
+    #[inline]
+    #[cfg(feature = "stats")]
+    pub fn guard_count(&self) -> (u64, u64) {
+        self.set.guard_count()
+    }
+
     /// Returns the number of entries in the set.
     ///
     /// See [`HashSet::len`] for details.
@@ -803,6 +1213,15 @@ where
         self.set.raw.clear(&self.guard)
     }
 
+    /// Clears the set, replacing the table with an empty table that has room for
+    /// at least `capacity` values.
+    ///
+    /// See [`HashSet::clear_and_reserve`] for details.
+    #[inline]
+    pub fn clear_and_reserve(&self, capacity: usize) {
+        self.set.raw.clear_and_reserve(capacity, &self.guard)
+    }
+
     /// Retains only the elements specified by the predicate.
     ///
     /// See [`HashSet::retain`] for details.
@@ -823,6 +1242,38 @@ where
         self.set.raw.reserve(additional, &self.guard)
     }
 
+    /// Completes any in-progress resizes of the set.
+    ///
+    /// See [`HashSet::finalize_resize`] for details.
+    #[inline]
+    pub fn finalize_resize(&self) {
+        self.set.raw.finalize_resize(&self.guard)
+    }
+
+    /// Rehashes the set into a table of the same capacity, removing any tombstones.
+    ///
+    /// See [`HashSet::compact`] for details.
+    #[inline]
+    pub fn compact(&self) {
+        self.set.raw.compact(&self.guard)
+    }
+
+    /// Returns the number of tombstones in the set's table.
+    ///
+    /// See [`HashSet::tombstone_count`] for details.
+    #[inline]
+    pub fn tombstone_count(&self) -> usize {
+        self.set.raw.tombstone_count(&self.guard)
+    }
+
+    /// Returns the number of slots in the set's table.
+    ///
+    /// See [`HashSet::capacity`] for details.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.set.raw.capacity(&self.guard)
+    }
+
     /// An iterator visiting all values in arbitrary order.
     /// The iterator element type is `(&K, &V)`.
     ///
@@ -833,6 +1284,257 @@ where
             raw: self.set.raw.iter(&self.guard),
         }
     }
+
+    /// Visits the values representing the difference, i.e., the values that are in `self`
+    /// but not in `other`.
+    ///
+    /// Set operations are not atomic. Each set is iterated or probed independently, and the
+    /// results may or may not reflect concurrent modifications to either set. These operations
+    /// are most useful on sets that are not being concurrently modified. See the
+    /// [consistency](crate#consistency) section for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    ///
+    /// let a = HashSet::from([1, 2, 3]);
+    /// let b = HashSet::from([4, 2, 3, 4]);
+    ///
+    /// let (a, b) = (a.pin(), b.pin());
+    /// let diff: Vec<_> = a.difference(&b).collect();
+    /// assert_eq!(diff, [&1]);
+    /// ```
+    #[inline]
+    pub fn difference<'a, G2>(
+        &'a self,
+        other: &'a HashSetRef<'_, K, S, G2>,
+    ) -> Difference<'a, K, S, G, G2>
+    where
+        G2: Guard,
+    {
+        Difference {
+            iter: self.iter(),
+            other,
+        }
+    }
+
+    /// Visits the values representing the symmetric difference, i.e., the values that are
+    /// in `self` or in `other` but not in both.
+    ///
+    /// See [`HashSetRef::difference`] for details about the consistency of set operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    ///
+    /// let a = HashSet::from([1, 2, 3]);
+    /// let b = HashSet::from([4, 2, 3, 4]);
+    ///
+    /// let (a, b) = (a.pin(), b.pin());
+    /// let mut diff: Vec<_> = a.symmetric_difference(&b).collect();
+    /// diff.sort();
+    /// assert_eq!(diff, [&1, &4]);
+    /// ```
+    #[inline]
+    pub fn symmetric_difference<'a, G2>(
+        &'a self,
+        other: &'a HashSetRef<'_, K, S, G2>,
+    ) -> SymmetricDifference<'a, K, S, G, G2>
+    where
+        G2: Guard,
+    {
+        SymmetricDifference {
+            iter: self.difference(other).chain(other.difference(self)),
+        }
+    }
+
+    /// Visits the values representing the intersection, i.e., the values that are both in
+    /// `self` and `other`.
+    ///
+    /// The smaller of the two sets is iterated, probing the larger set for each value.
+    /// See [`HashSetRef::difference`] for details about the consistency of set operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    ///
+    /// let a = HashSet::from([1, 2, 3]);
+    /// let b = HashSet::from([4, 2, 3, 4]);
+    ///
+    /// let (a, b) = (a.pin(), b.pin());
+    /// let mut intersection: Vec<_> = a.intersection(&b).collect();
+    /// intersection.sort();
+    /// assert_eq!(intersection, [&2, &3]);
+    /// ```
+    #[inline]
+    pub fn intersection<'a, G2>(
+        &'a self,
+        other: &'a HashSetRef<'_, K, S, G2>,
+    ) -> Intersection<'a, K, S, G, G2>
+    where
+        G2: Guard,
+    {
+        let inner = if self.len() <= other.len() {
+            IntersectionInner::Left(Intersecting {
+                iter: self.iter(),
+                other,
+            })
+        } else {
+            IntersectionInner::Right(Intersecting {
+                iter: other.iter(),
+                other: self,
+            })
+        };
+
+        Intersection { inner }
+    }
+
+    /// Visits the values representing the union, i.e., all the values in `self` or `other`,
+    /// without duplicates.
+    ///
+    /// The larger of the two sets is iterated in full, followed by the values in the smaller
+    /// set that are not in the larger set. See [`HashSetRef::difference`] for details about the
+    /// consistency of set operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    ///
+    /// let a = HashSet::from([1, 2, 3]);
+    /// let b = HashSet::from([4, 2, 3, 4]);
+    ///
+    /// let (a, b) = (a.pin(), b.pin());
+    /// let mut union: Vec<_> = a.union(&b).collect();
+    /// union.sort();
+    /// assert_eq!(union, [&1, &2, &3, &4]);
+    /// ```
+    #[inline]
+    pub fn union<'a, G2>(&'a self, other: &'a HashSetRef<'_, K, S, G2>) -> Union<'a, K, S, G, G2>
+    where
+        G2: Guard,
+    {
+        let inner = if self.len() >= other.len() {
+            UnionInner::Left(self.iter().chain(other.difference(self)))
+        } else {
+            UnionInner::Right(other.iter().chain(self.difference(other)))
+        };
+
+        Union { inner }
+    }
+
+    /// Returns `true` if `self` has no values in common with `other`.
+    ///
+    /// The smaller of the two sets is iterated, probing the larger set for each value.
+    /// See [`HashSetRef::difference`] for details about the consistency of set operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    ///
+    /// let a = HashSet::from([1, 2, 3]);
+    /// let b = HashSet::new();
+    ///
+    /// assert!(a.pin().is_disjoint(&b.pin()));
+    /// b.pin().insert(4);
+    /// assert!(a.pin().is_disjoint(&b.pin()));
+    /// b.pin().insert(1);
+    /// assert!(!a.pin().is_disjoint(&b.pin()));
+    /// ```
+    #[inline]
+    pub fn is_disjoint<G2>(&self, other: &HashSetRef<'_, K, S, G2>) -> bool
+    where
+        G2: Guard,
+    {
+        if self.len() <= other.len() {
+            self.iter().all(|key| !other.contains(key))
+        } else {
+            other.iter().all(|key| !self.contains(key))
+        }
+    }
+
+    /// Returns `true` if the set is a subset of another, i.e., `other` contains at least all
+    /// the values in `self`.
+    ///
+    /// See [`HashSetRef::difference`] for details about the consistency of set operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    ///
+    /// let sup = HashSet::from([1, 2, 3]);
+    /// let set = HashSet::new();
+    ///
+    /// assert!(set.pin().is_subset(&sup.pin()));
+    /// set.pin().insert(2);
+    /// assert!(set.pin().is_subset(&sup.pin()));
+    /// set.pin().insert(4);
+    /// assert!(!set.pin().is_subset(&sup.pin()));
+    /// ```
+    #[inline]
+    pub fn is_subset<G2>(&self, other: &HashSetRef<'_, K, S, G2>) -> bool
+    where
+        G2: Guard,
+    {
+        if self.len() > other.len() {
+            return false;
+        }
+
+        self.iter().all(|key| other.contains(key))
+    }
+
+    /// Returns `true` if the set is a superset of another, i.e., `self` contains at least all
+    /// the values in `other`.
+    ///
+    /// See [`HashSetRef::difference`] for details about the consistency of set operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use papaya::HashSet;
+    ///
+    /// let sub = HashSet::from([1, 2]);
+    /// let set = HashSet::new();
+    ///
+    /// assert!(!set.pin().is_superset(&sub.pin()));
+    /// set.pin().insert(0);
+    /// set.pin().insert(1);
+    /// assert!(!set.pin().is_superset(&sub.pin()));
+    /// set.pin().insert(2);
+    /// assert!(set.pin().is_superset(&sub.pin()));
+    /// ```
+    #[inline]
+    pub fn is_superset<G2>(&self, other: &HashSetRef<'_, K, S, G2>) -> bool
+    where
+        G2: Guard,
+    {
+        other.is_subset(self)
+    }
+}
+
+impl<'set, K, S> HashSetRef<'set, K, S, OwnedGuard<'set>> {
+    /// Returns a pinned reference to the set that uses a thread-local guard.
+    ///
+    /// See [`HashMapRef::as_local`](crate::HashMapRef::as_local) for details.
+    #[inline]
+    pub fn as_local(&self) -> HashSetRef<'set, K, S, LocalGuard<'set>> {
+        self.set.pin()
+    }
+}
+
+impl<'set, K, S> HashSetRef<'set, K, S, LocalGuard<'set>> {
+    /// Converts this reference into a pinned reference that uses an owned guard.
+    ///
+    /// See [`HashMapRef::into_owned`](crate::HashMapRef::into_owned) for details.
+    #[inline]
+    pub fn into_owned(self) -> HashSetRef<'set, K, S, OwnedGuard<'set>> {
+        self.set.pin_owned()
+    }
 }
 
 impl<K, S, G> fmt::Debug for HashSetRef<'_, K, S, G>
@@ -877,6 +1579,11 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         self.raw.next().map(|(k, _)| k)
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
 }
 
 impl<K, G> fmt::Debug for Iter<'_, K, G>
@@ -885,10 +1592,280 @@ where
     G: Guard,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_list()
-            .entries(Iter {
-                raw: self.raw.clone(),
-            })
-            .finish()
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<K, G> Clone for Iter<'_, K, G> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Iter {
+            raw: self.raw.clone(),
+        }
+    }
+}
+
+/// A lazy iterator producing elements in the difference of two sets.
+///
+/// This struct is created by the [`difference`](HashSetRef::difference) method on [`HashSetRef`].
+/// See its documentation for details.
+pub struct Difference<'a, K, S, G1, G2> {
+    iter: Iter<'a, K, G1>,
+    other: &'a HashSetRef<'a, K, S, G2>,
+}
+
+impl<'a, K, S, G1, G2> Iterator for Difference<'a, K, S, G1, G2>
+where
+    K: Hash + Eq + 'a,
+    S: BuildHasher,
+    G1: Guard,
+    G2: Guard,
+{
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.find(|key| !other.contains(*key))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        (lower.saturating_sub(self.other.len()), upper)
+    }
+}
+
+impl<K, S, G1, G2> Clone for Difference<'_, K, S, G1, G2> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Difference {
+            iter: self.iter.clone(),
+            other: self.other,
+        }
+    }
+}
+
+impl<K, S, G1, G2> fmt::Debug for Difference<'_, K, S, G1, G2>
+where
+    K: Hash + Eq + fmt::Debug,
+    S: BuildHasher,
+    G1: Guard,
+    G2: Guard,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// A lazy iterator producing elements in the symmetric difference of two sets.
+///
+/// This struct is created by the [`symmetric_difference`](HashSetRef::symmetric_difference) method
+/// on [`HashSetRef`]. See its documentation for details.
+pub struct SymmetricDifference<'a, K, S, G1, G2> {
+    iter: SymmetricDifferenceInner<'a, K, S, G1, G2>,
+}
+
+// The difference of `self` and `other`, followed by the difference of `other` and `self`.
+type SymmetricDifferenceInner<'a, K, S, G1, G2> =
+    Chain<Difference<'a, K, S, G1, G2>, Difference<'a, K, S, G2, G1>>;
+
+impl<'a, K, S, G1, G2> Iterator for SymmetricDifference<'a, K, S, G1, G2>
+where
+    K: Hash + Eq + 'a,
+    S: BuildHasher,
+    G1: Guard,
+    G2: Guard,
+{
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, S, G1, G2> Clone for SymmetricDifference<'_, K, S, G1, G2> {
+    #[inline]
+    fn clone(&self) -> Self {
+        SymmetricDifference {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<K, S, G1, G2> fmt::Debug for SymmetricDifference<'_, K, S, G1, G2>
+where
+    K: Hash + Eq + fmt::Debug,
+    S: BuildHasher,
+    G1: Guard,
+    G2: Guard,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// A lazy iterator producing elements in the intersection of two sets.
+///
+/// This struct is created by the [`intersection`](HashSetRef::intersection) method on [`HashSetRef`].
+/// See its documentation for details.
+pub struct Intersection<'a, K, S, G1, G2> {
+    inner: IntersectionInner<'a, K, S, G1, G2>,
+}
+
+// The smaller of the two sets is iterated, probing the other.
+enum IntersectionInner<'a, K, S, G1, G2> {
+    Left(Intersecting<'a, K, S, G1, G2>),
+    Right(Intersecting<'a, K, S, G2, G1>),
+}
+
+// Iterates over the values of one set that are contained in the other.
+struct Intersecting<'a, K, S, G1, G2> {
+    iter: Iter<'a, K, G1>,
+    other: &'a HashSetRef<'a, K, S, G2>,
+}
+
+impl<'a, K, S, G1, G2> Intersecting<'a, K, S, G1, G2>
+where
+    K: Hash + Eq + 'a,
+    S: BuildHasher,
+    G1: Guard,
+    G2: Guard,
+{
+    #[inline]
+    fn next(&mut self) -> Option<&'a K> {
+        let other = self.other;
+        self.iter.find(|key| other.contains(*key))
+    }
+}
+
+impl<K, S, G1, G2> Clone for Intersecting<'_, K, S, G1, G2> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Intersecting {
+            iter: self.iter.clone(),
+            other: self.other,
+        }
+    }
+}
+
+impl<'a, K, S, G1, G2> Iterator for Intersection<'a, K, S, G1, G2>
+where
+    K: Hash + Eq + 'a,
+    S: BuildHasher,
+    G1: Guard,
+    G2: Guard,
+{
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.inner {
+            IntersectionInner::Left(ref mut iter) => iter.next(),
+            IntersectionInner::Right(ref mut iter) => iter.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper) = match self.inner {
+            IntersectionInner::Left(ref iter) => iter.iter.size_hint(),
+            IntersectionInner::Right(ref iter) => iter.iter.size_hint(),
+        };
+        (0, upper)
+    }
+}
+
+impl<K, S, G1, G2> Clone for Intersection<'_, K, S, G1, G2> {
+    #[inline]
+    fn clone(&self) -> Self {
+        let inner = match self.inner {
+            IntersectionInner::Left(ref iter) => IntersectionInner::Left(iter.clone()),
+            IntersectionInner::Right(ref iter) => IntersectionInner::Right(iter.clone()),
+        };
+
+        Intersection { inner }
+    }
+}
+
+impl<K, S, G1, G2> fmt::Debug for Intersection<'_, K, S, G1, G2>
+where
+    K: Hash + Eq + fmt::Debug,
+    S: BuildHasher,
+    G1: Guard,
+    G2: Guard,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// A lazy iterator producing elements in the union of two sets.
+///
+/// This struct is created by the [`union`](HashSetRef::union) method on [`HashSetRef`].
+/// See its documentation for details.
+pub struct Union<'a, K, S, G1, G2> {
+    inner: UnionInner<'a, K, S, G1, G2>,
+}
+
+// The larger of the two sets is iterated in full, followed by the difference of the smaller set.
+enum UnionInner<'a, K, S, G1, G2> {
+    Left(Chain<Iter<'a, K, G1>, Difference<'a, K, S, G2, G1>>),
+    Right(Chain<Iter<'a, K, G2>, Difference<'a, K, S, G1, G2>>),
+}
+
+impl<'a, K, S, G1, G2> Iterator for Union<'a, K, S, G1, G2>
+where
+    K: Hash + Eq + 'a,
+    S: BuildHasher,
+    G1: Guard,
+    G2: Guard,
+{
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.inner {
+            UnionInner::Left(ref mut iter) => iter.next(),
+            UnionInner::Right(ref mut iter) => iter.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.inner {
+            UnionInner::Left(ref iter) => iter.size_hint(),
+            UnionInner::Right(ref iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<K, S, G1, G2> Clone for Union<'_, K, S, G1, G2> {
+    #[inline]
+    fn clone(&self) -> Self {
+        let inner = match self.inner {
+            UnionInner::Left(ref iter) => UnionInner::Left(iter.clone()),
+            UnionInner::Right(ref iter) => UnionInner::Right(iter.clone()),
+        };
+
+        Union { inner }
+    }
+}
+
+impl<K, S, G1, G2> fmt::Debug for Union<'_, K, S, G1, G2>
+where
+    K: Hash + Eq + fmt::Debug,
+    S: BuildHasher,
+    G1: Guard,
+    G2: Guard,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
     }
 }
@@ -1,9 +1,16 @@
 // Adapted from: https://github.com/jonhoo/flurry/blob/main/tests/basic.rs
 
-use papaya::{Compute, HashMap, OccupiedError, Operation};
+use papaya::{
+    ByProjection, CapacityError, Compute, ConcurrentMap, DynMap, Expiring, GetResult, HashMap,
+    KeyProjection, MultiMap, OccupiedError, Operation, Projected, SeededState,
+};
 
+use std::any::TypeId;
+use std::collections::hash_map::RandomState;
 use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
-use std::sync::Arc;
+use std::ops::ControlFlow;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 mod common;
 use common::with_map;
@@ -30,6 +37,113 @@ fn clear() {
     });
 }
 
+#[test]
+fn finalize_resize() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        // Finalizing an unallocated table is a no-op.
+        map.finalize_resize(&guard);
+        assert!(map.is_empty());
+
+        for i in 0..1000 {
+            map.insert(i, i + 1, &guard);
+        }
+
+        map.finalize_resize(&guard);
+        assert_eq!(map.len(), 1000);
+        for i in 0..1000 {
+            assert_eq!(map.get(&i, &guard), Some(&(i + 1)));
+        }
+    });
+}
+
+#[test]
+fn compact() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        // Compacting an unallocated table is a no-op.
+        map.compact(&guard);
+        assert!(map.is_empty());
+
+        for i in 0..1000 {
+            map.insert(i, i + 1, &guard);
+        }
+
+        for i in (0..1000).step_by(2) {
+            map.remove(&i, &guard);
+        }
+
+        // Compacting never changes the capacity of the table.
+        map.finalize_resize(&guard);
+        let resizes = map.resize_count();
+        map.compact(&guard);
+        assert_eq!(map.resize_count(), resizes);
+
+        assert_eq!(map.len(), 500);
+        for i in 0..1000 {
+            let expected = (i % 2 == 1).then_some(i + 1);
+            assert_eq!(map.get(&i, &guard).copied(), expected);
+        }
+
+        // Compacting concurrently with writers.
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                let guard = map.guard();
+                for i in (0..1000).step_by(2) {
+                    map.insert(i, i + 1, &guard);
+                }
+            });
+
+            s.spawn(|| {
+                for _ in 0..10 {
+                    map.pin().compact();
+                }
+            });
+        });
+
+        assert_eq!(map.len(), 1000);
+        for i in 0..1000 {
+            assert_eq!(map.get(&i, &guard), Some(&(i + 1)));
+        }
+    });
+}
+
+#[test]
+fn clear_and_reserve() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        // Reserving an unallocated table.
+        map.clear_and_reserve(100, &guard);
+        let resizes = map.resize_count();
+        for i in 0..100 {
+            map.insert(i, i + 1, &guard);
+        }
+        assert_eq!(map.resize_count(), resizes);
+
+        for i in 0..1000 {
+            map.insert(i, i + 1, &guard);
+        }
+
+        map.clear_and_reserve(100, &guard);
+        assert!(map.is_empty());
+        assert_eq!(map.get(&0, &guard), None);
+
+        // Refilling to the reserved capacity does not resize the table.
+        let resizes = map.resize_count();
+        for i in 0..100 {
+            map.insert(i, i + 1, &guard);
+        }
+        assert_eq!(map.resize_count(), resizes);
+        assert_eq!(map.len(), 100);
+    });
+}
+
 #[test]
 fn insert() {
     with_map::<usize, usize>(|map| {
@@ -40,6 +154,1383 @@ fn insert() {
     });
 }
 
+#[test]
+fn insert_into() {
+    with_map::<String, i64>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        assert_eq!(map.insert_into("a", 1, &guard), None);
+        assert_eq!(map.insert_into("a", 2i32, &guard), Some(&1));
+        assert_eq!(map.insert_into(String::from("b"), 3u8, &guard), None);
+        assert_eq!(map.get("a", &guard), Some(&2));
+        assert_eq!(map.pin().insert_into('c', 4), None);
+        assert_eq!(map.get("c", &guard), Some(&4));
+        assert_eq!(map.len(), 3);
+    });
+}
+
+#[test]
+fn insert_if_changed() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        assert!(map.insert_if_changed(1, 1, &guard));
+        assert!(!map.insert_if_changed(1, 1, &guard));
+        let before = map.get(&1, &guard).unwrap() as *const usize;
+        assert!(!map.pin().insert_if_changed(1, 1));
+
+        // The entry is untouched by a skipped write.
+        assert_eq!(map.get(&1, &guard).unwrap() as *const usize, before);
+
+        assert!(map.insert_if_changed(1, 2, &guard));
+        assert_eq!(map.get(&1, &guard), Some(&2));
+        assert_eq!(map.len(), 1);
+    });
+}
+
+#[test]
+fn concurrent_insert_if_changed() {
+    const THREADS: usize = if cfg!(miri) { 2 } else { 8 };
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+
+        // Only the first write of each value takes effect.
+        let writes = std::thread::scope(|s| {
+            let handles = (0..THREADS)
+                .map(|_| {
+                    s.spawn(|| {
+                        let guard = map.guard();
+                        (0..64)
+                            .filter(|&i| map.insert_if_changed(i, i, &guard))
+                            .count()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .sum::<usize>()
+        });
+
+        assert_eq!(writes, 64);
+        assert_eq!(map.len(), 64);
+    });
+}
+
+#[test]
+fn get_consistent() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+        map.insert(1, 10, &guard);
+        map.insert(2, 20, &guard);
+
+        assert_eq!(map.get_consistent([&1, &2], &guard), [Some(&10), Some(&20)]);
+        assert_eq!(map.get_consistent([&1, &3], &guard), [Some(&10), None]);
+        assert_eq!(map.pin().get_consistent::<usize, 0>([]), []);
+    });
+}
+
+#[test]
+fn concurrent_get_consistent() {
+    const WRITES: usize = if cfg!(miri) { 16 } else { 4096 };
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        map.pin().insert(0, 0);
+        map.pin().insert(1, 0);
+
+        let done = std::sync::atomic::AtomicBool::new(false);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                for i in 1..WRITES {
+                    map.pin().insert(0, i);
+                    map.pin().insert(1, i);
+                }
+                done.store(true, std::sync::atomic::Ordering::Release);
+            });
+
+            s.spawn(|| {
+                // The first key is always written first, so it is at most one write ahead.
+                while !done.load(std::sync::atomic::Ordering::Acquire) {
+                    let [first, second] = map.pin().get_consistent([&0, &1]).map(|v| *v.unwrap());
+                    assert!(first == second || first == second + 1);
+                }
+            });
+        });
+    });
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn get_or_insert_future() {
+    use std::future::Future;
+
+    if cfg!(miri) {
+        return;
+    }
+
+    const THREADS: usize = 8;
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+
+        // Only a single caller computes the value, concurrent callers wait for it.
+        std::thread::scope(|s| {
+            for _ in 0..THREADS {
+                s.spawn(|| {
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .build()
+                        .unwrap();
+
+                    let map = map.pin_owned();
+                    let value = runtime.block_on(map.get_or_insert_future(1, || async {
+                        calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        std::thread::sleep(std::time::Duration::from_millis(10));
+                        2
+                    }));
+
+                    assert_eq!(*value, 2);
+                });
+            }
+        });
+
+        assert_eq!(calls.load(std::sync::atomic::Ordering::Relaxed), 1);
+
+        // A cancelled initialization is retried by the next caller.
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let guard = map.owned_guard();
+        runtime.block_on(async {
+            let future = map.get_or_insert_future(3, std::future::pending, &guard);
+            let mut future = std::pin::pin!(future);
+
+            // Start the initialization, then cancel it.
+            std::future::poll_fn(|cx| {
+                assert!(future.as_mut().poll(cx).is_pending());
+                std::task::Poll::Ready(())
+            })
+            .await;
+        });
+
+        let value = runtime.block_on(map.get_or_insert_future(3, || async { 5 }, &guard));
+        assert_eq!(*value, 5);
+    });
+}
+
+#[test]
+fn reclaim_batch() {
+    // Count the number of replaced values that have not yet been reclaimed.
+    fn retained(batch_size: usize) -> usize {
+        let value = Arc::new(());
+        let map = HashMap::builder().reclaim_batch(batch_size).build();
+
+        for _ in 0..512 {
+            map.pin().insert(0, value.clone());
+        }
+
+        Arc::strong_count(&value) - 1
+    }
+
+    // Smaller batches are reclaimed more eagerly.
+    assert!(retained(1) < retained(1024));
+    assert_eq!(retained(1024), 512);
+}
+
+#[test]
+fn with_collector() {
+    let template = seize::Collector::new().batch_size(1);
+    let maps: Vec<HashMap<usize, usize>> = (0..4)
+        .map(|_| HashMap::with_collector(template.clone()))
+        .collect();
+
+    for (i, map) in maps.iter().enumerate() {
+        map.pin().insert(i, i);
+    }
+
+    for (i, map) in maps.iter().enumerate() {
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.pin().get(&i), Some(&i));
+    }
+}
+
+#[test]
+fn get_arc() {
+    with_map::<usize, Arc<usize>>(|map| {
+        let map = map();
+        assert_eq!(map.get_arc(&1, &map.guard()), None);
+
+        map.pin().insert(1, Arc::new(2));
+        let value = map.get_arc(&1, &map.guard()).unwrap();
+        assert_eq!(Arc::strong_count(&value), 2);
+
+        // The value outlives its removal from the map.
+        map.pin().remove(&1);
+        assert_eq!(*value, 2);
+        assert_eq!(map.pin().get_arc(&1), None);
+    });
+}
+
+#[test]
+fn iter_live() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..64 {
+            map.pin().insert(i, i);
+        }
+
+        let guard = map.guard();
+        let stable = map.iter(&guard);
+        let live = map.iter_live(&guard);
+
+        // Migrate every entry to a larger table while the iterators are held.
+        map.pin().reserve(4096);
+        for i in 0..32 {
+            map.pin().insert(i, i + 100);
+        }
+        for i in 32..48 {
+            map.pin().remove(&i);
+        }
+        map.pin().insert(1000, 1000);
+
+        // The stable iterator observes the table as it was.
+        let mut entries: Vec<_> = stable.map(|(&k, &v)| (k, v)).collect();
+        entries.sort();
+        assert_eq!(entries, (0..64).map(|i| (i, i)).collect::<Vec<_>>());
+
+        // The live iterator observes updates and removals of migrated entries.
+        let mut entries: Vec<_> = live.map(|(&k, &v)| (k, v)).collect();
+        entries.sort();
+        let expected: Vec<_> = (0..32)
+            .map(|i| (i, i + 100))
+            .chain((48..64).map(|i| (i, i)))
+            .collect();
+        assert_eq!(entries, expected);
+
+        // Without a concurrent resize, both iterators agree.
+        let mut a: Vec<_> = map.pin().iter_live().map(|(&k, &v)| (k, v)).collect();
+        let mut b: Vec<_> = map.pin().iter().map(|(&k, &v)| (k, v)).collect();
+        a.sort();
+        b.sort();
+        assert_eq!(a, b);
+    });
+}
+
+#[test]
+fn expiring_values() {
+    with_map::<usize, Expiring<usize>>(|map| {
+        let map = map();
+        let map = map.pin();
+        assert_eq!(map.get_unexpired(&1), None);
+
+        assert_eq!(map.insert_with_ttl(1, 1, Duration::from_secs(600)), None);
+        assert_eq!(
+            map.insert_with_ttl(1, 2, Duration::from_secs(600)),
+            Some(&1)
+        );
+        assert_eq!(map.insert_with_ttl(2, 2, Duration::ZERO), None);
+        assert_eq!(map.insert_with_ttl(3, 3, Duration::ZERO), None);
+        map.insert(4, Expiring::forever(4));
+
+        // Replacing an expired value does not return it.
+        assert_eq!(map.insert_with_ttl(3, 3, Duration::ZERO), None);
+
+        assert_eq!(map.get_unexpired(&1), Some(&2));
+        assert_eq!(map.get_unexpired(&4), Some(&4));
+        assert!(map.get(&4).unwrap().expires_at().is_none());
+
+        // Expired entries are removed lazily.
+        assert_eq!(map.len(), 4);
+        assert_eq!(map.get_unexpired(&2), None);
+        assert_eq!(map.len(), 3);
+        assert!(map.get(&3).unwrap().is_expired());
+
+        assert_eq!(map.purge_expired(), 1);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.purge_expired(), 0);
+    });
+}
+
+#[test]
+fn boxed_trait_objects() {
+    trait Plugin: Send + Sync {
+        fn run(&self) -> usize;
+    }
+
+    struct Add(usize);
+
+    impl Plugin for Add {
+        fn run(&self) -> usize {
+            self.0 + 1
+        }
+    }
+
+    with_map::<usize, Box<dyn Plugin>>(|map| {
+        let map = map();
+        map.pin().insert(0, Box::new(Add(0)));
+
+        let guard = map.guard();
+        let plugin = map.get_dyn(&0, &guard).unwrap();
+
+        // Trigger resizes while holding a reference to the value.
+        for i in 1..1000 {
+            map.pin().insert(i, Box::new(Add(i)));
+        }
+        assert_eq!(plugin.run(), 1);
+
+        // The reference remains valid after the entry is removed.
+        map.pin().remove(&0);
+        assert_eq!(plugin.run(), 1);
+        assert!(map.pin().get_dyn(&0).is_none());
+
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                for i in 1..1000 {
+                    assert_eq!(map.pin().get_dyn(&i).unwrap().run(), i + 1);
+                }
+            });
+        });
+    });
+}
+
+#[test]
+fn intern() {
+    with_map::<String, usize>(|map| {
+        let map = map();
+        let map = map.pin();
+
+        assert_eq!(map.release("a"), None);
+        assert_eq!(map.intern("a".to_owned()), "a");
+        assert_eq!(map.intern("a".to_owned()), "a");
+        assert_eq!(map.intern("b".to_owned()), "b");
+        assert_eq!(map.get("a"), Some(&2));
+        assert_eq!(map.get("b"), Some(&1));
+
+        assert_eq!(map.release("a"), Some(1));
+        assert_eq!(map.release("b"), Some(0));
+        assert_eq!(map.release("a"), Some(0));
+        assert_eq!(map.release("a"), None);
+        assert!(map.is_empty());
+    });
+
+    with_map::<Arc<str>, usize>(|map| {
+        let map = map();
+        let map = map.pin();
+
+        // The canonical key is returned, not the key that was passed in.
+        let a: Arc<str> = Arc::from("a");
+        assert!(Arc::ptr_eq(map.intern(a.clone()), &a));
+        assert!(Arc::ptr_eq(map.intern(Arc::from("a")), &a));
+        assert!(Arc::ptr_eq(map.intern(Arc::from("a")), &a));
+        assert_eq!(map.get("a"), Some(&3));
+    });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn intern_concurrent() {
+    const THREADS: usize = 8;
+    const ITERATIONS: usize = 1000;
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+
+        std::thread::scope(|s| {
+            for _ in 0..THREADS {
+                s.spawn(|| {
+                    for i in 0..ITERATIONS {
+                        let key = i % 16;
+                        assert_eq!(map.pin().intern(key), &key);
+                        assert!(map.pin().release(&key).is_some());
+                    }
+                });
+            }
+        });
+
+        // Every intern was matched by a release.
+        assert!(map.is_empty());
+    });
+}
+
+#[test]
+fn claim() {
+    with_map::<usize, ()>(|map| {
+        let map = map();
+        let map = map.pin();
+
+        assert!(!map.unclaim(&1));
+        assert!(map.claim(1));
+        assert!(!map.claim(1));
+        assert!(map.claim(2));
+        assert_eq!(map.len(), 2);
+
+        assert!(map.unclaim(&1));
+        assert!(!map.unclaim(&1));
+        assert!(map.claim(1));
+    });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn claim_concurrent() {
+    const THREADS: usize = 8;
+    const ITERATIONS: usize = 1000;
+
+    with_map::<usize, ()>(|map| {
+        let map = map();
+        let claimed = std::sync::atomic::AtomicUsize::new(0);
+
+        std::thread::scope(|s| {
+            for _ in 0..THREADS {
+                s.spawn(|| {
+                    for i in 0..ITERATIONS {
+                        if map.pin().claim(i) {
+                            claimed.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+
+        // Every key was claimed exactly once.
+        assert_eq!(claimed.into_inner(), ITERATIONS);
+        assert_eq!(map.len(), ITERATIONS);
+    });
+}
+
+#[test]
+fn try_insert_no_resize() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let map = map.pin();
+
+        // The table has not been allocated.
+        if map.capacity() == 0 {
+            assert_eq!(
+                map.try_insert_no_resize(0, 0),
+                Err(papaya::WouldResize { key: 0, value: 0 })
+            );
+        }
+
+        map.reserve(64);
+        let capacity = map.capacity();
+
+        // Insert until the table is full.
+        let mut inserted = 0;
+        for i in 0..10_000 {
+            match map.try_insert_no_resize(i, i) {
+                Ok(None) => inserted += 1,
+                Ok(Some(_)) => unreachable!(),
+                Err(rejected) => {
+                    assert_eq!((rejected.key, rejected.value), (i, i));
+                    assert_eq!(map.get(&i), None);
+                }
+            }
+        }
+
+        assert!(inserted >= 64);
+        assert_eq!(map.len(), inserted);
+        assert_eq!(map.capacity(), capacity);
+
+        // Replacing an existing key never resizes.
+        assert_eq!(map.try_insert_no_resize(0, 1), Ok(Some(&0)));
+        assert_eq!(map.get(&0), Some(&1));
+    });
+}
+
+#[test]
+fn get_tiered() {
+    with_map::<usize, usize>(|l1| {
+        with_map::<usize, usize>(|l2| {
+            let (l1, l2) = (l1(), l2());
+            l1.pin().insert(0, 0);
+            l2.pin().insert(0, 1);
+            l2.pin().insert(1, 1);
+
+            let (l1, l2) = (l1.pin(), l2.pin());
+
+            // The first tier takes precedence.
+            assert_eq!(papaya::get_tiered(&[&l1, &l2], &0, true), Some(&0));
+            assert_eq!(papaya::get_tiered(&[&l1, &l2], &2, true), None);
+
+            // Lookups without promotion do not modify earlier tiers.
+            assert_eq!(papaya::get_tiered(&[&l1, &l2], &1, false), Some(&1));
+            assert_eq!(l1.get(&1), None);
+
+            assert_eq!(papaya::get_tiered(&[&l1, &l2], &1, true), Some(&1));
+            assert_eq!(l1.get(&1), Some(&1));
+            assert_eq!(l2.get(&1), Some(&1));
+        });
+    });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn get_tiered_concurrent() {
+    const THREADS: usize = 8;
+
+    with_map::<usize, usize>(|l1| {
+        with_map::<usize, usize>(|l2| {
+            let (l1, l2) = (l1(), l2());
+            for i in 0..100 {
+                l2.pin().insert(i, i);
+            }
+
+            std::thread::scope(|s| {
+                for _ in 0..THREADS {
+                    s.spawn(|| {
+                        let (l1, l2) = (l1.pin(), l2.pin());
+                        for i in 0..100 {
+                            assert_eq!(papaya::get_tiered(&[&l1, &l2], &i, true), Some(&i));
+                        }
+                    });
+                }
+            });
+
+            assert_eq!(l1.len(), 100);
+        });
+    });
+}
+
+#[test]
+fn partition() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..1000 {
+            map.pin().insert(i, i + 1);
+        }
+
+        let shards = map.partition(4);
+        assert_eq!(shards.len(), 4);
+        assert_eq!(shards.iter().map(|shard| shard.len()).sum::<usize>(), 1000);
+
+        for i in 0..1000 {
+            let shard = (shards[0].hasher().hash_one(i) % 4) as usize;
+            for (j, map) in shards.iter().enumerate() {
+                let expected = if j == shard { Some(&(i + 1)) } else { None };
+                assert_eq!(map.pin().get(&i), expected);
+            }
+        }
+    });
+
+    let map: HashMap<usize, usize> = HashMap::new();
+    let shards = map.partition(1);
+    assert!(shards[0].is_empty());
+}
+
+#[test]
+fn iter_removable() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..100 {
+            map.pin().insert(i, i);
+        }
+
+        let guard = map.guard();
+        let handles: Vec<_> = map.iter_removable(&guard).collect();
+        assert_eq!(handles.len(), 100);
+
+        // Update some entries after they were observed, and trigger a resize.
+        for i in (0..100).step_by(2) {
+            map.insert(i, i + 1, &guard);
+        }
+        map.reserve(1000, &guard);
+
+        for handle in &handles {
+            let key = *handle.key();
+            assert_eq!(*handle.value(), key);
+            assert_eq!(handle.remove_if_unchanged(), key % 2 == 1);
+        }
+
+        // Entries that were already removed are not removed again.
+        assert!(handles.iter().all(|handle| !handle.remove_if_unchanged()));
+
+        assert_eq!(map.len(), 50);
+        for i in 0..100 {
+            let expected = if i % 2 == 0 { Some(&(i + 1)) } else { None };
+            assert_eq!(map.get(&i, &guard), expected);
+        }
+    });
+}
+
+#[test]
+fn key_projection() {
+    #[derive(Clone, Debug)]
+    struct Record {
+        id: usize,
+        payload: Vec<u8>,
+    }
+
+    struct ById;
+
+    impl KeyProjection<Record> for ById {
+        type Key = usize;
+
+        fn project(record: &Record) -> &usize {
+            &record.id
+        }
+    }
+
+    let record = |id, len| {
+        Projected::<_, ById>::new(Record {
+            id,
+            payload: vec![0; len],
+        })
+    };
+
+    with_map::<Projected<Record, ById>, usize>(|map| {
+        let map = map();
+        let map = map.pin();
+
+        for i in 0..100 {
+            assert_eq!(map.insert(record(i, i), i), None);
+        }
+
+        // Keys are compared by their projection only.
+        assert_eq!(map.insert(record(0, 1000), 1), Some(&0));
+        assert_eq!(map.len(), 100);
+        assert_eq!(map.get(&record(0, 0)), Some(&1));
+
+        for i in 1..100 {
+            let (key, value) = map.get_key_value(&ByProjection(&i)).unwrap();
+            assert_eq!(key.projection(), &i);
+            assert_eq!(key.key().payload.len(), i);
+            assert_eq!(*value, i);
+        }
+
+        assert_eq!(map.get(&ByProjection(&100)), None);
+        assert_eq!(map.remove(&ByProjection(&0)), Some(&1));
+        assert!(!map.contains_key(&ByProjection(&0)));
+    });
+}
+
+#[test]
+fn insert_evicting() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let map = map.pin();
+
+        let queue = std::sync::Mutex::new(std::collections::VecDeque::new());
+        let evicted = std::cell::RefCell::new(Vec::new());
+
+        for i in 0..100 {
+            queue.lock().unwrap().push_back(i);
+            let result = map.insert_evicting(
+                i,
+                i,
+                10,
+                || queue.lock().unwrap().pop_front(),
+                |&key, &value| evicted.borrow_mut().push((key, value)),
+            );
+            assert_eq!(result, Ok(None));
+            assert!(map.len() <= 10);
+        }
+
+        // The oldest entries were evicted in order.
+        assert_eq!(
+            *evicted.borrow(),
+            (0..90).map(|i| (i, i)).collect::<Vec<_>>()
+        );
+        for i in 90..100 {
+            assert_eq!(map.get(&i), Some(&i));
+        }
+
+        // Replacing an existing value never evicts.
+        assert_eq!(
+            map.insert_evicting(99, 0, 10, || unreachable!(), |_, _| {}),
+            Ok(Some(&99))
+        );
+
+        // Stale victims are skipped.
+        let mut victims = vec![90, 1000];
+        let result = map.insert_evicting(100, 100, 10, || victims.pop(), |_, _| {});
+        assert_eq!(result, Ok(None));
+        assert_eq!(map.get(&90), None);
+
+        // There is nothing left to evict.
+        let result = map.insert_evicting(101, 101, 10, || None, |_, _| {});
+        assert_eq!(
+            result,
+            Err(CapacityError {
+                key: 101,
+                value: 101
+            })
+        );
+        assert_eq!(map.len(), 10);
+    });
+}
+
+#[test]
+fn freeze() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..100 {
+            map.pin().insert(i, i + 1);
+        }
+
+        let frozen = map.freeze();
+        assert_eq!(frozen.len(), 100);
+        assert!(!frozen.is_empty());
+        for i in 0..100 {
+            assert!(frozen.contains_key(&i));
+            assert_eq!(frozen.get(&i), Some(&(i + 1)));
+            assert_eq!(frozen.get_key_value(&i), Some((&i, &(i + 1))));
+        }
+        assert_eq!(frozen.get(&100), None);
+
+        let mut keys: Vec<_> = frozen.keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, (0..100).collect::<Vec<_>>());
+        assert_eq!(frozen.iter().count(), 100);
+        assert_eq!(frozen.values().sum::<usize>(), (1..=100).sum());
+
+        // Frozen maps can be shared across threads.
+        std::thread::scope(|s| {
+            s.spawn(|| assert_eq!(frozen.get(&1), Some(&2)));
+        });
+
+        let map = frozen.thaw();
+        map.pin().insert(100, 101);
+        assert_eq!(map.len(), 101);
+        assert_eq!(map.pin().get(&0), Some(&1));
+    });
+}
+
+#[test]
+fn iter_passive() {
+    let map = HashMap::builder()
+        .resize_mode(papaya::ResizeMode::Incremental(1))
+        .build();
+    for i in 0..64 {
+        map.pin().insert(i, i);
+    }
+    map.pin().finalize_resize();
+
+    // Start a resize, and migrate some of the entries through writes.
+    map.pin().force_resize();
+    for i in 0..8 {
+        map.pin().insert(i, i + 100);
+    }
+    map.pin().insert(1000, 1000);
+
+    // Iteration does not complete the resize. The new key may or may not be observed,
+    // depending on whether the writes above completed the resize.
+    let capacity = map.pin().capacity();
+    let mut entries: Vec<_> = map.pin().iter_passive().map(|(&k, &v)| (k, v)).collect();
+    entries.sort();
+    if entries.last() == Some(&(1000, 1000)) {
+        entries.pop();
+    }
+    let expected: Vec<_> = (0..8)
+        .map(|i| (i, i + 100))
+        .chain((8..64).map(|i| (i, i)))
+        .collect();
+    assert_eq!(entries, expected);
+    assert_eq!(map.pin().capacity(), capacity);
+
+    // Once the resize completes, every entry is observed.
+    map.pin().finalize_resize();
+    assert_eq!(map.pin().iter_passive().count(), 65);
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        assert_eq!(map.pin().iter_passive().count(), 0);
+        for i in 0..100 {
+            map.pin().insert(i, i);
+        }
+        map.pin().finalize_resize();
+        let mut keys: Vec<_> = map.pin().iter_passive().map(|(&k, _)| k).collect();
+        keys.sort();
+        assert_eq!(keys, (0..100).collect::<Vec<_>>());
+    });
+}
+
+#[test]
+fn weak_values() {
+    with_map::<usize, Weak<usize>>(|map| {
+        let map = map();
+        assert_eq!(map.get_upgraded(&1, &map.guard()), None);
+
+        let live = Arc::new(1);
+        let dead = Arc::new(2);
+        map.pin().insert(1, Arc::downgrade(&live));
+        map.pin().insert(2, Arc::downgrade(&dead));
+        assert_eq!(map.pin().get_upgraded(&2), Some(dead.clone()));
+
+        drop(dead);
+        assert_eq!(map.pin().get_upgraded(&1), Some(live.clone()));
+        assert_eq!(map.pin().get_upgraded(&2), None);
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.pin().prune_dead(), 1);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.prune_dead(&map.guard()), 0);
+        assert!(map.pin().contains_key(&1));
+    });
+}
+
+#[test]
+fn insert_tracked() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        let mut grows = 0;
+        for i in 0..10_000 {
+            let (old, grew) = map.insert_tracked(i, i, &guard);
+            assert_eq!(old, None);
+            grows += grew as u64;
+        }
+
+        assert!(grows > 0);
+        assert_eq!(grows, map.resize_count().0);
+
+        // Replacing values does not grow the table.
+        for i in 0..10_000 {
+            assert_eq!(map.insert_tracked(i, i + 1, &guard), (Some(&i), false));
+        }
+    });
+}
+
+#[test]
+fn extend_unique() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        map.pin().insert(0, 0);
+        map.extend_unique((1..1000).map(|i| (i, i + 1)), &map.guard());
+
+        assert_eq!(map.len(), 1000);
+        for i in 1..1000 {
+            assert_eq!(map.pin().get(&i), Some(&(i + 1)));
+        }
+
+        map.pin().extend_unique((1000..2000).map(|i| (i, i + 1)));
+        assert_eq!(map.len(), 2000);
+        for i in 0..2000 {
+            assert!(map.pin().contains_key(&i));
+        }
+    });
+}
+
+#[test]
+fn for_each_update() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..1000 {
+            map.pin().insert(i, i);
+        }
+
+        map.pin()
+            .for_each_update(|&k, &v| if k % 2 == 0 { Some(v * 2) } else { None });
+
+        assert_eq!(map.len(), 1000);
+        for i in 0..1000 {
+            let expected = if i % 2 == 0 { i * 2 } else { i };
+            assert_eq!(map.pin().get(&i), Some(&expected));
+        }
+    });
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn par_update_all() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        map.pin().par_update_all(|_| unreachable!());
+
+        for i in 0..10_000 {
+            map.pin().insert(i, i);
+        }
+
+        map.pin().par_update_all(|&v| v * 2);
+
+        assert_eq!(map.len(), 10_000);
+        for i in 0..10_000 {
+            assert_eq!(map.pin().get(&i), Some(&(i * 2)));
+        }
+
+        // Concurrent updates are never lost.
+        std::thread::scope(|s| {
+            s.spawn(|| map.pin().par_update_all(|&v| v + 1));
+            s.spawn(|| {
+                for i in 0..10_000 {
+                    map.pin().update(i, |&v| v + 1);
+                }
+            });
+        });
+
+        for i in 0..10_000 {
+            assert_eq!(map.pin().get(&i), Some(&(i * 2 + 2)));
+        }
+    });
+}
+
+#[test]
+fn save_and_load() {
+    fn encode(key: &usize, value: &usize, buf: &mut Vec<u8>) -> std::io::Result<()> {
+        buf.extend_from_slice(&(*key as u64).to_le_bytes());
+        buf.extend_from_slice(&(*value as u64).to_le_bytes());
+        Ok(())
+    }
+
+    fn decode(buf: &[u8]) -> std::io::Result<(usize, usize)> {
+        let key = u64::from_le_bytes(buf[..8].try_into().unwrap());
+        let value = u64::from_le_bytes(buf[8..].try_into().unwrap());
+        Ok((key as usize, value as usize))
+    }
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..1000 {
+            map.pin().insert(i, i + 1);
+        }
+
+        let mut bytes = Vec::new();
+        map.pin().save_to(&mut bytes, encode).unwrap();
+        assert_eq!(&bytes[..6], b"PAPAYA");
+        assert_eq!(bytes.len(), 16 + 1000 * (8 + 16));
+
+        // The loaded map is preallocated from the header.
+        let loaded: HashMap<usize, usize> = HashMap::load_from(&bytes[..], decode).unwrap();
+        assert_eq!(loaded, map);
+        assert_eq!(loaded.resize_count(), (0, 0));
+
+        // An empty map.
+        let mut empty = Vec::new();
+        HashMap::<usize, usize>::new()
+            .pin()
+            .save_to(&mut empty, encode)
+            .unwrap();
+        let loaded: HashMap<usize, usize> = HashMap::load_from(&empty[..], decode).unwrap();
+        assert!(loaded.is_empty());
+
+        // Invalid magic bytes.
+        let mut corrupted = bytes.clone();
+        corrupted[0] = b'X';
+        let err = HashMap::<usize, usize>::load_from(&corrupted[..], decode).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        // An unsupported version.
+        let mut corrupted = bytes.clone();
+        corrupted[6] = 2;
+        let err = HashMap::<usize, usize>::load_from(&corrupted[..], decode).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        // Truncated input.
+        let truncated = &bytes[..bytes.len() - 1];
+        let err = HashMap::<usize, usize>::load_from(truncated, decode).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+        // A corrupted entry count is not trusted for preallocation.
+        let mut corrupted = bytes[..16].to_vec();
+        corrupted[8..16].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        let err = HashMap::<usize, usize>::load_from(&corrupted[..], decode).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    });
+}
+
+#[test]
+fn snapshot_len() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        assert_eq!(map.pin().snapshot_len(), 0);
+
+        for i in 0..100 {
+            map.pin().insert(i, i);
+        }
+
+        let guard = map.guard();
+        assert_eq!(map.snapshot_len(&guard), 100);
+        assert_eq!(map.snapshot_len(&guard), map.snapshot(&guard).len());
+
+        map.pin().remove(&0);
+        assert_eq!(map.pin().snapshot_len(), 99);
+    });
+}
+
+#[test]
+fn count_matching_snapshot() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        assert_eq!(map.pin().count_matching_snapshot(|_, _| true), 0);
+
+        for i in 0..100 {
+            map.pin().insert(i, i);
+        }
+
+        let guard = map.guard();
+        assert_eq!(map.count_matching_snapshot(|_, v| v % 2 == 0, &guard), 50);
+        assert_eq!(map.count_matching_snapshot(|&k, _| k < 10, &guard), 10);
+
+        // Entries are counted exactly once across a concurrent resize.
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                for i in 100..2000 {
+                    map.pin().insert(i, i);
+                }
+            });
+
+            for _ in 0..10 {
+                assert_eq!(map.pin().count_matching_snapshot(|&k, _| k < 100), 100);
+            }
+        });
+    });
+}
+
+#[test]
+fn iter_enumerate() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        assert_eq!(map.pin().iter_enumerate().count(), 0);
+
+        for i in 0..100 {
+            map.pin().insert(i, i);
+        }
+
+        let map = map.pin();
+        let capacity = map.capacity();
+        let slots: Vec<_> = map.iter_enumerate().map(|(slot, _, _)| slot).collect();
+        assert_eq!(slots.len(), 100);
+
+        // Slots are visited in ascending order and are within the table.
+        assert!(slots.windows(2).all(|w| w[0] < w[1]));
+        assert!(slots.iter().all(|&slot| slot < capacity));
+
+        // The entries match those yielded in table order.
+        let entries: Vec<_> = map.iter_enumerate().map(|(_, k, v)| (k, v)).collect();
+        assert_eq!(entries, map.iter_in_table_order().collect::<Vec<_>>());
+    });
+}
+
+#[test]
+fn tombstone_count() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+        assert_eq!(map.tombstone_count(&guard), 0);
+
+        for i in 0..1000 {
+            map.insert(i, i, &guard);
+        }
+        map.finalize_resize(&guard);
+
+        for i in 0..100 {
+            map.remove(&i, &guard);
+        }
+        assert_eq!(map.tombstone_count(&guard), 100);
+
+        map.compact(&guard);
+        assert_eq!(map.tombstone_count(&guard), 0);
+    });
+}
+
+#[test]
+fn capacity() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+        assert_eq!(map.capacity(&guard), 0);
+
+        for i in 0..1000 {
+            map.insert(i, i, &guard);
+        }
+        let capacity = map.capacity(&guard);
+        assert!(capacity.is_power_of_two());
+        assert!(capacity >= 1000);
+
+        // The capacity of the new table is reported while a resize is in progress.
+        map.reserve(10_000, &guard);
+        assert!(map.pin().capacity() >= 10_000);
+        map.finalize_resize(&guard);
+        assert!(map.capacity(&guard) >= 10_000);
+    });
+}
+
+#[test]
+fn force_resize() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        // Forcing a resize allocates an unallocated table.
+        map.force_resize(&guard);
+        let capacity = map.capacity(&guard);
+        assert!(capacity > 0);
+
+        for i in 0..10 {
+            map.insert(i, i, &guard);
+        }
+
+        for round in 1..=3 {
+            map.pin().force_resize();
+            assert_eq!(map.capacity(&guard), capacity << round);
+
+            // Operations during the resize observe every entry.
+            for i in 0..10 {
+                assert_eq!(map.get(&i, &guard), Some(&i));
+            }
+        }
+
+        map.finalize_resize(&guard);
+        assert_eq!(map.len(), 10);
+        assert_eq!(map.pin().iter().count(), 10);
+    });
+}
+
+#[test]
+fn auto_compact_at() {
+    let map = HashMap::builder().auto_compact_at(0.1).build();
+    let guard = map.guard();
+
+    for i in 0..1000 {
+        map.insert(i, i, &guard);
+    }
+    map.finalize_resize(&guard);
+    let resizes = map.resize_count();
+
+    // Removals trigger compaction once they reach the threshold.
+    for i in 0..1000 {
+        map.remove(&i, &guard);
+        assert!(map.tombstone_count(&guard) < 1000);
+    }
+    assert!(map.tombstone_count(&guard) < 1000);
+    assert!(map.is_empty());
+
+    // Compaction never changes the capacity of the table.
+    assert_eq!(map.resize_count(), resizes);
+
+    // Bulk removals also trigger compaction.
+    for i in 0..1000 {
+        map.insert(i, i, &guard);
+    }
+    map.pin().retain(|_, _| false);
+    assert_eq!(map.tombstone_count(&guard), 0);
+
+    // Compacting concurrently with writers.
+    std::thread::scope(|s| {
+        for t in 0..4 {
+            let map = &map;
+            s.spawn(move || {
+                let guard = map.guard();
+                for _ in 0..10 {
+                    for i in (t * 250)..((t + 1) * 250) {
+                        map.insert(i, i, &guard);
+                    }
+                    for i in (t * 250)..((t + 1) * 250) {
+                        assert_eq!(map.remove(&i, &guard), Some(&i));
+                    }
+                }
+            });
+        }
+    });
+    assert!(map.is_empty());
+}
+
+#[test]
+fn get_expect() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        map.pin().insert(1, 2);
+        assert_eq!(map.get_expect(&1, &map.guard()), &2);
+        assert_eq!(map.pin().get_expect(&1), &2);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            map.pin().get_expect(&42);
+        }));
+        let message = result.unwrap_err();
+        let message = message.downcast_ref::<String>().unwrap();
+        assert_eq!(message, "key 42 not found in map");
+    });
+}
+
+#[test]
+fn from_keys_values() {
+    let keys: Vec<usize> = (0..100).chain(0..10).collect();
+    let values: Vec<usize> = (0..110).collect();
+    let map = HashMap::from_keys_values(keys, values);
+
+    assert_eq!(map.len(), 100);
+    for i in 0..100 {
+        let expected = if i < 10 { i + 100 } else { i };
+        assert_eq!(map.pin().get(&i), Some(&expected));
+    }
+
+    // The lengths must match.
+    let result = std::panic::catch_unwind(|| HashMap::from_keys_values(vec![1, 2], vec![1]));
+    assert!(result.is_err());
+}
+
+#[test]
+fn concurrency() {
+    for concurrency in [0, 1, 3, 64, usize::MAX] {
+        let map = HashMap::builder().concurrency(concurrency).build();
+
+        std::thread::scope(|s| {
+            for t in 0..4 {
+                let map = &map;
+                s.spawn(move || {
+                    for i in (t * 100)..((t + 1) * 100) {
+                        map.pin().insert(i, i);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(map.len(), 400);
+    }
+}
+
+#[test]
+fn get_or_insert_with_key() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+        assert_eq!(map.get_or_insert_with_key(1, |&k| k * 10, &guard), &10);
+        assert_eq!(
+            map.get_or_insert_with_key(1, |_| panic!("key is present"), &guard),
+            &10
+        );
+        assert_eq!(map.pin().get_or_insert_with_key(2, |&k| k * 10), &20);
+        assert_eq!(map.len(), 2);
+    });
+}
+
+#[test]
+fn contains_mask() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        assert_eq!(map.pin().contains_mask(&[&1, &2]), [false, false]);
+
+        for i in (0..100).step_by(3) {
+            map.pin().insert(i, i);
+        }
+
+        let keys: Vec<usize> = (0..100).rev().collect();
+        let keys: Vec<&usize> = keys.iter().collect();
+        let mask = map.contains_mask(&keys, &map.guard());
+
+        assert_eq!(mask.len(), keys.len());
+        for (key, present) in keys.iter().zip(mask) {
+            assert_eq!(present, *key % 3 == 0);
+        }
+    });
+}
+
+#[test]
+fn publish_through_channel() {
+    with_map::<usize, Vec<usize>>(|map| {
+        let map = map();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        std::thread::scope(|s| {
+            // A write that happens-before a message is visible to the receiver.
+            s.spawn(|| {
+                for i in 0..1000 {
+                    map.pin().insert(i, vec![i; 4]);
+                    tx.send(i).unwrap();
+                }
+                drop(tx);
+            });
+
+            s.spawn(|| {
+                let map = map.pin();
+                for i in rx {
+                    assert_eq!(map.get(&i), Some(&vec![i; 4]));
+                }
+            });
+        });
+    });
+}
+
+#[test]
+fn publish_through_map() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let data = AtomicUsize::new(0);
+
+        std::thread::scope(|s| {
+            // Writes before an insert are visible to any thread that observes the insert.
+            s.spawn(|| {
+                for i in 1..=1000 {
+                    data.store(i, Ordering::Relaxed);
+                    map.pin().insert(i, i);
+                }
+            });
+
+            s.spawn(|| {
+                for i in 1..=1000 {
+                    let map = map.pin();
+                    while map.get(&i).is_none() {
+                        std::hint::spin_loop();
+                    }
+                    assert!(data.load(Ordering::Relaxed) >= i);
+                }
+            });
+        });
+    });
+}
+
+#[test]
+fn insert_bounded() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        for i in 0..10 {
+            assert_eq!(map.insert_bounded(i, i, 10, &guard), Ok(None));
+        }
+
+        // New keys are rejected at capacity.
+        assert_eq!(
+            map.insert_bounded(10, 10, 10, &guard),
+            Err(CapacityError { key: 10, value: 10 })
+        );
+        assert_eq!(map.len(), 10);
+
+        // Existing keys can still be updated.
+        assert_eq!(map.insert_bounded(0, 1, 10, &guard), Ok(Some(&0)));
+
+        // Evict an entry and retry.
+        map.remove(&0, &guard);
+        assert_eq!(map.insert_bounded(10, 10, 10, &guard), Ok(None));
+        assert_eq!(map.len(), 10);
+    });
+}
+
+#[test]
+fn named_guard_types() {
+    fn get_local(map: &HashMap<usize, usize>, guard: &papaya::LocalGuard<'_>) -> Option<usize> {
+        map.get(&1, guard).copied()
+    }
+
+    fn get_owned(map: &HashMap<usize, usize>, guard: &papaya::OwnedGuard<'_>) -> Option<usize> {
+        map.get(&1, guard).copied()
+    }
+
+    fn insert(map: papaya::HashMapPin<'_, usize, usize>) {
+        map.insert(1, 2);
+    }
+
+    fn remove(map: papaya::HashMapPinOwned<'_, usize, usize>) {
+        map.remove(&1);
+    }
+
+    let map = HashMap::new();
+    insert(map.pin());
+    assert_eq!(get_local(&map, &map.guard()), Some(2));
+    assert_eq!(get_owned(&map, &map.owned_guard()), Some(2));
+    remove(map.pin_owned());
+    assert!(map.is_empty());
+}
+
 #[test]
 fn get_empty() {
     with_map::<usize, usize>(|map| {
@@ -71,78 +1562,646 @@ fn remove_empty() {
 }
 
 #[test]
-fn insert_and_remove() {
+fn insert_and_remove() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+        map.insert(42, 0, &guard);
+        let old = map.remove(&42, &guard).unwrap();
+        assert_eq!(old, &0);
+        assert!(map.get(&42, &guard).is_none());
+    });
+}
+
+#[test]
+fn insert_and_get() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        map.insert(42, 0, &map.guard());
+
+        {
+            let guard = map.guard();
+            let e = map.get(&42, &guard).unwrap();
+            assert_eq!(e, &0);
+        }
+    });
+}
+
+#[test]
+fn insert_and_get_key_value() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        map.insert(42, 0, &map.guard());
+
+        {
+            let guard = map.guard();
+            let e = map.get_key_value(&42, &guard).unwrap();
+            assert_eq!(e, (&42, &0));
+        }
+    });
+}
+
+#[test]
+fn reinsert() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+        map.insert(42, 0, &guard);
+        let old = map.insert(42, 1, &guard);
+        assert_eq!(old, Some(&0));
+
+        {
+            let guard = map.guard();
+            let e = map.get(&42, &guard).unwrap();
+            assert_eq!(e, &1);
+        }
+    });
+}
+
+#[test]
+fn update() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+        map.insert(42, 0, &guard);
+        assert_eq!(map.len(), 1);
+        let new = map.update(42, |v| v + 1, &guard);
+        assert_eq!(map.len(), 1);
+        assert_eq!(new, Some(&1));
+
+        {
+            let guard = map.guard();
+            let e = map.get(&42, &guard).unwrap();
+            assert_eq!(e, &1);
+        }
+    });
+}
+
+#[test]
+fn update_with_key() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        assert_eq!(map.update_with_key(7, |k, v| k * v, &guard), None);
+        assert!(map.is_empty());
+
+        map.insert(7, 2, &guard);
+        assert_eq!(map.update_with_key(7, |k, v| k * v, &guard), Some(&14));
+        assert_eq!(map.pin().update_with_key(7, |k, v| k + v), Some(&21));
+        assert_eq!(map.get(&7, &guard), Some(&21));
+        assert_eq!(map.len(), 1);
+    });
+}
+
+#[test]
+fn try_update() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+        let halve = |v: &usize| if v % 2 == 0 { Ok(v / 2) } else { Err(*v) };
+
+        assert_eq!(map.try_update(42, halve, &guard), Ok(None));
+        assert!(map.is_empty());
+
+        map.insert(42, 4, &guard);
+        assert_eq!(map.try_update(42, halve, &guard), Ok(Some(&2)));
+        assert_eq!(map.pin().try_update(42, halve), Ok(Some(&1)));
+
+        // A failed update leaves the entry unchanged.
+        assert_eq!(map.try_update(42, halve, &guard), Err(1));
+        assert_eq!(map.get(&42, &guard), Some(&1));
+        assert_eq!(map.len(), 1);
+    });
+}
+
+#[test]
+fn apply_deltas() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        map.apply_deltas(Vec::new(), |v, d: &usize| v + d, 0, &guard);
+        assert!(map.is_empty());
+
+        map.insert(0, 100, &guard);
+        map.apply_deltas((0..10).map(|i| (i % 5, i)), |v, d| v + d, 0, &guard);
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.get(&0, &guard), Some(&105));
+        assert_eq!(map.get(&4, &guard), Some(&13));
+
+        map.pin().apply_deltas([(9, 1)], |v, d| v * d, 7);
+        assert_eq!(map.get(&9, &guard), Some(&7));
+    });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn apply_deltas_concurrent() {
+    const THREADS: usize = 4;
+    const KEYS: usize = 64;
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+
+        std::thread::scope(|s| {
+            for _ in 0..THREADS {
+                s.spawn(|| {
+                    for _ in 0..10 {
+                        map.pin()
+                            .apply_deltas((0..KEYS).map(|k| (k, 1)), |v, d| v + d, 0);
+                    }
+                });
+            }
+        });
+
+        // No deltas were lost.
+        let map = map.pin();
+        assert!((0..KEYS).all(|k| map.get(&k) == Some(&(THREADS * 10))));
+    });
+}
+
+#[test]
+fn take_value() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        assert_eq!(map.take_value(1, &guard), None);
+        assert!(map.is_empty());
+
+        map.insert(1, 10, &guard);
+        assert_eq!(map.take_value(1, &guard), Some(&10));
+        assert_eq!(map.get(&1, &guard), Some(&0));
+        assert_eq!(map.pin().take_value(1), Some(&0));
+        assert_eq!(map.len(), 1);
+    });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn take_value_concurrent() {
+    const THREADS: usize = 4;
+    const INCREMENTS: usize = 1000;
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        map.pin().insert(0, 0);
+
+        let taken = std::thread::scope(|s| {
+            for _ in 0..THREADS {
+                s.spawn(|| {
+                    for _ in 0..INCREMENTS {
+                        map.pin().update(0, |v| v + 1);
+                    }
+                });
+            }
+
+            let mut taken = 0;
+            for _ in 0..100 {
+                taken += map.pin().take_value(0).unwrap();
+            }
+            taken
+        });
+
+        // No increments were lost between reads.
+        let remaining = *map.pin().get(&0).unwrap();
+        assert_eq!(taken + remaining, THREADS * INCREMENTS);
+    });
+}
+
+#[test]
+fn decrement_or_remove() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        assert_eq!(map.decrement_or_remove(1, 1, &guard), None);
+        assert!(map.is_empty());
+
+        map.insert(1, 10, &guard);
+        assert_eq!(map.decrement_or_remove(1, 3, &guard), Some(&7));
+        assert_eq!(map.pin().decrement_or_remove(1, 6), Some(&1));
+        assert_eq!(map.decrement_or_remove(1, 1, &guard), None);
+        assert!(map.is_empty());
+
+        // Decrementing by more than the current value removes the entry.
+        map.insert(2, 5, &guard);
+        assert_eq!(map.decrement_or_remove(2, 100, &guard), None);
+        assert!(map.is_empty());
+    });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn decrement_or_remove_concurrent() {
+    const THREADS: usize = 8;
+    const DECREMENTS: usize = 1000;
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        map.pin().insert(0, THREADS * DECREMENTS + 1);
+
+        std::thread::scope(|s| {
+            for _ in 0..THREADS {
+                s.spawn(|| {
+                    for _ in 0..DECREMENTS {
+                        assert!(map.pin().decrement_or_remove(0, 1).is_some());
+                    }
+                });
+            }
+        });
+
+        // No decrements were lost.
+        assert_eq!(map.pin().get(&0), Some(&1));
+        assert_eq!(map.pin().decrement_or_remove(0, 1), None);
+        assert!(map.is_empty());
+    });
+}
+
+#[test]
+fn update_in_place() {
+    with_map::<usize, Vec<usize>>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        assert_eq!(map.update_in_place(42, |v| v.push(1), &guard), None);
+        assert!(map.is_empty());
+
+        map.insert(42, vec![0], &guard);
+        let old = map.get(&42, &guard).unwrap();
+        let new = map.update_in_place(42, |v| v.push(1), &guard);
+        assert_eq!(new, Some(&vec![0, 1]));
+
+        // The previous value is left untouched for concurrent readers.
+        assert_eq!(old, &vec![0]);
+        assert_eq!(
+            map.pin().update_in_place(42, |v| v[0] = 2),
+            Some(&vec![2, 1])
+        );
+        assert_eq!(map.get(&42, &guard), Some(&vec![2, 1]));
+        assert_eq!(map.len(), 1);
+    });
+}
+
+#[test]
+fn with_hash() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        for i in 0..1000 {
+            let hash = map.hasher().hash_one(i);
+            assert_eq!(map.insert_with_hash(hash, i, i + 1, &guard), None);
+        }
+
+        for i in 0..1000 {
+            let hash = map.hasher().hash_one(i);
+            assert_eq!(map.get_with_hash(hash, &i, &guard), Some(&(i + 1)));
+            assert_eq!(map.get(&i, &guard), Some(&(i + 1)));
+            assert!(map.contains_key_with_hash(hash, &i, &guard));
+        }
+
+        let hash = map.hasher().hash_one(0_usize);
+        assert_eq!(map.insert_with_hash(hash, 0, 7, &guard), Some(&1));
+        assert_eq!(map.get_with_hash(hash, &0, &guard), Some(&7));
+
+        let hash = map.hasher().hash_one(1000_usize);
+        assert_eq!(map.get_with_hash(hash, &1000, &guard), None);
+        assert!(!map.contains_key_with_hash(hash, &1000, &guard));
+        assert_eq!(map.len(), 1000);
+    });
+}
+
+#[test]
+fn finish_hash() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        // A simple membership filter in front of the map.
+        let mut filter = [false; 64];
+        for i in 0..100 {
+            let hash = map.hasher().hash_one(i);
+            filter[(map.finish_hash(hash) & 63) as usize] = true;
+            map.insert_with_hash(hash, i, i + 1, &guard);
+        }
+
+        for i in 0..100 {
+            let hash = map.hasher().hash_one(i);
+            assert!(filter[(map.finish_hash(hash) & 63) as usize]);
+            assert!(map.contains_key_with_hash(hash, &i, &guard));
+        }
+    });
+}
+
+#[test]
+fn collision_report() {
+    #[derive(Default)]
+    struct ConstantHasher;
+
+    impl Hasher for ConstantHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, _: &[u8]) {}
+    }
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        assert_eq!(map.pin().collision_report().entries, 0);
+
+        for i in 0..100 {
+            map.pin().insert(i, i);
+        }
+
+        let report = map.pin().collision_report();
+        assert_eq!(report.entries, 100);
+        assert!(report.max_probe_len < 100);
+        assert!(report.displaced < 100);
+    });
+
+    let map = HashMap::<usize, usize, _>::builder()
+        .hasher(BuildHasherDefault::<ConstantHasher>::default())
+        .resize_mode(papaya::ResizeMode::Blocking)
+        .probe_strategy(papaya::ProbeStrategy::Linear)
+        .build();
+
+    // Note that the number of keys must stay within the probe limit of the table, as
+    // a table full of identical hashes cannot be resized.
+    for i in 0..16 {
+        map.pin().insert(i, i);
+    }
+
+    // Every key collides, so probe sequences grow with the number of entries.
+    let report = map.pin().collision_report();
+    assert_eq!(report.entries, 16);
+    assert_eq!(report.displaced, 15);
+    assert_eq!(report.max_probe_len, 15);
+    assert_eq!(report.full_groups, 2);
+}
+
+#[test]
+fn max_probe_len() {
+    for probe_strategy in [
+        papaya::ProbeStrategy::Quadratic,
+        papaya::ProbeStrategy::Linear,
+    ] {
+        for resize_mode in [
+            papaya::ResizeMode::Blocking,
+            papaya::ResizeMode::Incremental(16),
+        ] {
+            let map = HashMap::<usize, usize>::builder()
+                .resize_mode(resize_mode)
+                .probe_strategy(probe_strategy)
+                .max_probe_len(8)
+                .build();
+
+            for i in 0..10_000 {
+                map.pin().insert(i, i);
+            }
+
+            // Finish any in-progress resize to inspect a single table.
+            map.pin().finalize_resize();
+
+            #[cfg(feature = "testing")]
+            map.pin().check_invariants();
+
+            let report = map.pin().collision_report();
+            assert_eq!(report.entries, 10_000);
+            assert!(report.max_probe_len <= 8);
+
+            for i in 0..10_000 {
+                assert_eq!(map.pin().get(&i), Some(&i));
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct ConstantHasher;
+
+    impl Hasher for ConstantHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, _: &[u8]) {}
+    }
+
+    // Identical hashes cannot be separated by growing the table, so the cap is lifted
+    // once the table grows too sparse rather than growing without bound.
+    let map = HashMap::<usize, usize, _>::builder()
+        .hasher(BuildHasherDefault::<ConstantHasher>::default())
+        .max_probe_len(4)
+        .build();
+
+    for i in 0..40 {
+        map.pin().insert(i, i);
+    }
+
+    #[cfg(feature = "testing")]
+    map.pin().check_invariants();
+
+    assert!(map.pin().capacity() <= 1024);
+    for i in 0..40 {
+        assert_eq!(map.pin().get(&i), Some(&i));
+    }
+}
+
+#[test]
+fn rehash_with() {
     with_map::<usize, usize>(|map| {
-        let map = map();
-        let guard = map.guard();
-        map.insert(42, 0, &guard);
-        let old = map.remove(&42, &guard).unwrap();
-        assert_eq!(old, &0);
-        assert!(map.get(&42, &guard).is_none());
+        let mut map = map();
+
+        // Rehashing an unallocated table.
+        map.rehash_with(RandomState::new());
+        assert!(map.is_empty());
+
+        for i in 0..1000 {
+            map.pin().insert(i, i + 1);
+        }
+        for i in (0..1000).step_by(2) {
+            map.pin().remove(&i);
+        }
+
+        let value = map.pin().get(&1).unwrap() as *const usize;
+        map.rehash_with(RandomState::new());
+
+        // Entries are moved without being reallocated.
+        assert_eq!(map.pin().get(&1).unwrap() as *const usize, value);
+
+        #[cfg(feature = "testing")]
+        map.pin().check_invariants();
+
+        assert_eq!(map.len(), 500);
+        for i in 0..1000 {
+            let expected = (i % 2 == 1).then_some(i + 1);
+            assert_eq!(map.pin().get(&i).copied(), expected);
+        }
+
+        for i in 1000..2000 {
+            map.pin().insert(i, i + 1);
+        }
+        assert_eq!(map.len(), 1500);
     });
 }
 
 #[test]
-fn insert_and_get() {
-    with_map::<usize, usize>(|map| {
-        let map = map();
-        map.insert(42, 0, &map.guard());
+fn rehash_with_panic() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::panic::{self, AssertUnwindSafe};
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    enum TestState {
+        Default,
+        Constant,
+        Panic,
+    }
 
-        {
-            let guard = map.guard();
-            let e = map.get(&42, &guard).unwrap();
-            assert_eq!(e, &0);
+    // Hashes every key to zero if the inner hasher is missing.
+    struct TestHasher(Option<DefaultHasher>);
+
+    impl Hasher for TestHasher {
+        fn finish(&self) -> u64 {
+            self.0.as_ref().map_or(0, Hasher::finish)
         }
-    });
+
+        fn write(&mut self, bytes: &[u8]) {
+            if let Some(hasher) = &mut self.0 {
+                hasher.write(bytes);
+            }
+        }
+    }
+
+    impl BuildHasher for TestState {
+        type Hasher = TestHasher;
+
+        fn build_hasher(&self) -> TestHasher {
+            match self {
+                TestState::Default => TestHasher(Some(DefaultHasher::new())),
+                TestState::Constant => TestHasher(None),
+                TestState::Panic => panic!("hasher panicked"),
+            }
+        }
+    }
+
+    let mut map = HashMap::<usize, usize, _>::builder()
+        .hasher(TestState::Default)
+        .resize_mode(papaya::ResizeMode::Blocking)
+        .max_probe_len(4)
+        .build();
+
+    for i in 0..16 {
+        map.pin().insert(i, i);
+    }
+
+    // A panicking hasher leaves the map unchanged.
+    let resizes = map.resize_count();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| map.rehash_with(TestState::Panic)));
+    assert!(result.is_err());
+    assert_eq!(*map.hasher(), TestState::Default);
+    assert_eq!(map.resize_count(), resizes);
+    for i in 0..16 {
+        assert_eq!(map.pin().get(&i), Some(&i));
+    }
+
+    // Colliding keys do not fit within the probe limit, so the table grows.
+    map.rehash_with(TestState::Constant);
+    assert!(map.resize_count().0 > resizes.0);
+    for i in 0..16 {
+        assert_eq!(map.pin().get(&i), Some(&i));
+    }
+
+    #[cfg(feature = "testing")]
+    map.pin().check_invariants();
 }
 
 #[test]
-fn insert_and_get_key_value() {
+fn try_get() {
     with_map::<usize, usize>(|map| {
         let map = map();
-        map.insert(42, 0, &map.guard());
+        let guard = map.guard();
+        assert_eq!(map.try_get(&0, &guard), GetResult::Absent);
 
-        {
-            let guard = map.guard();
-            let e = map.get_key_value(&42, &guard).unwrap();
-            assert_eq!(e, (&42, &0));
+        for i in 0..1000 {
+            map.insert(i, i + 1, &guard);
+        }
+
+        // Keys may have been migrated to a new table.
+        for i in 0..1000 {
+            match map.try_get(&i, &guard) {
+                GetResult::Found(value) => assert_eq!(value, &(i + 1)),
+                GetResult::Migrating => assert_eq!(map.get(&i, &guard), Some(&(i + 1))),
+                GetResult::Absent => panic!("missing key {i}"),
+            }
+        }
+
+        map.finalize_resize(&guard);
+        for i in 0..1000 {
+            assert_eq!(map.try_get(&i, &guard), GetResult::Found(&(i + 1)));
         }
+        assert_eq!(map.try_get(&1000, &guard), GetResult::Absent);
     });
 }
 
 #[test]
-fn reinsert() {
-    with_map::<usize, usize>(|map| {
+fn get_pin() {
+    use std::marker::PhantomPinned;
+
+    struct Pinned {
+        value: usize,
+        _pinned: PhantomPinned,
+    }
+
+    with_map::<usize, Pinned>(|map| {
         let map = map();
         let guard = map.guard();
-        map.insert(42, 0, &guard);
-        let old = map.insert(42, 1, &guard);
-        assert_eq!(old, Some(&0));
 
-        {
-            let guard = map.guard();
-            let e = map.get(&42, &guard).unwrap();
-            assert_eq!(e, &1);
+        for i in 0..64 {
+            let value = Pinned {
+                value: i,
+                _pinned: PhantomPinned,
+            };
+            map.insert(i, value, &guard);
+        }
+
+        let first = map.get_pin(&0, &guard).unwrap();
+        let addr = &*first as *const Pinned;
+
+        // Values are not moved by resizes.
+        for i in 64..1024 {
+            let value = Pinned {
+                value: i,
+                _pinned: PhantomPinned,
+            };
+            map.insert(i, value, &guard);
         }
+
+        let first = map.get_pin(&0, &guard).unwrap();
+        assert_eq!(&*first as *const Pinned, addr);
+        assert_eq!(first.value, 0);
+        assert!(map.get_pin(&1024, &guard).is_none());
     });
 }
 
 #[test]
-fn update() {
-    with_map::<usize, usize>(|map| {
+fn update_non_clone() {
+    #[derive(Debug, PartialEq)]
+    struct NotClone(Vec<usize>);
+
+    with_map::<usize, NotClone>(|map| {
         let map = map();
         let guard = map.guard();
-        map.insert(42, 0, &guard);
-        assert_eq!(map.len(), 1);
-        let new = map.update(42, |v| v + 1, &guard);
-        assert_eq!(map.len(), 1);
-        assert_eq!(new, Some(&1));
+        map.insert(42, NotClone(vec![0; 64]), &guard);
 
-        {
-            let guard = map.guard();
-            let e = map.get(&42, &guard).unwrap();
-            assert_eq!(e, &1);
-        }
+        let old = map.get(&42, &guard).unwrap();
+        let new = map.update(42, |v| NotClone(vec![v.0.len(); 128]), &guard);
+        assert_eq!(new, Some(&NotClone(vec![64; 128])));
+
+        // The old value remains accessible through the existing guard.
+        assert_eq!(old, &NotClone(vec![0; 64]));
+        assert_eq!(map.len(), 1);
     });
 }
 
@@ -338,35 +2397,438 @@ fn get_or_insert_with() {
 }
 
 #[test]
-fn compute() {
+fn get_mut() {
+    with_map::<usize, usize>(|map| {
+        let mut map = map();
+        assert_eq!(map.get_mut(&0), None);
+
+        for i in 0..1000 {
+            map.pin().insert(i, i);
+        }
+
+        // Mutations are visible through guarded reads.
+        for i in 0..1000 {
+            *map.get_mut(&i).unwrap() += 1;
+        }
+        for i in 0..1000 {
+            assert_eq!(map.pin().get(&i), Some(&(i + 1)));
+        }
+
+        map.pin().remove(&0);
+        assert_eq!(map.get_mut(&0), None);
+        assert_eq!(map.get_mut(&1000), None);
+    });
+}
+
+#[test]
+fn get_mut_or_insert_with() {
+    with_map::<usize, usize>(|map| {
+        let mut map = map();
+
+        let value = map.get_mut_or_insert_with(42, || 0);
+        assert_eq!(*value, 0);
+        *value += 1;
+        assert_eq!(map.len(), 1);
+
+        let value = map.get_mut_or_insert_with(42, || panic!("value exists"));
+        assert_eq!(*value, 1);
+        *value += 1;
+        assert_eq!(map.pin().get(&42), Some(&2));
+
+        // Mutations are preserved across resizes.
+        for round in 1..=3 {
+            for i in 0..1000 {
+                *map.get_mut_or_insert_with(i, || 0) += 1;
+            }
+
+            for i in 0..1000 {
+                let expected = if i == 42 { round + 2 } else { round };
+                assert_eq!(map.pin().get(&i), Some(&expected));
+            }
+        }
+
+        assert_eq!(map.len(), 1000);
+    });
+}
+
+#[test]
+fn compute() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let map = map.pin();
+
+        for i in 0..100 {
+            let compute = |entry| match entry {
+                Some((_, value)) if value % 2 == 0 => Operation::Remove,
+                Some((_, value)) => Operation::Insert(value + 1),
+                None => Operation::Abort(()),
+            };
+
+            assert_eq!(map.len(), 0);
+            assert_eq!(map.compute(i, compute), Compute::Aborted(()));
+            assert_eq!(map.get(&i), None);
+
+            map.compute(i, |_| Operation::Insert::<_, ()>(1));
+            assert_eq!(map.len(), 1);
+
+            assert_eq!(
+                map.compute(i, compute),
+                Compute::Updated {
+                    old: (&i, &1),
+                    new: (&i, &2),
+                }
+            );
+            assert_eq!(map.compute(i, compute), Compute::Removed(&i, &2));
+            assert_eq!(map.len(), 0);
+        }
+    });
+}
+
+#[test]
+fn compute_verbose() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let map = map.pin();
+
+        let (result, retries) = map.compute_verbose(0, |_| Operation::Insert::<_, ()>(1));
+        assert_eq!(result, Compute::Inserted(&0, &1));
+        assert_eq!(retries, 0);
+
+        // Modify the entry while the operation is in progress to force a retry.
+        let mut interfered = false;
+        let (result, retries) = map.compute_verbose(0, |entry| {
+            if !interfered {
+                interfered = true;
+                map.insert(0, 10);
+            }
+
+            Operation::Insert::<_, ()>(entry.unwrap().1 + 1)
+        });
+
+        assert_eq!(
+            result,
+            Compute::Updated {
+                old: (&0, &10),
+                new: (&0, &11),
+            }
+        );
+        assert!(retries >= 1);
+    });
+}
+
+#[test]
+fn entry() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        let entry = map.entry(0, &guard);
+        assert_eq!(entry.key(), &0);
+        assert_eq!(entry.get(), None);
+        assert_eq!(entry.update(|v| v + 1), None);
+        assert_eq!(entry.insert(1), None);
+        assert_eq!(entry.get(), Some(&1));
+
+        for i in 1..100 {
+            assert_eq!(entry.update(|v| v + 1), Some(&(i + 1)));
+        }
+
+        // Replace the value at the remembered location.
+        assert_eq!(entry.insert(100), Some(&100));
+        assert_eq!(entry.get(), Some(&100));
+
+        // Trigger resizes between operations on the handle.
+        for i in 1..1000 {
+            map.insert(i, i, &guard);
+        }
+        assert_eq!(entry.get(), Some(&100));
+        assert_eq!(entry.update(|v| v + 1), Some(&101));
+
+        // The entry is removed concurrently between operations.
+        assert_eq!(map.remove(&0, &guard), Some(&101));
+        assert_eq!(entry.get(), None);
+        assert_eq!(entry.update(|v| v + 1), None);
+
+        let result = entry.compute(|entry| match entry {
+            Some(_) => Operation::Abort(()),
+            None => Operation::Insert(7),
+        });
+        assert_eq!(result, Compute::Inserted(&0, &7));
+
+        assert_eq!(entry.remove(), Some(&7));
+        assert_eq!(map.get(&0, &guard), None);
+        assert_eq!(map.len(), 999);
+    });
+}
+
+#[test]
+fn entry_or_insert() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        let entry = map.entry(0, &guard).or_insert(1);
+        assert_eq!(entry.key(), &0);
+        assert_eq!(entry.get(), Some(&1));
+
+        for i in 1..100 {
+            assert_eq!(entry.update(|v| v + 1), Some(&(i + 1)));
+        }
+
+        // The existing value is not replaced.
+        let entry = map.entry(0, &guard).or_insert_with(|| unreachable!());
+        assert_eq!(entry.get(), Some(&100));
+
+        // Trigger resizes between operations on the handle.
+        for i in 1..1000 {
+            map.insert(i, i, &guard);
+        }
+        assert_eq!(entry.get(), Some(&100));
+        assert_eq!(entry.update(|v| v + 1), Some(&101));
+        assert_eq!(entry.update(|v| v + 1), Some(&102));
+        assert_eq!(map.get(&0, &guard), Some(&102));
+
+        // Replace the value in place.
+        assert_eq!(entry.insert(7), Some(&102));
+        assert_eq!(entry.get(), Some(&7));
+        assert_eq!(entry.update(|v| v + 1), Some(&8));
+
+        // The entry is removed concurrently between operations.
+        assert_eq!(map.remove(&0, &guard), Some(&8));
+        assert_eq!(entry.get(), None);
+        assert_eq!(entry.update(|v| v + 1), None);
+        assert_eq!(map.len(), 999);
+
+        // Inserting through a stale handle adds the entry back.
+        assert_eq!(entry.insert(1), None);
+        assert_eq!(map.get(&0, &guard), Some(&1));
+        assert_eq!(map.len(), 1000);
+
+        assert_eq!(entry.remove(), Some(&1));
+        assert_eq!(map.get(&0, &guard), None);
+        assert_eq!(map.len(), 999);
+
+        // Removing through a stale handle finds nothing.
+        let entry = map.entry(1, &guard).or_insert(0);
+        assert_eq!(map.remove(&1, &guard), Some(&1));
+        assert_eq!(entry.remove(), None);
+        assert_eq!(map.len(), 998);
+    });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn entry_or_insert_concurrent() {
+    const THREADS: usize = 8;
+    const ITERATIONS: usize = 1000;
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+
+        std::thread::scope(|s| {
+            for _ in 0..THREADS {
+                s.spawn(|| {
+                    let guard = map.guard();
+                    for i in 0..ITERATIONS {
+                        let entry = map.entry(i % 16, &guard).or_insert(0);
+                        assert!(entry.update(|v| v + 1).is_some());
+                    }
+                });
+            }
+        });
+
+        let total: usize = map.pin().values().sum();
+        assert_eq!(total, THREADS * ITERATIONS);
+    });
+}
+
+#[test]
+fn panicking_update() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        map.pin().insert(0, 1);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            map.pin().update(0, |_| panic!("update failed"));
+        }));
+        assert!(result.is_err());
+
+        // The existing value is unchanged and the map is still usable.
+        assert_eq!(map.pin().get(&0), Some(&1));
+        assert_eq!(map.pin().update(0, |v| v + 1), Some(&2));
+        assert_eq!(map.pin().insert(1, 1), None);
+        assert_eq!(map.pin().remove(&0), Some(&2));
+        assert_eq!(map.len(), 1);
+    });
+}
+
+#[test]
+fn panicking_update_after_retry() {
+    let key = Arc::new(0);
+
+    with_map::<Arc<usize>, usize>(|map| {
+        let map = map();
+        map.pin().insert(key.clone(), 1);
+
+        // Modify the entry during the first call to force a retry, and panic on the retry
+        // after the new entry has been allocated.
+        let interfered = std::cell::Cell::new(false);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            map.pin().update(key.clone(), |value| {
+                if interfered.replace(true) {
+                    panic!("update failed");
+                }
+
+                map.pin().insert(key.clone(), 10);
+                value + 1
+            });
+        }));
+        assert!(result.is_err());
+
+        // The concurrent value is intact and the map is still usable.
+        assert_eq!(map.pin().get(&key), Some(&10));
+        assert_eq!(map.pin().update(key.clone(), |v| v + 1), Some(&11));
+
+        // The key of the entry that was never inserted was not leaked.
+        drop(map);
+        assert_eq!(Arc::strong_count(&key), 1);
+    });
+}
+
+#[test]
+fn concurrent_insert_returns_replaced() {
+    const THREADS: usize = 4;
+    const ITERS: usize = if cfg!(miri) { 16 } else { 256 };
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        map.pin().insert(0, usize::MAX);
+
+        // Every thread swaps in a unique set of values.
+        let mut replaced = std::thread::scope(|s| {
+            let handles = (0..THREADS)
+                .map(|t| {
+                    let map = &map;
+                    s.spawn(move || {
+                        let map = map.pin();
+                        (0..ITERS)
+                            .map(|i| *map.insert(0, t * ITERS + i).unwrap())
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        // Each value was replaced exactly once, except for the final value.
+        replaced.push(*map.pin().get(&0).unwrap());
+        replaced.sort_unstable();
+
+        let mut expected = (0..THREADS * ITERS).collect::<Vec<_>>();
+        expected.push(usize::MAX);
+        assert_eq!(replaced, expected);
+    });
+}
+
+#[test]
+fn concurrent_try_insert_returns_rejected() {
+    const THREADS: usize = 4;
+    const ITERS: usize = if cfg!(miri) { 16 } else { 256 };
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+
+        // Every thread races to insert a unique value for each key.
+        let results = std::thread::scope(|s| {
+            let handles = (0..THREADS)
+                .map(|t| {
+                    let map = &map;
+                    s.spawn(move || {
+                        let map = map.pin();
+                        (0..ITERS)
+                            .map(|i| match map.try_insert(i, t) {
+                                Ok(value) => {
+                                    assert_eq!(*value, t);
+                                    None
+                                }
+                                Err(err) => {
+                                    // The rejected value is returned by move.
+                                    assert_eq!(err.not_inserted, t);
+                                    assert_ne!(*err.current, t);
+                                    Some(*err.current)
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        let map = map.pin();
+        for i in 0..ITERS {
+            // Exactly one thread won the race for each key.
+            let winners = results.iter().filter(|r| r[i].is_none()).count();
+            assert_eq!(winners, 1);
+
+            // Every other thread observed the winning value.
+            let winner = *map.get(&i).unwrap();
+            assert!(results.iter().all(|r| r[i].map_or(true, |v| v == winner)));
+        }
+    });
+}
+
+#[test]
+fn reentrant_operations() {
     with_map::<usize, usize>(|map| {
         let map = map();
-        let map = map.pin();
-
-        for i in 0..100 {
-            let compute = |entry| match entry {
-                Some((_, value)) if value % 2 == 0 => Operation::Remove,
-                Some((_, value)) => Operation::Insert(value + 1),
-                None => Operation::Abort(()),
-            };
+        let guard = map.guard();
+        for i in 0..64 {
+            map.insert(i, i, &guard);
+        }
 
-            assert_eq!(map.len(), 0);
-            assert_eq!(map.compute(i, compute), Compute::Aborted(()));
-            assert_eq!(map.get(&i), None);
+        // Read and write other keys from within the closure, including inserts that
+        // may trigger a resize. Note that the closure may be retried if the entry is
+        // copied to a new table.
+        let value = map.update(
+            0,
+            |value| {
+                assert_eq!(map.pin().get(&1), Some(&1));
+                map.pin().insert(2, 42);
+                for i in 64..256 {
+                    map.pin().insert(i, i);
+                }
+                value + 1
+            },
+            &guard,
+        );
 
-            map.compute(i, |_| Operation::Insert::<_, ()>(1));
-            assert_eq!(map.len(), 1);
+        assert_eq!(value, Some(&1));
+        assert_eq!(map.get(&2, &guard), Some(&42));
+        assert_eq!(map.len(), 256);
 
-            assert_eq!(
-                map.compute(i, compute),
-                Compute::Updated {
-                    old: (&i, &1),
-                    new: (&i, &2),
+        let result = map.compute(
+            1,
+            |entry| match entry {
+                Some((_, value)) => {
+                    map.pin().remove(&3);
+                    Operation::Insert(*value + 1)
                 }
-            );
-            assert_eq!(map.compute(i, compute), Compute::Removed(&i, &2));
-            assert_eq!(map.len(), 0);
-        }
+                None => Operation::Abort(()),
+            },
+            &guard,
+        );
+
+        assert!(matches!(result, Compute::Updated { new: (_, 2), .. }));
+        assert_eq!(map.get(&3, &guard), None);
     });
 }
 
@@ -560,6 +3022,46 @@ fn current_kv_dropped() {
     });
 }
 
+#[test]
+fn drop_reclaims_everything() {
+    const ITERS: usize = if cfg!(miri) { 4 } else { 16 };
+    const ENTRIES: usize = if cfg!(miri) { 64 } else { 1024 };
+
+    let key = Arc::new(0);
+    let value = Arc::new(0);
+
+    with_map::<(usize, Arc<usize>), Arc<usize>>(|map| {
+        for _ in 0..ITERS {
+            let map = map();
+
+            {
+                let map = map.pin();
+
+                // Fill the map, triggering resizes that may still be in progress.
+                for i in 0..ENTRIES {
+                    map.insert((i, key.clone()), value.clone());
+                }
+
+                // Retire some entries through removals and replacements.
+                for i in (0..ENTRIES).step_by(2) {
+                    map.remove(&(i, key.clone()));
+                }
+
+                for i in (1..ENTRIES).step_by(2) {
+                    map.insert((i, key.clone()), value.clone());
+                }
+            }
+
+            drop(map);
+
+            // Dropping the map should immediately drop all keys and values,
+            // including entries that were retired.
+            assert_eq!(Arc::strong_count(&key), 1);
+            assert_eq!(Arc::strong_count(&value), 1);
+        }
+    });
+}
+
 #[test]
 fn empty_maps_equal() {
     with_map::<usize, usize>(|map1| {
@@ -614,151 +3116,681 @@ fn same_values_equal() {
 }
 
 #[test]
-fn different_values_not_equal() {
-    with_map::<usize, usize>(|map1| {
-        let map1 = map1();
-        with_map::<usize, usize>(|map2| {
-            let map2 = map2();
-            {
-                map1.pin().insert(1, 0);
-                map2.pin().insert(1, 1);
-            }
+fn different_values_not_equal() {
+    with_map::<usize, usize>(|map1| {
+        let map1 = map1();
+        with_map::<usize, usize>(|map2| {
+            let map2 = map2();
+            {
+                map1.pin().insert(1, 0);
+                map2.pin().insert(1, 1);
+            }
+
+            assert_ne!(map1, map2);
+            assert_ne!(map2, map1);
+        });
+    });
+}
+
+#[test]
+fn clone_map_empty() {
+    with_map::<&'static str, u32>(|map| {
+        let map = map();
+        let cloned_map = map.clone();
+        assert_eq!(map.len(), cloned_map.len());
+        assert_eq!(&map, &cloned_map);
+        assert_eq!(cloned_map.len(), 0);
+    });
+}
+
+#[test]
+// Test that same values exists in both maps (original and cloned)
+fn clone_map_filled() {
+    with_map::<&'static str, u32>(|map| {
+        let map = map();
+        map.insert("FooKey", 0, &map.guard());
+        map.insert("BarKey", 10, &map.guard());
+        let cloned_map = map.clone();
+        assert_eq!(map.len(), cloned_map.len());
+        assert_eq!(&map, &cloned_map);
+
+        // test that we are not mapping the same tables
+        map.insert("NewItem", 100, &map.guard());
+        assert_ne!(&map, &cloned_map);
+    });
+}
+
+#[test]
+fn default() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+        map.insert(42, 0, &guard);
+
+        assert_eq!(map.get(&42, &guard), Some(&0));
+    });
+}
+
+#[test]
+fn debug() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+        map.insert(42, 0, &guard);
+        map.insert(16, 8, &guard);
+
+        let formatted = format!("{:?}", map);
+
+        assert!(formatted == "{42: 0, 16: 8}" || formatted == "{16: 8, 42: 0}");
+    });
+}
+
+#[test]
+fn extend() {
+    if cfg!(papaya_stress) {
+        return;
+    }
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+
+        let mut entries: Vec<(usize, usize)> = vec![(42, 0), (16, 6), (38, 42)];
+        entries.sort_unstable();
+
+        (&map).extend(entries.clone());
+
+        let mut collected: Vec<(usize, usize)> = map
+            .iter(&guard)
+            .map(|(key, value)| (*key, *value))
+            .collect();
+        collected.sort_unstable();
+
+        assert_eq!(entries, collected);
+    });
+}
+
+#[test]
+fn extend_ref() {
+    if cfg!(papaya_stress) {
+        return;
+    }
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let mut entries: Vec<(&usize, &usize)> = vec![(&42, &0), (&16, &6), (&38, &42)];
+        entries.sort();
+
+        (&map).extend(entries.clone());
+
+        let guard = map.guard();
+        let mut collected: Vec<(&usize, &usize)> = map.iter(&guard).collect();
+        collected.sort();
+
+        assert_eq!(entries, collected);
+    });
+}
+
+#[test]
+fn from_iter_empty() {
+    use std::iter::FromIterator;
+
+    let entries: Vec<(usize, usize)> = Vec::new();
+    let map: HashMap<usize, usize> = HashMap::from_iter(entries);
+
+    assert_eq!(map.len(), 0)
+}
+
+#[test]
+fn from_iter_repeated() {
+    use std::iter::FromIterator;
+
+    let entries = vec![(0, 1), (0, 2), (0, 3)];
+    let map: HashMap<_, _> = HashMap::from_iter(entries);
+    let map = map.pin();
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.iter().collect::<Vec<_>>(), vec![(&0, &3)])
+}
+
+#[test]
+fn len() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let len = if cfg!(miri) { 100 } else { 10_000 };
+        for i in 0..len {
+            map.pin().insert(i, i + 1);
+        }
+        assert_eq!(map.len(), len);
+    });
+}
+
+#[test]
+fn range_scan() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+        assert!(map.range_scan(.., &guard).is_empty());
+
+        for i in (0..100).rev() {
+            map.insert(i, i + 1, &guard);
+        }
+
+        let entries = map.range_scan(10..20, &guard);
+        let expected = (10..20).map(|i| (i, i + 1)).collect::<Vec<_>>();
+        assert_eq!(
+            entries.iter().map(|(k, v)| (**k, **v)).collect::<Vec<_>>(),
+            expected
+        );
+
+        assert_eq!(map.range_scan(.., &guard).len(), 100);
+        assert_eq!(map.range_scan(95.., &guard).len(), 5);
+        assert!(map.range_scan(100.., &guard).is_empty());
+    });
+}
+
+#[test]
+fn sample() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let guard = map.guard();
+        assert!(map.sample(10, &guard).is_empty());
+
+        for i in 0..100 {
+            map.insert(i, i + 1, &guard);
+        }
+
+        let sample = map.sample(10, &guard);
+        assert_eq!(sample.len(), 10);
+        assert!(sample.iter().all(|(k, v)| **v == **k + 1));
+
+        // The sampled keys are distinct.
+        let mut keys = sample.iter().map(|(k, _)| **k).collect::<Vec<_>>();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), 10);
+
+        assert!(map.sample(0, &guard).is_empty());
+        assert_eq!(map.sample(100, &guard).len(), 100);
+        assert_eq!(map.sample(1000, &guard).len(), 100);
+    });
+}
+
+#[test]
+fn into_iter() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        assert_eq!(map.into_iter().count(), 0);
+    });
+
+    with_map::<usize, Box<usize>>(|map| {
+        let map = map();
+        for i in 0..1000 {
+            map.pin().insert(i, Box::new(i + 1));
+        }
+
+        // Replace and remove some entries, leaving retired entries behind.
+        for i in 0..100 {
+            map.pin().insert(i, Box::new(i + 1));
+            map.pin().remove(&(i + 500));
+        }
+
+        let mut entries = map.into_iter().map(|(k, v)| (k, *v)).collect::<Vec<_>>();
+        entries.sort_unstable();
+
+        let expected = (0..500)
+            .chain(600..1000)
+            .map(|i| (i, i + 1))
+            .collect::<Vec<_>>();
+        assert_eq!(entries, expected);
+    });
+}
+
+#[test]
+fn into_keys_and_values() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..1000 {
+            map.pin().insert(i, i + 1);
+        }
+
+        let mut keys = map.into_keys().collect::<Vec<_>>();
+        keys.sort_unstable();
+        assert_eq!(keys, (0..1000).collect::<Vec<_>>());
+    });
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..1000 {
+            map.pin().insert(i, i + 1);
+        }
+
+        let mut values = map.into_values().collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, (1..1001).collect::<Vec<_>>());
+    });
+}
+
+#[test]
+fn into_iter_drops_remaining() {
+    let key = Arc::new(0);
+    let value = Arc::new(0);
 
-            assert_ne!(map1, map2);
-            assert_ne!(map2, map1);
-        });
+    with_map::<(usize, Arc<usize>), Arc<usize>>(|new_map| {
+        let map = new_map();
+        for i in 0..100 {
+            map.pin().insert((i, key.clone()), value.clone());
+        }
+
+        // Consume some entries, dropping the rest with the iterator.
+        let mut iter = map.into_iter();
+        let taken = iter.by_ref().take(10).collect::<Vec<_>>();
+        assert_eq!(Arc::strong_count(&key), 101);
+        drop(taken);
+        assert_eq!(Arc::strong_count(&key), 91);
+        drop(iter);
+        assert_eq!(Arc::strong_count(&key), 1);
+        assert_eq!(Arc::strong_count(&value), 1);
+
+        // Keys are moved out while values are dropped in place.
+        let map = new_map();
+        for i in 0..100 {
+            map.pin().insert((i, key.clone()), value.clone());
+        }
+
+        let keys = map.into_keys().collect::<Vec<_>>();
+        assert_eq!(Arc::strong_count(&key), 101);
+        assert_eq!(Arc::strong_count(&value), 1);
+        drop(keys);
+        assert_eq!(Arc::strong_count(&key), 1);
     });
 }
 
 #[test]
-fn clone_map_empty() {
-    with_map::<&'static str, u32>(|map| {
+fn into_iter_size_hint() {
+    with_map::<usize, usize>(|map| {
         let map = map();
-        let cloned_map = map.clone();
-        assert_eq!(map.len(), cloned_map.len());
-        assert_eq!(&map, &cloned_map);
-        assert_eq!(cloned_map.len(), 0);
+        for i in 0..64 {
+            map.pin().insert(i, i + 1);
+        }
+        map.pin().remove(&0);
+
+        let mut iter = map.into_iter();
+        assert_eq!(iter.size_hint(), (63, Some(63)));
+        iter.next().unwrap();
+        assert_eq!(iter.size_hint(), (62, Some(62)));
+        assert_eq!(iter.collect::<Vec<_>>().len(), 62);
+    });
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..64 {
+            map.pin().insert(i, i + 1);
+        }
+        assert_eq!(map.clone().into_keys().size_hint(), (64, Some(64)));
+        assert_eq!(map.into_values().size_hint(), (64, Some(64)));
     });
 }
 
 #[test]
-// Test that same values exists in both maps (original and cloned)
-fn clone_map_filled() {
-    with_map::<&'static str, u32>(|map| {
+fn try_for_each() {
+    with_map::<usize, usize>(|map| {
         let map = map();
-        map.insert("FooKey", 0, &map.guard());
-        map.insert("BarKey", 10, &map.guard());
-        let cloned_map = map.clone();
-        assert_eq!(map.len(), cloned_map.len());
-        assert_eq!(&map, &cloned_map);
+        let guard = map.guard();
+        for i in 0..100 {
+            map.insert(i, i + 1, &guard);
+        }
 
-        // test that we are not mapping the same tables
-        map.insert("NewItem", 100, &map.guard());
-        assert_ne!(&map, &cloned_map);
+        // Visit every entry.
+        let mut visited = 0;
+        let result = map.try_for_each(
+            |key, value| {
+                assert_eq!(*value, key + 1);
+                visited += 1;
+                ControlFlow::<()>::Continue(())
+            },
+            &guard,
+        );
+        assert_eq!(result, ControlFlow::Continue(()));
+        assert_eq!(visited, 100);
+
+        // Stop after the first match.
+        let mut visited = 0;
+        let result = map.try_for_each(
+            |key, _| {
+                visited += 1;
+                if *key == 50 {
+                    return ControlFlow::Break(*key);
+                }
+
+                ControlFlow::Continue(())
+            },
+            &guard,
+        );
+        assert_eq!(result, ControlFlow::Break(50));
+        assert!(visited <= 100);
+        assert_eq!(
+            map.pin().try_for_each(|_, _| ControlFlow::Break(())),
+            ControlFlow::Break(())
+        );
     });
 }
 
 #[test]
-fn default() {
+fn pin_accessors() {
     with_map::<usize, usize>(|map| {
         let map = map();
-        let guard = map.guard();
-        map.insert(42, 0, &guard);
+        let pinned = map.pin();
+        for i in 0..1000 {
+            pinned.insert(i, i);
+        }
 
-        assert_eq!(map.get(&42, &guard), Some(&0));
+        assert_eq!(pinned.hasher().hash_one(1), map.hasher().hash_one(1));
+        assert_eq!(pinned.resize_count(), map.resize_count());
+
+        // References from the pinned iterators live as long as the pinned reference.
+        let keys = pinned.keys().collect::<Vec<_>>();
+        let values = pinned.values().collect::<Vec<_>>();
+        let entries = pinned.iter().collect::<Vec<_>>();
+        assert_eq!(keys.len(), 1000);
+        assert_eq!(values.len(), 1000);
+        assert_eq!(entries.len(), 1000);
+        assert!(entries.iter().all(|(k, v)| k == v));
     });
 }
 
 #[test]
-fn debug() {
+#[cfg(feature = "testing")]
+fn check_invariants() {
+    const LEN: usize = if cfg!(miri) { 64 } else { 2048 };
+
     with_map::<usize, usize>(|map| {
         let map = map();
-        let guard = map.guard();
-        map.insert(42, 0, &guard);
-        map.insert(16, 8, &guard);
+        map.pin().check_invariants();
+
+        std::thread::scope(|s| {
+            for t in 0..4 {
+                let map = &map;
+                s.spawn(move || {
+                    let map = map.pin();
+                    for i in (t..LEN).step_by(4) {
+                        map.insert(i, i);
+                        if i % 3 == 0 {
+                            map.remove(&i);
+                        }
+                    }
+                });
+            }
+        });
 
-        let formatted = format!("{:?}", map);
+        // Incremental resizes may still be in progress.
+        map.pin().check_invariants();
 
-        assert!(formatted == "{42: 0, 16: 8}" || formatted == "{16: 8, 42: 0}");
+        map.pin().finalize_resize();
+        map.pin().check_invariants();
+
+        map.pin().retain(|k, _| k % 2 == 0);
+        map.pin().check_invariants();
+
+        map.pin().clear();
+        map.pin().check_invariants();
     });
 }
 
 #[test]
-fn extend() {
-    if cfg!(papaya_stress) {
-        return;
+fn with_seed() {
+    // Maps are seeded independently by default.
+    let a: HashMap<usize, usize> = HashMap::new();
+    let b: HashMap<usize, usize> = HashMap::new();
+    assert_ne!(a.hasher().hash_one(1), b.hasher().hash_one(1));
+
+    let a: HashMap<usize, usize, SeededState> = HashMap::with_seed(7);
+    let b: HashMap<usize, usize, SeededState> = HashMap::with_seed(7);
+    let c: HashMap<usize, usize, SeededState> = HashMap::with_seed(8);
+    assert_eq!(a.hasher().seed(), 7);
+    assert_eq!(a.hasher().hash_one(1), b.hasher().hash_one(1));
+    assert_ne!(a.hasher().hash_one(1), c.hasher().hash_one(1));
+
+    for i in 0..1000 {
+        a.pin().insert(i, i);
+        b.pin().insert(i, i);
     }
 
+    // Identically seeded maps have the same layout.
+    assert!(a.pin().iter().eq(b.pin().iter()));
+    for i in 0..1000 {
+        assert_eq!(a.pin().get(&i), Some(&i));
+    }
+}
+
+#[test]
+fn readonly_view() {
     with_map::<usize, usize>(|map| {
         let map = map();
-        let guard = map.guard();
+        for i in 0..100 {
+            map.pin().insert(i, i + 1);
+        }
 
-        let mut entries: Vec<(usize, usize)> = vec![(42, 0), (16, 6), (38, 42)];
-        entries.sort_unstable();
+        let view = map.as_readonly();
+        let copy = view;
+        let guard = view.guard();
+
+        assert_eq!(view.len(), 100);
+        assert!(!copy.is_empty());
+        assert!(view.contains_key(&1, &guard));
+        assert!(!view.contains_key(&100, &guard));
+        assert_eq!(view.get(&1, &guard), Some(&2));
+        assert_eq!(view.get_key_value(&1, &guard), Some((&1, &2)));
+        assert_eq!(view.iter(&guard).count(), 100);
+        assert_eq!(view.keys(&guard).sum::<usize>(), (0..100).sum());
+        assert_eq!(view.values(&guard).sum::<usize>(), (1..=100).sum());
+
+        // Views observe writes made through the map.
+        map.pin().insert(100, 101);
+        assert_eq!(copy.get(&100, &copy.owned_guard()), Some(&101));
+        assert_eq!(view.len(), 101);
+    });
+}
 
-        (&map).extend(entries.clone().into_iter());
+#[test]
+fn dyn_map() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let other = HashMap::<usize, String>::new();
+        let registry: [&dyn DynMap<usize>; 2] = [&map, &other];
 
-        let mut collected: Vec<(usize, usize)> = map
-            .iter(&guard)
-            .map(|(key, value)| (*key, *value))
-            .collect();
-        collected.sort_unstable();
+        assert_eq!(registry[0].value_type_id(), TypeId::of::<usize>());
+        assert_eq!(registry[1].value_type_id(), TypeId::of::<String>());
 
-        assert_eq!(entries, collected);
+        assert_eq!(registry[0].insert(1, Box::new(1usize)).ok(), Some(false));
+        assert_eq!(registry[0].insert(1, Box::new(2usize)).ok(), Some(true));
+        assert_eq!(
+            registry[1].insert(1, Box::new("one".to_owned())).ok(),
+            Some(false)
+        );
+
+        // Values of the wrong type are returned.
+        let rejected = registry[0].insert(2, Box::new("two")).unwrap_err();
+        assert_eq!(rejected.downcast_ref::<&str>(), Some(&"two"));
+        assert!(!registry[0].contains_key(&2));
+
+        assert_eq!(registry[0].len(), 1);
+        assert_eq!(registry[0].get(&1, |v: &usize| *v), Some(2));
+        assert_eq!(registry[0].get(&1, |v: &String| v.clone()), None);
+        assert_eq!(
+            registry[1].get(&1, |v: &String| v.clone()),
+            Some("one".to_owned())
+        );
+        assert_eq!(map.pin().get(&1), Some(&2));
+
+        let mut found = None;
+        assert!(registry[0].get_with(&1, &mut |v| found = v.downcast_ref::<usize>().copied()));
+        assert_eq!(found, Some(2));
+        assert!(!registry[0].get_with(&2, &mut |_| panic!("key is absent")));
+
+        let concrete = registry[1].downcast_ref::<String, RandomState>().unwrap();
+        assert_eq!(concrete.pin().get(&1).unwrap(), "one");
+        assert!(registry[1].downcast_ref::<usize, RandomState>().is_none());
+
+        assert!(registry[0].remove(&1));
+        assert!(!registry[0].remove(&1));
+        assert!(registry[0].is_empty());
+
+        registry[1].clear();
+        assert!(other.is_empty());
     });
 }
 
 #[test]
-fn extend_ref() {
-    if cfg!(papaya_stress) {
-        return;
+fn concurrent_map() {
+    fn exercise<M: ConcurrentMap<usize, usize>>(map: &M) {
+        let guard = map.guard();
+        assert_eq!(map.get(&1, &guard), None);
+        assert_eq!(map.insert(1, 2, &guard), None);
+        assert_eq!(map.insert(1, 3, &guard), Some(&2));
+        assert_eq!(map.get(&1, &guard), Some(&3));
+        assert_eq!(map.remove(&1, &guard), Some(&3));
+        assert_eq!(map.remove(&1, &guard), None);
+        assert_eq!(map.insert(2, 4, &guard), None);
     }
 
     with_map::<usize, usize>(|map| {
         let map = map();
-        let mut entries: Vec<(&usize, &usize)> = vec![(&42, &0), (&16, &6), (&38, &42)];
-        entries.sort();
+        exercise(&map);
+        assert_eq!(map.pin().get(&2), Some(&4));
+        assert_eq!(map.len(), 1);
+    });
+}
 
-        (&map).extend(entries.clone().into_iter());
+#[test]
+fn multi_map() {
+    let map = MultiMap::new();
+    let guard = map.guard();
 
-        let guard = map.guard();
-        let mut collected: Vec<(&usize, &usize)> = map.iter(&guard).collect();
-        collected.sort();
+    assert_eq!(map.get_all(&1, &guard).next(), None);
+    assert!(!map.remove_value(&1, &1, &guard));
 
-        assert_eq!(entries, collected);
-    });
+    for i in 0..10 {
+        map.push(1, i, &guard);
+    }
+    map.push(2, 0, &guard);
+    map.push(1, 5, &guard);
+    assert_eq!(map.len(), 2);
+
+    // Values are yielded starting with the most recently pushed.
+    let values: Vec<usize> = map.get_all(&1, &guard).copied().collect();
+    assert_eq!(values, [5, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+
+    // Only the most recent occurrence is removed.
+    assert!(map.remove_value(&1, &5, &guard));
+    assert!(map.remove_value(&1, &3, &guard));
+    assert!(!map.remove_value(&1, &10, &guard));
+    let values: Vec<usize> = map.get_all(&1, &guard).copied().collect();
+    assert_eq!(values, [9, 8, 7, 6, 5, 4, 2, 1, 0]);
+
+    // Removing the last value removes the key.
+    assert!(map.remove_value(&2, &0, &guard));
+    assert_eq!(map.get_all(&2, &guard).next(), None);
+    assert_eq!(map.len(), 1);
+
+    assert!(map.remove(&1, &guard));
+    assert!(map.is_empty());
 }
 
 #[test]
-fn from_iter_empty() {
-    use std::iter::FromIterator;
+#[cfg_attr(miri, ignore)]
+fn multi_map_concurrent() {
+    const THREADS: usize = 8;
+    const ITERATIONS: usize = 1000;
+
+    let map = MultiMap::new();
+
+    std::thread::scope(|s| {
+        for t in 0..THREADS {
+            let map = &map;
+            s.spawn(move || {
+                let guard = map.guard();
+                for i in 0..ITERATIONS {
+                    map.push(i % 4, (t, i), &guard);
+                }
 
-    let entries: Vec<(usize, usize)> = Vec::new();
-    let map: HashMap<usize, usize> = HashMap::from_iter(entries.into_iter());
+                // Remove every other value pushed by this thread.
+                for i in (0..ITERATIONS).step_by(2) {
+                    assert!(map.remove_value(&(i % 4), &(t, i), &guard));
+                }
+            });
+        }
+    });
 
-    assert_eq!(map.len(), 0)
+    // No pushes or removals were lost.
+    let guard = map.guard();
+    let mut values: Vec<_> = (0..4)
+        .flat_map(|key| map.get_all(&key, &guard).copied())
+        .collect();
+    values.sort();
+
+    let mut expected: Vec<_> = (0..THREADS)
+        .flat_map(|t| (1..ITERATIONS).step_by(2).map(move |i| (t, i)))
+        .collect();
+    expected.sort();
+    assert_eq!(values, expected);
+
+    // Dropping long lists does not overflow the stack.
+    for i in 0..100_000 {
+        map.push(0, (0, i), &guard);
+    }
+    drop(guard);
+    drop(map);
 }
 
 #[test]
-fn from_iter_repeated() {
-    use std::iter::FromIterator;
+#[cfg(feature = "stats")]
+fn guard_count() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        assert_eq!(map.guard_count(), (0, 0));
 
-    let entries = vec![(0, 1), (0, 2), (0, 3)];
-    let map: HashMap<_, _> = HashMap::from_iter(entries.into_iter());
-    let map = map.pin();
-    assert_eq!(map.len(), 1);
-    assert_eq!(map.iter().collect::<Vec<_>>(), vec![(&0, &3)])
+        // A hoisted guard is only counted once.
+        let guard = map.guard();
+        for i in 0..100 {
+            map.insert(i, i, &guard);
+        }
+        assert_eq!(map.guard_count(), (1, 0));
+
+        // Pinning in a loop creates a guard per iteration.
+        for i in 0..100 {
+            assert_eq!(map.pin().get(&i), Some(&i));
+        }
+        assert_eq!(map.guard_count(), (101, 0));
+
+        let pinned = map.pin_owned();
+        let _guard = map.owned_guard();
+        assert_eq!(pinned.guard_count(), (101, 2));
+    });
 }
 
 #[test]
-fn len() {
+fn resize_count() {
+    if cfg!(papaya_stress) {
+        return;
+    }
+
     with_map::<usize, usize>(|map| {
         let map = map();
+        assert_eq!(map.resize_count(), (0, 0));
+
         let len = if cfg!(miri) { 100 } else { 10_000 };
         for i in 0..len {
             map.pin().insert(i, i + 1);
         }
-        assert_eq!(map.len(), len);
+
+        let (grows, shrinks) = map.resize_count();
+        assert!(grows > 0);
+        assert_eq!(shrinks, 0);
+
+        // Reserving within the current capacity does not resize.
+        map.pin().finalize_resize();
+        map.pin().reserve(1);
+        assert_eq!(map.resize_count(), (grows, 0));
     });
 }
 
@@ -782,6 +3814,103 @@ fn iter() {
     });
 }
 
+#[test]
+fn iter_size_hint() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        assert_eq!(map.pin().iter().size_hint(), (0, Some(0)));
+
+        for i in 0..64 {
+            map.pin().insert(i, i + 1);
+        }
+
+        let map = map.pin();
+        let mut iter = map.iter();
+        let (lower, upper) = iter.size_hint();
+        assert_eq!(lower, 64);
+        assert!(upper.unwrap() >= 64);
+
+        iter.next().unwrap();
+        assert_eq!(iter.size_hint().0, 63);
+        assert_eq!(map.keys().size_hint().0, 64);
+        assert_eq!(map.values().size_hint().0, 64);
+
+        for _ in iter.by_ref() {}
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+    });
+}
+
+#[test]
+fn iter_in_table_order() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..1000 {
+            map.pin().insert(i, i + 1);
+        }
+        for i in (0..1000).step_by(3) {
+            map.pin().remove(&i);
+        }
+
+        let map = map.pin();
+        let first: Vec<_> = map.iter_in_table_order().collect();
+        let second: Vec<_> = map.iter_in_table_order().collect();
+        assert_eq!(first, second);
+
+        let mut entries: Vec<_> = first.iter().map(|(&k, &v)| (k, v)).collect();
+        entries.sort();
+        let expected: Vec<_> = (0..1000)
+            .filter(|i| i % 3 != 0)
+            .map(|i| (i, i + 1))
+            .collect();
+        assert_eq!(entries, expected);
+    });
+}
+
+#[test]
+fn snapshot() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let map = map.pin();
+        assert_eq!(map.snapshot().len(), 0);
+
+        for i in 0..64 {
+            map.insert(i, i + 1);
+        }
+
+        let mut snapshot = map.snapshot();
+        assert_eq!(snapshot.len(), 64);
+
+        // Later modifications are not reflected in the snapshot.
+        map.insert(64, 65);
+        map.remove(&0);
+
+        snapshot.next().unwrap();
+        assert_eq!(snapshot.len(), 63);
+
+        let mut entries: Vec<_> = map.snapshot().map(|(&k, &v)| (k, v)).collect();
+        entries.sort();
+        assert_eq!(entries, (1..65).map(|i| (i, i + 1)).collect::<Vec<_>>());
+    });
+}
+
+#[test]
+fn to_vec() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..64 {
+            map.pin().insert(i, i + 1);
+        }
+
+        let mut entries = map.pin().to_vec();
+        entries.sort();
+        assert_eq!(entries, (0..64).map(|i| (i, i + 1)).collect::<Vec<_>>());
+
+        let mut keys = map.pin().keys_cloned();
+        keys.sort();
+        assert_eq!(keys, (0..64).collect::<Vec<_>>());
+    });
+}
+
 #[test]
 fn retain_empty() {
     with_map::<usize, usize>(|map| {
@@ -830,6 +3959,92 @@ fn retain_some() {
     });
 }
 
+#[test]
+fn retain_with() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..10 {
+            map.pin().insert(i, i);
+        }
+
+        let mut removed = Vec::new();
+        map.pin()
+            .retain_with(|_, v| *v >= 5, |k, v| removed.push((*k, *v)));
+        removed.sort();
+
+        assert_eq!(removed, [(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)]);
+        assert_eq!(map.len(), 5);
+    });
+}
+
+#[test]
+fn concurrent_retain_with() {
+    const LEN: usize = if cfg!(miri) { 64 } else { 2048 };
+
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        for i in 0..LEN {
+            map.pin().insert(i, i);
+        }
+
+        // Every removed entry is reported to exactly one thread.
+        let mut removed = std::thread::scope(|s| {
+            let handles = (0..4)
+                .map(|_| {
+                    let map = &map;
+                    s.spawn(move || {
+                        let mut removed = Vec::new();
+                        map.pin()
+                            .retain_with(|k, _| k % 2 == 0, |k, _| removed.push(*k));
+                        removed
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        removed.sort();
+
+        assert_eq!(removed, (0..LEN).filter(|k| k % 2 != 0).collect::<Vec<_>>());
+        assert_eq!(map.len(), LEN / 2);
+    });
+}
+
+#[test]
+fn pin_as_local() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let owned = map.pin_owned();
+        owned.insert(1, 2);
+
+        {
+            let local = owned.as_local();
+            assert_eq!(local.get(&1), Some(&2));
+            local.insert(3, 4);
+        }
+
+        assert_eq!(owned.get(&3), Some(&4));
+        assert_eq!(owned.len(), 2);
+    });
+}
+
+#[test]
+fn pin_into_owned() {
+    with_map::<usize, usize>(|map| {
+        let map = map();
+        let local = map.pin();
+        local.insert(1, 2);
+
+        let owned = local.into_owned();
+        assert_eq!(owned.get(&1), Some(&2));
+        assert_eq!(owned.remove(&1), Some(&2));
+        assert!(owned.is_empty());
+    });
+}
+
 #[test]
 fn mixed() {
     const LEN: usize = if cfg!(miri) { 48 } else { 1024 };
@@ -893,6 +4108,7 @@ fn mixed() {
 mod hasher {
     use super::*;
 
+    #[allow(clippy::extra_unused_type_parameters)]
     fn check<S: BuildHasher + Default>() {
         let range = if cfg!(miri) { 0..16 } else { 0..100 };
 
@@ -903,13 +4119,13 @@ mod hasher {
                 map.insert(i, i, &guard);
             }
 
-            assert!(!map.contains_key(&i32::min_value(), &guard));
+            assert!(!map.contains_key(&i32::MIN, &guard));
             assert!(!map.contains_key(&(range.start - 1), &guard));
             for i in range.clone() {
                 assert!(map.contains_key(&i, &guard));
             }
             assert!(!map.contains_key(&range.end, &guard));
-            assert!(!map.contains_key(&i32::max_value(), &guard));
+            assert!(!map.contains_key(&i32::MAX, &guard));
         });
     }
 
@@ -936,7 +4152,7 @@ mod hasher {
 
         impl Hasher for MaxHasher {
             fn finish(&self) -> u64 {
-                u64::max_value()
+                u64::MAX
             }
 
             fn write(&mut self, _: &[u8]) {}
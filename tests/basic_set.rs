@@ -30,13 +30,34 @@ fn clear() {
     });
 }
 
+#[test]
+fn finalize_resize() {
+    with_set::<usize>(|set| {
+        let set = set();
+        let guard = set.guard();
+
+        set.finalize_resize(&guard);
+        assert!(set.is_empty());
+
+        for i in 0..1000 {
+            set.insert(i, &guard);
+        }
+
+        set.finalize_resize(&guard);
+        assert_eq!(set.len(), 1000);
+        for i in 0..1000 {
+            assert!(set.contains(&i, &guard));
+        }
+    });
+}
+
 #[test]
 fn insert() {
     with_set::<usize>(|set| {
         let set = set();
         let guard = set.guard();
-        assert_eq!(set.insert(42, &guard), true);
-        assert_eq!(set.insert(42, &guard), false);
+        assert!(set.insert(42, &guard));
+        assert!(!set.insert(42, &guard));
         assert_eq!(set.len(), 1);
     });
 }
@@ -56,7 +77,7 @@ fn remove_empty() {
     with_set::<usize>(|set| {
         let set = set();
         let guard = set.guard();
-        assert_eq!(set.remove(&42, &guard), false);
+        assert!(!set.remove(&42, &guard));
     });
 }
 
@@ -375,9 +396,9 @@ fn extend() {
         let mut entries: Vec<usize> = vec![42, 16, 38];
         entries.sort_unstable();
 
-        (&set).extend(entries.clone().into_iter());
+        (&set).extend(entries.clone());
 
-        let mut collected: Vec<usize> = set.iter(&guard).map(|key| *key).collect();
+        let mut collected: Vec<usize> = set.iter(&guard).copied().collect();
         collected.sort_unstable();
 
         assert_eq!(entries, collected);
@@ -395,7 +416,7 @@ fn extend_ref() {
         let mut entries: Vec<&usize> = vec![&42, &36, &18];
         entries.sort();
 
-        (&set).extend(entries.clone().into_iter());
+        (&set).extend(entries.clone());
 
         let guard = set.guard();
         let mut collected: Vec<&usize> = set.iter(&guard).collect();
@@ -410,7 +431,7 @@ fn from_iter_empty() {
     use std::iter::FromIterator;
 
     let entries: Vec<usize> = Vec::new();
-    let set: HashSet<usize> = HashSet::from_iter(entries.into_iter());
+    let set: HashSet<usize> = HashSet::from_iter(entries);
 
     assert_eq!(set.len(), 0)
 }
@@ -420,7 +441,7 @@ fn from_iter_repeated() {
     use std::iter::FromIterator;
 
     let entries = vec![0, 0, 0];
-    let set: HashSet<_> = HashSet::from_iter(entries.into_iter());
+    let set: HashSet<_> = HashSet::from_iter(entries);
     let set = set.pin();
     assert_eq!(set.len(), 1);
     assert_eq!(set.iter().collect::<Vec<_>>(), vec![&0])
@@ -448,11 +469,11 @@ fn iter() {
         let set = set();
         let len = if cfg!(miri) { 100 } else { 10_000 };
         for i in 0..len {
-            assert_eq!(set.pin().insert(i), true);
+            assert!(set.pin().insert(i));
         }
 
         let v: Vec<_> = (0..len).collect();
-        let mut got: Vec<_> = set.pin().iter().map(|&k| k).collect();
+        let mut got: Vec<_> = set.pin().iter().copied().collect();
         got.sort();
         assert_eq!(v, got);
     });
@@ -506,6 +527,154 @@ fn retain_some() {
     });
 }
 
+#[test]
+fn pin_as_local() {
+    with_set::<usize>(|set| {
+        let set = set();
+        let owned = set.pin_owned();
+        owned.insert(1);
+
+        {
+            let local = owned.as_local();
+            assert!(local.contains(&1));
+            local.insert(2);
+        }
+
+        assert!(owned.contains(&2));
+        assert_eq!(owned.len(), 2);
+    });
+}
+
+#[test]
+fn pin_into_owned() {
+    with_set::<usize>(|set| {
+        let set = set();
+        let local = set.pin();
+        local.insert(1);
+
+        let owned = local.into_owned();
+        assert!(owned.contains(&1));
+        assert!(owned.remove(&1));
+        assert!(owned.is_empty());
+    });
+}
+
+#[test]
+fn set_operations() {
+    fn sorted<'a>(iter: impl Iterator<Item = &'a usize>) -> Vec<usize> {
+        let mut values: Vec<_> = iter.copied().collect();
+        values.sort();
+        values
+    }
+
+    with_set::<usize>(|a| {
+        with_set::<usize>(|b| {
+            let (a, b) = (a(), b());
+            for i in 0..64 {
+                a.pin().insert(i);
+            }
+            for i in 32..128 {
+                b.pin().insert(i);
+            }
+
+            // Mix guard types between the two sets.
+            let (a, b) = (a.pin(), b.pin_owned());
+
+            assert_eq!(sorted(a.union(&b)), (0..128).collect::<Vec<_>>());
+            assert_eq!(sorted(b.union(&a)), (0..128).collect::<Vec<_>>());
+            assert_eq!(sorted(a.intersection(&b)), (32..64).collect::<Vec<_>>());
+            assert_eq!(sorted(b.intersection(&a)), (32..64).collect::<Vec<_>>());
+            assert_eq!(sorted(a.difference(&b)), (0..32).collect::<Vec<_>>());
+            assert_eq!(sorted(b.difference(&a)), (64..128).collect::<Vec<_>>());
+
+            let expected: Vec<_> = (0..32).chain(64..128).collect();
+            assert_eq!(sorted(a.symmetric_difference(&b)), expected);
+            assert_eq!(sorted(b.symmetric_difference(&a)), expected);
+
+            let intersection = a.intersection(&b);
+            assert_eq!(intersection.clone().count(), 32);
+            assert_eq!(intersection.count(), 32);
+        });
+    });
+}
+
+#[test]
+fn size_hint() {
+    with_set::<usize>(|a| {
+        with_set::<usize>(|b| {
+            let (a, b) = (a(), b());
+            for i in 0..64 {
+                a.pin().insert(i);
+            }
+            for i in 32..40 {
+                b.pin().insert(i);
+            }
+
+            let (a, b) = (a.pin(), b.pin());
+            assert_eq!(a.iter().size_hint().0, 64);
+            assert_eq!(a.difference(&b).size_hint().0, 56);
+            assert_eq!(a.union(&b).size_hint().0, 64);
+            assert_eq!(a.intersection(&b).size_hint().0, 0);
+
+            for (lower, upper, count) in [
+                hint(a.union(&b)),
+                hint(a.intersection(&b)),
+                hint(a.difference(&b)),
+                hint(a.symmetric_difference(&b)),
+            ] {
+                assert!(lower <= count);
+                assert!(upper.unwrap() >= count);
+            }
+        });
+    });
+
+    fn hint<'a>(iter: impl Iterator<Item = &'a usize>) -> (usize, Option<usize>, usize) {
+        let (lower, upper) = iter.size_hint();
+        (lower, upper, iter.count())
+    }
+}
+
+#[test]
+fn subset_superset_disjoint() {
+    with_set::<usize>(|a| {
+        with_set::<usize>(|b| {
+            let (a, b) = (a(), b());
+            assert!(a.pin().is_subset(&b.pin()));
+            assert!(a.pin().is_superset(&b.pin()));
+            assert!(a.pin().is_disjoint(&b.pin()));
+
+            for i in 0..64 {
+                a.pin().insert(i);
+            }
+
+            assert!(!a.pin().is_subset(&b.pin()));
+            assert!(a.pin().is_superset(&b.pin()));
+            assert!(b.pin().is_subset(&a.pin()));
+            assert!(a.pin().is_disjoint(&b.pin()));
+
+            for i in 64..128 {
+                b.pin().insert(i);
+            }
+
+            assert!(a.pin().is_disjoint(&b.pin()));
+            assert!(b.pin().is_disjoint(&a.pin()));
+            assert!(!a.pin().is_superset(&b.pin()));
+
+            b.pin().insert(0);
+            assert!(!a.pin().is_disjoint(&b.pin()));
+            assert!(!b.pin().is_disjoint(&a.pin()));
+
+            for i in 0..64 {
+                b.pin().insert(i);
+            }
+
+            assert!(a.pin().is_subset(&b.pin()));
+            assert!(b.pin().is_superset(&a.pin()));
+            assert!(!b.pin().is_subset(&a.pin()));
+        });
+    });
+}
+
 #[test]
 fn mixed() {
     const LEN: usize = if cfg!(miri) { 48 } else { 1024 };
@@ -521,16 +690,16 @@ fn mixed() {
 
         assert!(set.pin().get(&300).is_none());
 
-        assert_eq!(set.pin().remove(&100), true);
-        assert_eq!(set.pin().remove(&200), true);
-        assert_eq!(set.pin().remove(&300), false);
+        assert!(set.pin().remove(&100));
+        assert!(set.pin().remove(&200));
+        assert!(!set.pin().remove(&300));
 
         assert!(set.pin().get(&100).is_none());
         assert!(set.pin().get(&200).is_none());
         assert!(set.pin().get(&300).is_none());
 
         for i in 0..LEN {
-            assert_eq!(set.pin().insert(i), true);
+            assert!(set.pin().insert(i));
         }
 
         for i in 0..LEN {
@@ -538,7 +707,7 @@ fn mixed() {
         }
 
         for i in 0..LEN {
-            assert_eq!(set.pin().remove(&i), true);
+            assert!(set.pin().remove(&i));
         }
 
         for i in 0..LEN {
@@ -546,7 +715,7 @@ fn mixed() {
         }
 
         for i in 0..(LEN * 2) {
-            assert_eq!(set.pin().insert(i), true);
+            assert!(set.pin().insert(i));
         }
 
         for i in 0..(LEN * 2) {
@@ -559,6 +728,7 @@ fn mixed() {
 mod hasher {
     use super::*;
 
+    #[allow(clippy::extra_unused_type_parameters)]
     fn check<S: BuildHasher + Default>() {
         let range = if cfg!(miri) { 0..16 } else { 0..100 };
 
@@ -569,13 +739,13 @@ mod hasher {
                 set.insert(i, &guard);
             }
 
-            assert!(!set.contains(&i32::min_value(), &guard));
+            assert!(!set.contains(&i32::MIN, &guard));
             assert!(!set.contains(&(range.start - 1), &guard));
             for i in range.clone() {
                 assert!(set.contains(&i, &guard));
             }
             assert!(!set.contains(&range.end, &guard));
-            assert!(!set.contains(&i32::max_value(), &guard));
+            assert!(!set.contains(&i32::MAX, &guard));
         });
     }
 
@@ -602,7 +772,7 @@ mod hasher {
 
         impl Hasher for MaxHasher {
             fn finish(&self) -> u64 {
-                u64::max_value()
+                u64::MAX
             }
 
             fn write(&mut self, _: &[u8]) {}
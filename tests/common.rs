@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-use papaya::{HashMap, HashSet, ResizeMode};
+use papaya::{HashMap, HashSet, ProbeStrategy, ResizeMode};
 use seize::Collector;
 
 // Run the test on different configurations of a `HashMap`.
@@ -39,6 +39,28 @@ pub fn with_map<K, V>(mut test: impl FnMut(&dyn Fn() -> HashMap<K, V>)) {
                 .build()
         }),
     );
+
+    // Incremental resize mode with entry allocations recycled through a pool.
+    test(
+        &(|| {
+            HashMap::builder()
+                .collector(collector.clone())
+                .resize_mode(ResizeMode::Incremental(128))
+                .entry_pool(true)
+                .build()
+        }),
+    );
+
+    // Incremental resize mode with linear probing.
+    test(
+        &(|| {
+            HashMap::builder()
+                .collector(collector.clone())
+                .resize_mode(ResizeMode::Incremental(128))
+                .probe_strategy(ProbeStrategy::Linear)
+                .build()
+        }),
+    );
 }
 
 // Run the test on different configurations of a `HashSet`.
@@ -0,0 +1,60 @@
+use papaya::HashMap;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// A global allocator that counts the number of allocations.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+const ITERS: usize = if cfg!(miri) { 64 } else { 4096 };
+
+// Count the allocations made while repeatedly inserting and removing entries from a map.
+fn churn(entry_pool: bool) -> usize {
+    let map = HashMap::builder()
+        .capacity(64)
+        .entry_pool(entry_pool)
+        .build();
+
+    for i in 0..32 {
+        map.pin().insert(i, i);
+    }
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for i in 0..ITERS {
+        let key = i % 32;
+        assert_eq!(map.pin().remove(&key), Some(&key));
+        assert_eq!(map.pin().insert(key, key), None);
+    }
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+
+    assert_eq!(map.len(), 32);
+    after - before
+}
+
+#[test]
+fn entry_pool_reuses_allocations() {
+    let unpooled = churn(false);
+    let pooled = churn(true);
+
+    // Most entry allocations should have been reused.
+    assert!(
+        unpooled.saturating_sub(pooled) >= ITERS / 2,
+        "pooled: {pooled}, unpooled: {unpooled}"
+    );
+}